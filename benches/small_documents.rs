@@ -0,0 +1,66 @@
+//! Serialization latency for a typical webhook response, on the small-document fast path
+//! and on the `xml_builder` path used for larger documents
+//!
+//! Run with `cargo bench --bench small_documents`. Each case is timed one call at a time
+//! and reported as percentiles, since webhook latency budgets are set at p99.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use twiml::*;
+
+const WARMUP: usize = 1_000;
+const SAMPLES: usize = 100_000;
+
+/// Say + Gather menu, the shape of most webhook responses
+fn say_gather() -> Response {
+    Response::new()
+        .say(Say::new("Thanks for calling Example Corp.").voice("Polly.Joanna"))
+        .gather(
+            Gather::new()
+                .action("/menu")
+                .num_digits(1)
+                .timeout(5)
+                .say("Press 1 for sales or 2 for support."),
+        )
+        .redirect("/menu")
+}
+
+/// Menu with more than `FAST_PATH_MAX_ELEMENTS` elements, rendered by `xml_builder`
+fn large_menu() -> Response {
+    let options = (1..=FAST_PATH_MAX_ELEMENTS).fold(Gather::new().action("/menu").num_digits(1), |gather, option| {
+        gather.say(format!("Press {} for option {}.", option % 10, option))
+    });
+    Response::new().gather(options).redirect("/menu")
+}
+
+fn percentile(sorted: &[Duration], percent: usize) -> Duration {
+    sorted[(sorted.len() * percent / 100).min(sorted.len() - 1)]
+}
+
+fn bench(name: &str, response: &Response) {
+    for _ in 0..WARMUP {
+        black_box(response.to_xml_string());
+    }
+    let mut samples: Vec<Duration> = (0..SAMPLES)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(black_box(response).to_xml_string());
+            start.elapsed()
+        })
+        .collect();
+    samples.sort_unstable();
+    println!(
+        "{:<12} {:>6} bytes  p50 {:>9.2?}  p99 {:>9.2?}  max {:>9.2?}",
+        name,
+        response.to_xml_string().len(),
+        percentile(&samples, 50),
+        percentile(&samples, 99),
+        samples[samples.len() - 1],
+    );
+}
+
+fn main() {
+    bench("say_gather", &say_gather());
+    bench("large_menu", &large_menu());
+}
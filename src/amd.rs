@@ -0,0 +1,140 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::twiml::{Dial, Pause, Play, Response};
+use crate::validation::ValidationError;
+
+/// Result of answering machine detection, sent as the `AnsweredBy` webhook parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnsweredBy {
+    /// A person answered (`human`)
+    Human,
+    /// A machine answered and its greeting is still playing (`machine_start`)
+    MachineStart,
+    /// A machine greeting ended with a beep (`machine_end_beep`)
+    MachineEndBeep,
+    /// A machine greeting ended with silence (`machine_end_silence`)
+    MachineEndSilence,
+    /// A machine greeting ended some other way (`machine_end_other`)
+    MachineEndOther,
+    /// A fax machine answered (`fax`)
+    Fax,
+    /// Detection could not decide (`unknown`)
+    Unknown,
+}
+
+impl AnsweredBy {
+    /// Get the parameter value Twilio sends
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnsweredBy::Human => "human",
+            AnsweredBy::MachineStart => "machine_start",
+            AnsweredBy::MachineEndBeep => "machine_end_beep",
+            AnsweredBy::MachineEndSilence => "machine_end_silence",
+            AnsweredBy::MachineEndOther => "machine_end_other",
+            AnsweredBy::Fax => "fax",
+            AnsweredBy::Unknown => "unknown",
+        }
+    }
+
+    /// Whether an answering machine picked up
+    pub fn is_machine(&self) -> bool {
+        matches!(
+            self,
+            AnsweredBy::MachineStart
+                | AnsweredBy::MachineEndBeep
+                | AnsweredBy::MachineEndSilence
+                | AnsweredBy::MachineEndOther
+        )
+    }
+
+    /// Read the `AnsweredBy` field from webhook form parameters
+    pub fn from_params<K: AsRef<str>, V: AsRef<str>>(params: &[(K, V)]) -> Option<Self> {
+        params
+            .iter()
+            .find(|(key, _)| key.as_ref() == "AnsweredBy")
+            .and_then(|(_, value)| value.as_ref().parse().ok())
+    }
+}
+
+impl fmt::Display for AnsweredBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AnsweredBy {
+    type Err = ValidationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "human" => Ok(AnsweredBy::Human),
+            "machine_start" => Ok(AnsweredBy::MachineStart),
+            "machine_end_beep" => Ok(AnsweredBy::MachineEndBeep),
+            "machine_end_silence" => Ok(AnsweredBy::MachineEndSilence),
+            "machine_end_other" => Ok(AnsweredBy::MachineEndOther),
+            "fax" => Ok(AnsweredBy::Fax),
+            "unknown" => Ok(AnsweredBy::Unknown),
+            other => Err(ValidationError {
+                element: "Response".to_string(),
+                attribute: Some("AnsweredBy".to_string()),
+                child: None,
+                reason: format!("`{}` is not an answering machine detection result", other),
+            }),
+        }
+    }
+}
+
+/// Seconds to wait for the beep when detection reports `machine_start`
+const GREETING_PAUSE_SECONDS: usize = 5;
+
+/// Build the follow-up document for an outbound call once detection has finished
+///
+/// People, and calls detection could not classify, are connected to `agent`. Machines
+/// hear `message` and the call ends; use `MachineDetection=DetectMessageEnd` so the
+/// result arrives after the beep, since for `machine_start` the greeting is still playing
+/// and the message is only delayed by a short pause. Fax machines are hung up on.
+pub fn amd_response(answered_by: AnsweredBy, agent: Dial, message: impl Into<Play>) -> Response {
+    match answered_by {
+        AnsweredBy::Human | AnsweredBy::Unknown => Response::new().dial(agent),
+        AnsweredBy::Fax => Response::new().hangup(),
+        AnsweredBy::MachineStart => Response::new()
+            .pause(Pause::new().length(GREETING_PAUSE_SECONDS))
+            .play(message)
+            .hangup(),
+        AnsweredBy::MachineEndBeep | AnsweredBy::MachineEndSilence | AnsweredBy::MachineEndOther => {
+            Response::new().play(message).hangup()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::twiml::*;
+
+    fn respond(answered_by: AnsweredBy) -> String {
+        let agent = Dial::new_empty().client(Client::new("agent"));
+        amd_response(answered_by, agent, "https://example.com/voicemail.mp3").to_xml_string()
+    }
+
+    #[test]
+    fn test_amd_response() {
+        assert!(respond(AnsweredBy::Human).ends_with("<Response><Dial><Client>agent</Client></Dial></Response>"));
+        assert!(respond(AnsweredBy::Unknown).contains("<Dial>"));
+        assert!(respond(AnsweredBy::Fax).ends_with("<Response><Hangup /></Response>"));
+        assert!(respond(AnsweredBy::MachineEndBeep).ends_with(
+            "<Response><Play>https://example.com/voicemail.mp3</Play><Hangup /></Response>"
+        ));
+        assert!(respond(AnsweredBy::MachineStart).contains(r#"<Pause length="5" /><Play>"#));
+    }
+
+    #[test]
+    fn test_answered_by_from_params() {
+        let params = [("CallSid", "CA1"), ("AnsweredBy", "machine_end_silence")];
+        let answered_by = AnsweredBy::from_params(&params).unwrap();
+        assert!(answered_by.is_machine());
+        assert_eq!(answered_by.to_string(), "machine_end_silence");
+        assert!(AnsweredBy::from_params(&[("AnsweredBy", "robot")]).is_none());
+    }
+}
@@ -0,0 +1,273 @@
+//! `Arbitrary` implementations for generating bounded TwiML documents
+//!
+//! Generated trees follow the nesting TwiML allows (verbs in Response, nouns in Dial,
+//! Say/Play/Pause in Gather), so depth is bounded by construction. Sibling counts are
+//! capped by [`MAX_CHILDREN`] to keep documents small.
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::twiml::*;
+
+/// Maximum number of children generated for a single element
+pub const MAX_CHILDREN: usize = 8;
+
+/// Apply `set` to `element` with an arbitrary value, or leave it unset
+fn maybe<'a, E, T: Arbitrary<'a>>(u: &mut Unstructured<'a>, element: E, set: impl FnOnce(E, T) -> E) -> Result<E> {
+    Ok(match u.arbitrary::<Option<T>>()? {
+        Some(value) => set(element, value),
+        None => element,
+    })
+}
+
+/// Add up to [`MAX_CHILDREN`] children built by `child`
+fn children<'a, E>(
+    u: &mut Unstructured<'a>,
+    mut element: E,
+    mut child: impl FnMut(&mut Unstructured<'a>, E) -> Result<E>,
+) -> Result<E> {
+    let count = u.int_in_range(0..=MAX_CHILDREN)?;
+    for _ in 0..count {
+        element = child(u, element)?;
+    }
+    Ok(element)
+}
+
+impl<'a> Arbitrary<'a> for EventSet {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let events = [
+            EventSet::INITIATED,
+            EventSet::RINGING,
+            EventSet::ANSWERED,
+            EventSet::COMPLETED,
+            EventSet::START,
+            EventSet::END,
+            EventSet::JOIN,
+            EventSet::LEAVE,
+            EventSet::MUTE,
+            EventSet::HOLD,
+            EventSet::MODIFY,
+            EventSet::SPEAKER,
+            EventSet::ANNOUNCEMENT,
+            EventSet::IN_PROGRESS,
+            EventSet::ABSENT,
+        ];
+        let mut set = EventSet::new();
+        for event in events {
+            if u.arbitrary()? {
+                set = set | event;
+            }
+        }
+        Ok(set)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Say {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let say = Say::new(u.arbitrary::<String>()?);
+        let say = maybe(u, say, |say, value: String| say.voice(value))?;
+        let say = maybe(u, say, |say, value: String| say.language(value))?;
+        maybe(u, say, |say, value: Loop| say.loop_times(value))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Play {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let play = Play::new(u.arbitrary::<String>()?);
+        let play = maybe(u, play, |play, value: Loop| play.loop_times(value))?;
+        maybe(u, play, |play, value: String| play.digits(value))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Pause {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        maybe(u, Pause::new(), |pause, seconds: u32| pause.length(Seconds::new(seconds)))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Hangup {
+    fn arbitrary(_u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Hangup::new())
+    }
+}
+
+impl<'a> Arbitrary<'a> for Leave {
+    fn arbitrary(_u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Leave::new())
+    }
+}
+
+impl<'a> Arbitrary<'a> for Redirect {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let redirect = Redirect::new(u.arbitrary::<String>()?);
+        maybe(u, redirect, |redirect, value: String| redirect.method(value))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Gather {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let gather = maybe(u, Gather::new(), |gather, value: String| gather.action(value))?;
+        let gather = maybe(u, gather, |gather, value: String| gather.method(value))?;
+        let gather = maybe(u, gather, Gather::input)?;
+        let gather = maybe(u, gather, Gather::num_digits)?;
+        let gather = maybe(u, gather, |gather, seconds: u32| gather.timeout(Seconds::new(seconds)))?;
+        let gather = maybe(u, gather, |gather, value: String| gather.finish_on_key(value))?;
+        children(u, gather, |u, gather| {
+            Ok(match u.choose_index(3)? {
+                0 => gather.say(u.arbitrary::<Say>()?),
+                1 => gather.play(u.arbitrary::<Play>()?),
+                _ => gather.pause(u.arbitrary()?),
+            })
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Record {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let record = maybe(u, Record::new(), |record, value: String| record.action(value))?;
+        let record = maybe(u, record, |record, value: String| record.method(value))?;
+        let record = maybe(u, record, |record, seconds: u32| record.timeout(Seconds::new(seconds)))?;
+        let record = maybe(u, record, |record, value: String| record.finish_on_key(value))?;
+        let record = maybe(u, record, |record, seconds: u32| record.max_length(Seconds::new(seconds)))?;
+        let record = maybe(u, record, Record::play_beep)?;
+        maybe(u, record, Record::transcribe)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Number {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let number = Number::new(u.arbitrary::<String>()?);
+        let number = maybe(u, number, |number, value: String| number.send_digits(value))?;
+        let number = maybe(u, number, |number, value: String| number.url(value))?;
+        maybe(u, number, Number::status_callback_event)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Client {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let client = Client::new(u.arbitrary::<String>()?);
+        let client = maybe(u, client, |client, value: String| client.url(value))?;
+        maybe(u, client, Client::status_callback_event)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Conference {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let conference = Conference::new(u.arbitrary::<String>()?);
+        let conference = maybe(u, conference, Conference::muted)?;
+        let conference = maybe(u, conference, Conference::start_conference_on_enter)?;
+        let conference = maybe(u, conference, Conference::end_conference_on_exit)?;
+        let conference = maybe(u, conference, |conference, value: Beep| conference.beep(value))?;
+        maybe(u, conference, Conference::status_callback_event)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Sip {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let sip = Sip::new(u.arbitrary::<String>()?);
+        let sip = maybe(u, sip, |sip, value: String| sip.username(value))?;
+        let sip = maybe(u, sip, |sip, value: String| sip.password(value))?;
+        maybe(u, sip, Sip::status_callback_event)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Dial {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let number: Option<String> = u.arbitrary()?;
+        let has_number = number.is_some();
+        let dial = match number {
+            Some(number) => Dial::new_with_number(number),
+            None => Dial::new_empty(),
+        };
+        let dial = maybe(u, dial, |dial, value: String| dial.action(value))?;
+        let dial = maybe(u, dial, |dial, seconds: u32| dial.timeout(Seconds::new(seconds)))?;
+        let dial = maybe(u, dial, |dial, value: String| dial.caller_id(value))?;
+        if has_number {
+            // An element can't have both text and children
+            return Ok(dial);
+        }
+        children(u, dial, |u, dial| {
+            Ok(match u.choose_index(4)? {
+                0 => dial.number(u.arbitrary()?),
+                1 => dial.client(u.arbitrary()?),
+                2 => dial.conference(u.arbitrary()?),
+                _ => dial.sip(u.arbitrary()?),
+            })
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Sms {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let sms = Sms::new(u.arbitrary::<String>()?);
+        let sms = maybe(u, sms, |sms, value: String| sms.to(value))?;
+        maybe(u, sms, |sms, value: String| sms.from(value))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Body {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Body::new(u.arbitrary::<String>()?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Media {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Media::new(u.arbitrary::<String>()?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Message {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let message = maybe(u, Message::new_empty(), |message, value: String| message.to(value))?;
+        let message = maybe(u, message, |message, value: String| message.from(value))?;
+        let message = maybe(u, message, |message, value: String| message.action(value))?;
+        let message = maybe(u, message, |message, value: Body| message.body(value))?;
+        children(u, message, |u, message| Ok(message.media(u.arbitrary()?)))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Enqueue {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let enqueue = Enqueue::new(u.arbitrary::<String>()?);
+        let enqueue = maybe(u, enqueue, |enqueue, value: String| enqueue.action(value))?;
+        maybe(u, enqueue, |enqueue, value: String| enqueue.wait_url(value))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Response {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        children(u, Response::new(), |u, response| {
+            Ok(match u.choose_index(13)? {
+                0 => response.say(u.arbitrary::<Say>()?),
+                1 => response.play(u.arbitrary::<Play>()?),
+                2 => response.pause(u.arbitrary()?),
+                3 => response.gather(u.arbitrary()?),
+                4 => response.redirect(u.arbitrary::<Redirect>()?),
+                5 => response.record(u.arbitrary()?),
+                6 => response.dial(u.arbitrary()?),
+                7 => response.sms(u.arbitrary()?),
+                8 => response.message(u.arbitrary()?),
+                9 => response.enqueue(u.arbitrary()?),
+                10 => response.leave(),
+                11 => response.hangup(),
+                _ => response.pay(Pay::new()),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arbitrary_documents_are_bounded() {
+        let data: Vec<u8> = (0..4096u32).map(|i| (i.wrapping_mul(2654435761) >> 13) as u8).collect();
+        let mut u = Unstructured::new(&data);
+        while !u.is_empty() {
+            let response = Response::arbitrary(&mut u).unwrap();
+            assert!(response.factory().children().len() <= MAX_CHILDREN);
+            assert!(response.to_xml_string().starts_with("<?xml"));
+        }
+    }
+}
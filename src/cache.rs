@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::twiml::{render_document, TwiMLElement};
+
+/// Memoizes the serialized XML of documents that are built over and over again
+///
+/// Documents are keyed by an exact encoding of their elements, so a freshly built copy of
+/// a cached document is served without serializing it again. The whole key is compared on
+/// every lookup, so documents with user-supplied text can't be made to collide with one
+/// another. Once `capacity` documents are cached, further documents are serialized
+/// without being stored.
+#[derive(Debug)]
+pub struct TwimlCache {
+    capacity: usize,
+    entries: Mutex<HashMap<Vec<u8>, Arc<str>>>,
+}
+
+impl TwimlCache {
+    /// Create a cache holding at most `capacity` documents
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Get the serialized XML of a document, serializing it only on first use
+    pub fn get_or_render(&self, element: &dyn TwiMLElement) -> Arc<str> {
+        let key = element.factory().structure_key();
+
+        if let Some(xml) = self.entries.lock().unwrap().get(&key) {
+            return xml.clone();
+        }
+        let xml: Arc<str> = render_document(element.to_xml()).into();
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() < self.capacity {
+            entries.insert(key, xml.clone());
+        }
+        xml
+    }
+
+    /// Number of cached documents
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    /// Whether no documents are cached
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove every cached document
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+#[cfg(all(test, feature = "voice"))]
+mod tests {
+    use super::*;
+    use crate::twiml::*;
+
+    fn greeting(text: &str) -> Response {
+        Response::new().say(Say::new(text).voice("alice")).hangup()
+    }
+
+    #[test]
+    fn test_cache_by_structure() {
+        let cache = TwimlCache::new(2);
+        let first = cache.get_or_render(&greeting("We are closed"));
+        let second = cache.get_or_render(&greeting("We are closed"));
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(&*first, greeting("We are closed").to_xml_string());
+
+        let other = cache.get_or_render(&greeting("We are open"));
+        assert!(other.contains("We are open"));
+        assert_eq!(cache.len(), 2);
+
+        cache.get_or_render(&Response::new().hangup());
+        assert_eq!(cache.len(), 2);
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_cache_compares_whole_document() {
+        let cache = TwimlCache::new(4);
+        cache.get_or_render(&Response::new().say("ab"));
+        // Same strings split differently must not share an entry
+        let split = cache.get_or_render(&Response::new().say(Say::new("a").voice("b")));
+        assert_eq!(&*split, Response::new().say(Say::new("a").voice("b")).to_xml_string());
+        let cdata = cache.get_or_render(&Response::new().enqueue(Enqueue::new_empty().task(Task::new("{}").cdata(true))));
+        assert!(cdata.contains("<![CDATA[{}]]>"));
+        assert_eq!(cache.len(), 3);
+    }
+}
@@ -0,0 +1,214 @@
+//! Opt-out, opt-in and help keywords carriers require messaging programs to honor
+//!
+//! A message whose whole body is a keyword such as `STOP` or `AYUDA` must get a reply
+//! confirming the change or describing the program. Numbers using Twilio's Advanced
+//! Opt-Out reply on the application's behalf; the others can answer with
+//! [`Compliance::respond`].
+
+use crate::twiml::{Message, Response};
+use crate::webhook::MessagingRequest;
+
+/// Keyword class of an inbound message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ComplianceKeyword {
+    /// The sender asks to stop receiving messages (`STOP`, `UNSUBSCRIBE`, `PARAR`, ...)
+    OptOut,
+    /// The sender asks to receive messages again (`START`, `UNSTOP`, `COMENZAR`, ...)
+    OptIn,
+    /// The sender asks what the program is (`HELP`, `INFO`, `AYUDA`, ...)
+    Help,
+}
+
+/// Keywords by class, uppercase without spaces or accents
+const KEYWORDS: &[(&str, ComplianceKeyword)] = &[
+    ("ALTA", ComplianceKeyword::OptIn),
+    ("ALTO", ComplianceKeyword::OptOut),
+    ("AYUDA", ComplianceKeyword::Help),
+    ("BAJA", ComplianceKeyword::OptOut),
+    ("CANCEL", ComplianceKeyword::OptOut),
+    ("CANCELAR", ComplianceKeyword::OptOut),
+    ("COMENZAR", ComplianceKeyword::OptIn),
+    ("DETENER", ComplianceKeyword::OptOut),
+    ("END", ComplianceKeyword::OptOut),
+    ("HELP", ComplianceKeyword::Help),
+    ("INFO", ComplianceKeyword::Help),
+    ("INFORMACION", ComplianceKeyword::Help),
+    ("INICIAR", ComplianceKeyword::OptIn),
+    ("OPTIN", ComplianceKeyword::OptIn),
+    ("OPTOUT", ComplianceKeyword::OptOut),
+    ("PARA", ComplianceKeyword::OptOut),
+    ("PARAR", ComplianceKeyword::OptOut),
+    ("QUIT", ComplianceKeyword::OptOut),
+    ("REVOKE", ComplianceKeyword::OptOut),
+    ("START", ComplianceKeyword::OptIn),
+    ("STOP", ComplianceKeyword::OptOut),
+    ("STOPALL", ComplianceKeyword::OptOut),
+    ("UNSTOP", ComplianceKeyword::OptIn),
+    ("UNSUBSCRIBE", ComplianceKeyword::OptOut),
+    ("YES", ComplianceKeyword::OptIn),
+];
+
+impl ComplianceKeyword {
+    /// Classify a message body, or `None` if it is not a keyword
+    ///
+    /// The whole body must be the keyword. Case, accents, spaces and trailing
+    /// punctuation are ignored, so `Stop!`, `opt out` and `Información` all match.
+    pub fn classify(body: &str) -> Option<Self> {
+        let key: String = body
+            .trim()
+            .trim_end_matches(|c: char| c.is_ascii_punctuation())
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| match c {
+                'á' | 'Á' => 'A',
+                'é' | 'É' => 'E',
+                'í' | 'Í' => 'I',
+                'ó' | 'Ó' => 'O',
+                'ú' | 'Ú' => 'U',
+                c => c.to_ascii_uppercase(),
+            })
+            .collect();
+        KEYWORDS
+            .binary_search_by_key(&key.as_str(), |(keyword, _)| keyword)
+            .ok()
+            .map(|index| KEYWORDS[index].1)
+    }
+}
+
+/// Consent change to record after an opt-out or opt-in keyword
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Consent {
+    /// The sender may no longer be messaged
+    Revoked,
+    /// The sender may be messaged again
+    Granted,
+}
+
+/// Destination for consent changes, such as a subscriber table
+///
+/// Implemented for closures taking the sender and the change.
+pub trait ConsentRecorder {
+    /// Record that `sender` revoked or granted consent
+    fn record(&self, sender: &str, consent: Consent);
+}
+
+impl<F: Fn(&str, Consent)> ConsentRecorder for F {
+    fn record(&self, sender: &str, consent: Consent) {
+        self(sender, consent)
+    }
+}
+
+/// Replies to compliance keywords for one messaging program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compliance {
+    opt_out_reply: String,
+    opt_in_reply: String,
+    help_reply: String,
+}
+
+impl Compliance {
+    /// Create replies naming the program and, in the help reply, how to reach support,
+    /// such as `"Call 1-800-555-0100 or email help@example.com"`
+    pub fn new(program: &str, support: &str) -> Self {
+        Self {
+            opt_out_reply: format!(
+                "{}: You are unsubscribed and will receive no further messages. Reply START to resubscribe.",
+                program
+            ),
+            opt_in_reply: format!(
+                "{}: You are resubscribed. Msg & data rates may apply. Reply HELP for help, STOP to unsubscribe.",
+                program
+            ),
+            help_reply: format!(
+                "{}: {}. Msg & data rates may apply. Reply STOP to unsubscribe.",
+                program, support
+            ),
+        }
+    }
+
+    /// Replace the reply to opt-out keywords
+    pub fn opt_out_reply(mut self, text: impl Into<String>) -> Self {
+        self.opt_out_reply = text.into();
+        self
+    }
+
+    /// Replace the reply to opt-in keywords
+    pub fn opt_in_reply(mut self, text: impl Into<String>) -> Self {
+        self.opt_in_reply = text.into();
+        self
+    }
+
+    /// Replace the reply to help keywords
+    pub fn help_reply(mut self, text: impl Into<String>) -> Self {
+        self.help_reply = text.into();
+        self
+    }
+
+    /// Build the reply to a keyword
+    pub fn reply(&self, keyword: ComplianceKeyword) -> Response {
+        let text = match keyword {
+            ComplianceKeyword::OptOut => &self.opt_out_reply,
+            ComplianceKeyword::OptIn => &self.opt_in_reply,
+            ComplianceKeyword::Help => &self.help_reply,
+        };
+        Response::new().message(Message::new_with_text(text.as_str()))
+    }
+
+    /// Answer an inbound message if it is a keyword, recording opt-outs and opt-ins for
+    /// the sender, or return `None` to let the application handle it
+    pub fn respond(&self, request: &MessagingRequest, recorder: &dyn ConsentRecorder) -> Option<Response> {
+        let keyword = ComplianceKeyword::classify(&request.body)?;
+        match keyword {
+            ComplianceKeyword::OptOut => recorder.record(&request.from, Consent::Revoked),
+            ComplianceKeyword::OptIn => recorder.record(&request.from, Consent::Granted),
+            ComplianceKeyword::Help => {}
+        }
+        Some(self.reply(keyword))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use crate::twiml::ToXmlString;
+
+    #[test]
+    fn test_classify() {
+        assert!(KEYWORDS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert_eq!(ComplianceKeyword::classify(" Stop! "), Some(ComplianceKeyword::OptOut));
+        assert_eq!(ComplianceKeyword::classify("opt out"), Some(ComplianceKeyword::OptOut));
+        assert_eq!(ComplianceKeyword::classify("Información"), Some(ComplianceKeyword::Help));
+        assert_eq!(ComplianceKeyword::classify("comenzar"), Some(ComplianceKeyword::OptIn));
+        assert_eq!(ComplianceKeyword::classify("please stop texting me"), None);
+        assert_eq!(ComplianceKeyword::classify(""), None);
+    }
+
+    #[test]
+    fn test_respond() {
+        let compliance = Compliance::new("Acme Alerts", "Call 1-800-555-0100");
+        let changes = RefCell::new(Vec::new());
+        let recorder = |sender: &str, consent: Consent| changes.borrow_mut().push((sender.to_string(), consent));
+        let request = |body: &str| {
+            MessagingRequest::from_params(&[
+                ("MessageSid", "SM1"),
+                ("AccountSid", "AC1"),
+                ("From", "+14155550100"),
+                ("To", "+12125550199"),
+                ("Body", body),
+            ])
+            .unwrap()
+        };
+
+        let reply = compliance.respond(&request("STOP"), &recorder).unwrap();
+        assert!(reply.to_xml_string().contains("<Message>Acme Alerts: You are unsubscribed"));
+        assert!(compliance.respond(&request("help"), &recorder).unwrap().to_xml_string().contains("Call 1-800-555-0100."));
+        assert!(compliance.respond(&request("Where is my order?"), &recorder).is_none());
+        compliance.respond(&request("Unstop"), &recorder);
+        assert_eq!(
+            changes.into_inner(),
+            [("+14155550100".to_string(), Consent::Revoked), ("+14155550100".to_string(), Consent::Granted)]
+        );
+    }
+}
@@ -0,0 +1,395 @@
+//! Declarative call flows loaded from YAML or TOML
+//!
+//! A definition maps flow names to lists of verbs. Each verb is written as a single-key
+//! map, with a shorthand for the common case:
+//!
+//! ```yaml
+//! main:
+//!   - say: Welcome to Example Corp
+//!   - gather:
+//!       action: /menu
+//!       num_digits: 1
+//!       prompts:
+//!         - say: Press 1 for sales
+//!   - redirect: /fallback
+//! goodbye:
+//!   - say: { text: Goodbye, voice: Polly.Joanna }
+//!   - hangup
+//! ```
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+use serde::Deserialize;
+
+use crate::twiml::*;
+
+/// Error loading a call flow definition
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The YAML definition is malformed
+    Yaml(serde_yaml::Error),
+    /// The YAML definition doesn't match the call flow schema
+    Schema(serde_json::Error),
+    /// The TOML definition is malformed or doesn't match the schema
+    Toml(toml::de::Error),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Yaml(err) => write!(f, "invalid YAML call flow: {}", err),
+            ConfigError::Schema(err) => write!(f, "invalid call flow: {}", err),
+            ConfigError::Toml(err) => write!(f, "invalid TOML call flow: {}", err),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigError::Yaml(err) => Some(err),
+            ConfigError::Schema(err) => Some(err),
+            ConfigError::Toml(err) => Some(err),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for GatherInput {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let input = String::deserialize(deserializer)?;
+        input.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Say verb written as plain text or with options
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum SayConfig {
+    /// Text spoken with the default voice
+    Text(String),
+    /// Text with voice options
+    Options(SayOptions),
+}
+
+/// Say verb options
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SayOptions {
+    /// Text to speak
+    pub text: String,
+    /// Voice name
+    pub voice: Option<String>,
+    /// Language code
+    pub language: Option<String>,
+    /// Number of times to repeat, 0 for forever
+    #[serde(rename = "loop")]
+    pub loop_times: Option<u32>,
+}
+
+/// Verb allowed inside a Gather
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum PromptConfig {
+    /// Say verb
+    Say(SayConfig),
+    /// Play verb with an audio URL
+    Play(String),
+    /// Pause verb with a length in seconds
+    Pause(usize),
+}
+
+/// Gather verb options
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GatherConfig {
+    /// URL receiving the caller's input
+    pub action: Option<String>,
+    /// HTTP method for the action URL
+    pub method: Option<String>,
+    /// Input modes, e.g. `dtmf`, `speech` or `dtmf speech`
+    pub input: Option<GatherInput>,
+    /// Number of digits to collect
+    pub num_digits: Option<usize>,
+    /// Seconds to wait for input
+    pub timeout: Option<usize>,
+    /// Key that ends input
+    pub finish_on_key: Option<String>,
+    /// Speech recognition language
+    pub language: Option<String>,
+    /// Prompts played while gathering
+    #[serde(default)]
+    pub prompts: Vec<PromptConfig>,
+}
+
+/// Dial verb written as a number or with options
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum DialConfig {
+    /// Number to dial
+    Number(String),
+    /// Number with dial options
+    Options(DialOptions),
+}
+
+/// Dial verb options
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DialOptions {
+    /// Number to dial
+    pub number: String,
+    /// URL requested when the dialed call ends
+    pub action: Option<String>,
+    /// Seconds to wait for an answer
+    pub timeout: Option<usize>,
+    /// Caller ID presented to the dialed party
+    pub caller_id: Option<String>,
+}
+
+/// Record verb options
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RecordConfig {
+    /// URL receiving the recording
+    pub action: Option<String>,
+    /// Longest recording in seconds
+    pub max_length: Option<usize>,
+    /// Whether to play a beep before recording
+    pub play_beep: Option<bool>,
+    /// Whether to transcribe the recording
+    pub transcribe: Option<bool>,
+}
+
+/// One verb of a call flow
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum VerbConfig {
+    /// Say verb
+    Say(SayConfig),
+    /// Play verb with an audio URL
+    Play(String),
+    /// Pause verb with a length in seconds
+    Pause(usize),
+    /// Gather verb
+    Gather(GatherConfig),
+    /// Redirect verb with a URL
+    Redirect(String),
+    /// Dial verb
+    Dial(DialConfig),
+    /// Record verb
+    Record(RecordConfig),
+    /// Message verb with a body
+    Message(String),
+    /// Enqueue verb with a queue name
+    Enqueue(String),
+    /// Hangup verb
+    Hangup,
+}
+
+impl SayConfig {
+    fn to_say(&self) -> Say {
+        match self {
+            SayConfig::Text(text) => Say::new(text.as_str()),
+            SayConfig::Options(SayOptions { text, voice, language, loop_times }) => {
+                let mut say = Say::new(text.as_str());
+                if let Some(voice) = voice {
+                    say = say.voice(voice.as_str());
+                }
+                if let Some(language) = language {
+                    say = say.language(language.as_str());
+                }
+                if let Some(loop_times) = loop_times {
+                    say = say.loop_times(*loop_times);
+                }
+                say
+            }
+        }
+    }
+}
+
+impl GatherConfig {
+    fn to_gather(&self) -> Gather {
+        let mut gather = Gather::new();
+        if let Some(action) = &self.action {
+            gather = gather.action(action.as_str());
+        }
+        if let Some(method) = &self.method {
+            gather = gather.method(method.as_str());
+        }
+        if let Some(input) = self.input {
+            gather = gather.input(input);
+        }
+        if let Some(num_digits) = self.num_digits {
+            gather = gather.num_digits(u32::try_from(num_digits).unwrap_or(u32::MAX));
+        }
+        if let Some(timeout) = self.timeout {
+            gather = gather.timeout(timeout);
+        }
+        if let Some(key) = &self.finish_on_key {
+            gather = gather.finish_on_key(key.as_str());
+        }
+        if let Some(language) = &self.language {
+            gather = gather.language(language.as_str());
+        }
+        for prompt in &self.prompts {
+            gather = match prompt {
+                PromptConfig::Say(say) => gather.say(say.to_say()),
+                PromptConfig::Play(url) => gather.play(url.as_str()),
+                PromptConfig::Pause(length) => gather.pause(Pause::new().length(*length)),
+            };
+        }
+        gather
+    }
+}
+
+impl DialConfig {
+    fn to_dial(&self) -> Dial {
+        match self {
+            DialConfig::Number(number) => Dial::new_with_number(number.as_str()),
+            DialConfig::Options(DialOptions { number, action, timeout, caller_id }) => {
+                let mut dial = Dial::new_with_number(number.as_str());
+                if let Some(action) = action {
+                    dial = dial.action(action.as_str());
+                }
+                if let Some(timeout) = timeout {
+                    dial = dial.timeout(*timeout);
+                }
+                if let Some(caller_id) = caller_id {
+                    dial = dial.caller_id(caller_id.as_str());
+                }
+                dial
+            }
+        }
+    }
+}
+
+impl RecordConfig {
+    fn to_record(&self) -> Record {
+        let mut record = Record::new();
+        if let Some(action) = &self.action {
+            record = record.action(action.as_str());
+        }
+        if let Some(max_length) = self.max_length {
+            record = record.max_length(max_length);
+        }
+        if let Some(play_beep) = self.play_beep {
+            record = record.play_beep(play_beep);
+        }
+        if let Some(transcribe) = self.transcribe {
+            record = record.transcribe(transcribe);
+        }
+        record
+    }
+}
+
+/// Named call flows loaded from a definition file
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(transparent)]
+pub struct CallFlows {
+    flows: BTreeMap<String, Vec<VerbConfig>>,
+}
+
+impl CallFlows {
+    /// Parse call flows from YAML
+    pub fn from_yaml(source: &str) -> Result<Self, ConfigError> {
+        // serde_yaml expects `!tag` syntax for enums, so go through JSON to accept the
+        // single-key maps TOML uses
+        let value: serde_json::Value = serde_yaml::from_str(source).map_err(ConfigError::Yaml)?;
+        serde_json::from_value(value).map_err(ConfigError::Schema)
+    }
+
+    /// Parse call flows from TOML
+    pub fn from_toml(source: &str) -> Result<Self, ConfigError> {
+        toml::from_str(source).map_err(ConfigError::Toml)
+    }
+
+    /// Iterate over the flow names in sorted order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.flows.keys().map(String::as_str)
+    }
+
+    /// Get the verbs of a flow
+    pub fn verbs(&self, name: &str) -> Option<&[VerbConfig]> {
+        self.flows.get(name).map(Vec::as_slice)
+    }
+
+    /// Build the document for a flow
+    pub fn response(&self, name: &str) -> Option<Response> {
+        let verbs = self.flows.get(name)?;
+        Some(verbs.iter().fold(Response::new(), |response, verb| match verb {
+            VerbConfig::Say(say) => response.say(say.to_say()),
+            VerbConfig::Play(url) => response.play(url.as_str()),
+            VerbConfig::Pause(length) => response.pause(Pause::new().length(*length)),
+            VerbConfig::Gather(gather) => response.gather(gather.to_gather()),
+            VerbConfig::Redirect(url) => response.redirect(url.as_str()),
+            VerbConfig::Dial(dial) => response.dial(dial.to_dial()),
+            VerbConfig::Record(record) => response.record(record.to_record()),
+            VerbConfig::Message(body) => response.message(Message::new_with_text(body.as_str())),
+            VerbConfig::Enqueue(queue) => response.enqueue(Enqueue::new(queue.as_str())),
+            VerbConfig::Hangup => response.hangup(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const YAML: &str = r#"
+main:
+  - say: Welcome
+  - gather:
+      action: /menu
+      input: dtmf
+      num_digits: 1
+      prompts:
+        - say: { text: Press 1, voice: alice }
+  - redirect: /fallback
+goodbye:
+  - say: Goodbye
+  - hangup
+"#;
+
+    #[test]
+    fn test_yaml_call_flows() {
+        let flows = CallFlows::from_yaml(YAML).unwrap();
+        assert_eq!(flows.names().collect::<Vec<_>>(), vec!["goodbye", "main"]);
+
+        let xml_string = flows.response("main").unwrap().to_xml_string();
+        assert!(xml_string.ends_with(concat!(
+            r#"<Response><Say>Welcome</Say><Gather action="/menu" input="dtmf" numDigits="1"><Say voice="alice">Press 1</Say></Gather>"#,
+            r#"<Redirect>/fallback</Redirect></Response>"#,
+        )));
+        assert!(flows.response("goodbye").unwrap().to_xml_string().ends_with("<Say>Goodbye</Say><Hangup /></Response>"));
+        assert!(flows.response("missing").is_none());
+    }
+
+    #[test]
+    fn test_toml_call_flows() {
+        let flows = CallFlows::from_toml(
+            r#"
+main = [
+    { say = "Connecting you" },
+    { dial = { number = "+15551234567", timeout = 20 } },
+    "hangup",
+]
+"#,
+        )
+        .unwrap();
+
+        assert!(flows.response("main").unwrap().to_xml_string().ends_with(
+            r#"<Response><Say>Connecting you</Say><Dial timeout="20">+15551234567</Dial><Hangup /></Response>"#
+        ));
+    }
+
+    #[test]
+    fn test_unknown_fields_are_rejected() {
+        let error = CallFlows::from_yaml("main:\n  - gather: { action: /menu, num_digit: 1 }\n").unwrap_err();
+        assert!(matches!(error, ConfigError::Schema(_)));
+        assert!(matches!(CallFlows::from_yaml("main: [").unwrap_err(), ConfigError::Yaml(_)));
+        assert!(CallFlows::from_yaml("main:\n  - shout: Hello\n").is_err());
+        assert!(CallFlows::from_yaml("main:\n  - say: { text: Hello, voise: alice }\n").is_err());
+        assert!(CallFlows::from_yaml("main:\n  - dial: { number: '+15551234567', timout: 20 }\n").is_err());
+    }
+}
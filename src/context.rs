@@ -0,0 +1,260 @@
+//! Per-deployment defaults for the elements a flow builds
+//!
+//! A [`TwimlContext`] holds the voice, language, base URL and other settings shared by
+//! every document of a deployment, and creates elements with them already applied.
+
+use std::collections::BTreeMap;
+
+use crate::dialect::Dialect;
+use crate::serializer::Serializer;
+use crate::twiml::Redirect;
+#[cfg(feature = "voice")]
+use crate::twiml::{Dial, Gather, Play, Record, Say};
+
+/// Defaults shared by the documents of one deployment or tenant
+///
+/// Elements created through the context get its voice, language and method, and
+/// relative URLs are resolved against its base URL, so the defaults can be changed in
+/// one place. The setters of the attributes a context sets replace its value, so
+/// `ctx.say("Hi").voice("alice")` overrides the default voice for that element only.
+///
+/// A white-label deployment registers each tenant's overrides with
+/// [`tenant`](Self::tenant) and builds its documents from
+/// [`for_tenant`](Self::for_tenant), instead of passing tenant settings to every flow.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TwimlContext {
+    base_url: Option<String>,
+    voice: Option<String>,
+    language: Option<String>,
+    method: Option<String>,
+    dialect: Option<Dialect>,
+    caller_id: Option<String>,
+    dial_record: Option<String>,
+    tenants: BTreeMap<String, TwimlContext>,
+}
+
+impl TwimlContext {
+    /// Create a context without defaults
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the URL relative paths such as `/menu` are resolved against
+    pub fn base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = Some(url.into());
+        self
+    }
+
+    /// Set the voice of `Say`
+    pub fn voice(mut self, voice: impl Into<String>) -> Self {
+        self.voice = Some(voice.into());
+        self
+    }
+
+    /// Set the language of `Say` and of speech recognition in `Gather`, such as the
+    /// tenant's locale `en-GB`
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Set the HTTP method Twilio uses to request action and Redirect URLs
+    pub fn method(mut self, method: impl Into<String>) -> Self {
+        self.method = Some(method.into());
+        self
+    }
+
+    /// Set the dialect documents are serialized to by [`serializer`](Self::serializer)
+    pub fn dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = Some(dialect);
+        self
+    }
+
+    /// Set the caller ID of `Dial`
+    pub fn caller_id(mut self, caller_id: impl Into<String>) -> Self {
+        self.caller_id = Some(caller_id.into());
+        self
+    }
+
+    /// Set the record attribute of `Dial`, such as `record-from-answer-dual`
+    pub fn dial_record(mut self, record: impl Into<String>) -> Self {
+        self.dial_record = Some(record.into());
+        self
+    }
+
+    /// Register a tenant whose defaults replace this context's where they are set
+    pub fn tenant(mut self, id: impl Into<String>, overrides: TwimlContext) -> Self {
+        self.tenants.insert(id.into(), overrides);
+        self
+    }
+
+    /// Resolve the context of a tenant, or this context for an unknown tenant
+    pub fn for_tenant(&self, id: &str) -> TwimlContext {
+        match self.tenants.get(id) {
+            Some(overrides) => self.with_overrides(overrides),
+            None => TwimlContext { tenants: BTreeMap::new(), ..self.clone() },
+        }
+    }
+
+    /// Combine with another context whose defaults win where they are set
+    pub fn with_overrides(&self, overrides: &TwimlContext) -> TwimlContext {
+        let pick = |base: &Option<String>, other: &Option<String>| other.clone().or_else(|| base.clone());
+        TwimlContext {
+            base_url: pick(&self.base_url, &overrides.base_url),
+            voice: pick(&self.voice, &overrides.voice),
+            language: pick(&self.language, &overrides.language),
+            method: pick(&self.method, &overrides.method),
+            dialect: overrides.dialect.or(self.dialect),
+            caller_id: pick(&self.caller_id, &overrides.caller_id),
+            dial_record: pick(&self.dial_record, &overrides.dial_record),
+            tenants: BTreeMap::new(),
+        }
+    }
+
+    /// Resolve a path against the base URL, leaving absolute URLs unchanged
+    pub fn url(&self, path: &str) -> String {
+        match &self.base_url {
+            Some(base) if !path.contains("://") => {
+                format!("{}/{}", base.trim_end_matches('/'), path.trim_start_matches('/'))
+            }
+            _ => path.to_string(),
+        }
+    }
+
+    /// Create a serializer writing this context's dialect
+    pub fn serializer(&self) -> Serializer {
+        match self.dialect {
+            Some(dialect) => Serializer::new().dialect(dialect),
+            None => Serializer::new(),
+        }
+    }
+
+    /// Create a Redirect to a path
+    pub fn redirect(&self, path: &str) -> Redirect {
+        let redirect = Redirect::new(self.url(path));
+        match &self.method {
+            Some(method) => redirect.method(method.as_str()),
+            None => redirect,
+        }
+    }
+
+    /// Create a Say with the default voice and language
+    #[cfg(feature = "voice")]
+    pub fn say(&self, text: impl Into<String>) -> Say {
+        let mut say = Say::new(text);
+        if let Some(voice) = &self.voice {
+            say = say.voice(voice.as_str());
+        }
+        if let Some(language) = &self.language {
+            say = say.language(language.as_str());
+        }
+        say
+    }
+
+    /// Create a Play of a path or URL
+    #[cfg(feature = "voice")]
+    pub fn play(&self, path: &str) -> Play {
+        Play::new(self.url(path))
+    }
+
+    /// Create a Gather submitting to a path, with the default method and language
+    #[cfg(feature = "voice")]
+    pub fn gather(&self, action: &str) -> Gather {
+        let mut gather = Gather::new().action(self.url(action));
+        if let Some(method) = &self.method {
+            gather = gather.method(method.as_str());
+        }
+        if let Some(language) = &self.language {
+            gather = gather.language(language.as_str());
+        }
+        gather
+    }
+
+    /// Create an empty Dial with the default caller ID and recording
+    #[cfg(feature = "voice")]
+    pub fn dial(&self) -> Dial {
+        let mut dial = Dial::new_empty();
+        if let Some(caller_id) = &self.caller_id {
+            dial = dial.caller_id(caller_id.as_str());
+        }
+        if let Some(record) = &self.dial_record {
+            dial = dial.record(record.as_str());
+        }
+        dial
+    }
+
+    /// Create a Record submitting to a path, with the default method
+    #[cfg(feature = "voice")]
+    pub fn record(&self, action: &str) -> Record {
+        let record = Record::new().action(self.url(action));
+        match &self.method {
+            Some(method) => record.method(method.as_str()),
+            None => record,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "voice"))]
+mod tests {
+    use super::*;
+    use crate::twiml::{render_fragment, Response, ToXmlString, TwiMLElement};
+
+    #[test]
+    fn test_context_defaults() {
+        let ctx = TwimlContext::new()
+            .base_url("https://ivr.example.com/")
+            .voice("Polly.Joanna")
+            .language("en-US")
+            .method("POST");
+        let response = Response::new()
+            .gather(ctx.gather("/menu").num_digits(1).say(ctx.say("Press 1")))
+            .play(ctx.play("https://cdn.example.com/hold.mp3"))
+            .redirect(ctx.redirect("menu"));
+        assert!(response.to_xml_string().ends_with(concat!(
+            r#"<Response><Gather action="https://ivr.example.com/menu" method="POST" language="en-US" numDigits="1">"#,
+            r#"<Say voice="Polly.Joanna" language="en-US">Press 1</Say></Gather>"#,
+            r#"<Play>https://cdn.example.com/hold.mp3</Play>"#,
+            r#"<Redirect method="POST">https://ivr.example.com/menu</Redirect></Response>"#,
+        )));
+
+        let plain = TwimlContext::new();
+        assert_eq!(plain.url("/menu"), "/menu");
+        assert!(plain.say("Hi").to_xml_string().ends_with("<Say>Hi</Say>"));
+
+        let signalwire = TwimlContext::new().dialect(Dialect::SignalWire);
+        assert!(signalwire.serializer().to_string(&Response::new().denoise()).is_ok());
+        assert!(plain.serializer().dialect(Dialect::Twilio).to_string(&Response::new().denoise()).is_err());
+    }
+
+    #[test]
+    fn test_tenant_overrides() {
+        let ctx = TwimlContext::new()
+            .base_url("https://ivr.example.com")
+            .language("en-US")
+            .caller_id("+15550000000")
+            .tenant("acme", TwimlContext::new().language("en-GB").dial_record("record-from-answer"))
+            .tenant("globex", TwimlContext::new().caller_id("+15551111111"));
+
+        let acme = ctx.for_tenant("acme");
+        assert_eq!(
+            render_fragment(&acme.dial().to_xml()),
+            r#"<Dial callerId="+15550000000" record="record-from-answer" />"#
+        );
+        assert!(acme.say("Hello").to_xml_string().ends_with(r#"<Say language="en-GB">Hello</Say>"#));
+        assert_eq!(acme.url("/menu"), "https://ivr.example.com/menu");
+        assert_eq!(render_fragment(&ctx.for_tenant("globex").dial().to_xml()), r#"<Dial callerId="+15551111111" />"#);
+        assert_eq!(ctx.for_tenant("unknown").dial().to_xml_string(), ctx.dial().to_xml_string());
+    }
+
+    #[test]
+    fn test_override_defaults() {
+        let ctx = TwimlContext::new().voice("Polly.Joanna").language("en-US").method("POST").caller_id("+15550000000");
+        assert!(ctx.say("Hi").voice("alice").to_xml_string().ends_with(r#"<Say voice="alice" language="en-US">Hi</Say>"#));
+        assert_eq!(
+            render_fragment(&ctx.gather("/menu").method("GET").language("fr-FR").to_xml()),
+            r#"<Gather action="/menu" method="GET" language="fr-FR" />"#
+        );
+        assert_eq!(render_fragment(&ctx.dial().caller_id("+15551111111").to_xml()), r#"<Dial callerId="+15551111111" />"#);
+        assert!(ctx.redirect("/next").method("GET").to_xml_string().ends_with(r#"<Redirect method="GET">/next</Redirect>"#));
+    }
+}
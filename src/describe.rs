@@ -0,0 +1,145 @@
+use crate::twiml::{ElementFactory, Response, TwiMLElement};
+
+/// Longest quoted text kept in a description before it is shortened
+const MAX_QUOTE_CHARACTERS: usize = 30;
+
+fn quote(text: &str) -> String {
+    let text = text.trim();
+    if text.chars().count() > MAX_QUOTE_CHARACTERS {
+        let shortened: String = text.chars().take(MAX_QUOTE_CHARACTERS).collect();
+        format!("'{}…'", shortened.trim_end())
+    } else {
+        format!("'{}'", text)
+    }
+}
+
+/// Describe where an element posts its result, as ` → POST /url`
+fn target(node: &ElementFactory) -> String {
+    match node.attribute("action") {
+        Some(action) => format!(" → {} {}", node.attribute("method").unwrap_or("POST"), action),
+        None => String::new(),
+    }
+}
+
+fn describe_gather(node: &ElementFactory) -> String {
+    let digits = match node.attribute("numDigits") {
+        Some("1") => "1 digit".to_string(),
+        Some(count) => format!("{} digits", count),
+        None => "digits".to_string(),
+    };
+    let input = match node.attribute("input") {
+        Some("speech") => "speech".to_string(),
+        Some(input) if input.contains("speech") => format!("{} or speech", digits),
+        _ => digits,
+    };
+
+    let prompts: Vec<String> = node.children().iter().map(|child| describe(child.factory())).collect();
+    let prompts = if prompts.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", prompts.join(", "))
+    };
+    format!("Gather {}{}{}", input, prompts, target(node))
+}
+
+fn describe_dial(node: &ElementFactory) -> String {
+    let mut endpoints: Vec<String> = node.text().map(str::to_string).into_iter().collect();
+    for child in node.children() {
+        let noun = child.factory();
+        let text = match noun.name() {
+            "Client" => noun.text().or_else(|| noun.child_text("Identity")),
+            "Application" => noun.child_text("ApplicationSid"),
+            _ => noun.text(),
+        };
+        let text = text.unwrap_or_default();
+        endpoints.push(match noun.name() {
+            "Number" => text.to_string(),
+            "Conference" | "Queue" => format!("{} {}", noun.name(), quote(text)),
+            name => format!("{} {}", name, text),
+        });
+    }
+    format!("Dial {}{}", endpoints.join(" and "), target(node))
+}
+
+fn describe(node: &ElementFactory) -> String {
+    let text = node.plain_text().unwrap_or_default();
+    let text = text.as_ref();
+    match node.name() {
+        "Say" => format!("Say {}", quote(text)),
+        "Play" => format!("Play {}", text),
+        "Pause" => format!("Pause {}s", node.attribute("length").unwrap_or("1")),
+        "Gather" => describe_gather(node),
+        "Redirect" => match node.attribute("method") {
+            Some(method) => format!("Redirect {} {}", method, text),
+            None => format!("Redirect {}", text),
+        },
+        "Record" => format!("Record{}", target(node)),
+        "Dial" => describe_dial(node),
+        "Message" | "Sms" => {
+            let body = node.text().or_else(|| {
+                node.children()
+                    .iter()
+                    .find(|child| child.factory().name() == "Body")
+                    .and_then(|body| body.factory().text())
+            });
+            match body {
+                Some(body) => format!("{} {}", node.name(), quote(body)),
+                None => node.name().to_string(),
+            }
+        }
+        "Enqueue" if !text.is_empty() => format!("Enqueue {}", quote(text)),
+        name => format!("{}{}", name, target(node)),
+    }
+}
+
+impl Response {
+    /// Summarize the call flow as a single line of plain text
+    ///
+    /// The verb following a Gather is described as its timeout path, since Twilio only
+    /// reaches it when the caller gives no input.
+    pub fn describe(&self) -> String {
+        let mut outline = String::new();
+        let mut after_gather = false;
+        for (index, child) in self.factory().children().iter().enumerate() {
+            if index > 0 {
+                outline.push_str(if after_gather { ", on timeout " } else { ", then " });
+            }
+            let node = child.factory();
+            outline.push_str(&describe(node));
+            after_gather = node.name() == "Gather";
+        }
+        outline
+    }
+}
+
+#[cfg(all(test, feature = "messaging", feature = "voice"))]
+mod tests {
+    use crate::twiml::*;
+
+    #[test]
+    fn test_describe_call_flow() {
+        let response = Response::new()
+            .say("Welcome to Example Corp, the best company in the world")
+            .gather(Gather::new().num_digits(1).action("/menu").say("Press 1 for sales"))
+            .redirect("/fallback");
+
+        assert_eq!(
+            response.describe(),
+            "Say 'Welcome to Example Corp, the b…', then Gather 1 digit (Say 'Press 1 for sales') → POST /menu, on timeout Redirect /fallback"
+        );
+    }
+
+    #[test]
+    fn test_describe_dial_and_message() {
+        let response = Response::new()
+            .dial(Dial::new_empty().action("/after").number(Number::new("+15551234567")).client(Client::new("alice")))
+            .message(Message::new_with_text("Thanks for calling"))
+            .hangup();
+
+        assert_eq!(
+            response.describe(),
+            "Dial +15551234567 and Client alice → POST /after, then Message 'Thanks for calling', then Hangup"
+        );
+        assert_eq!(Response::new().describe(), "");
+    }
+}
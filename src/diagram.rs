@@ -0,0 +1,211 @@
+use crate::twiml::{Response, TwiMLElement};
+
+/// Maximum number of text characters shown in a node label
+const LABEL_TEXT_LIMIT: usize = 40;
+
+/// Kind of edge in a call flow diagram
+#[derive(Debug, PartialEq)]
+enum EdgeKind {
+    /// Parent element to nested child element
+    Child,
+    /// Element to the URL Twilio requests next, labelled with the HTTP method if set
+    Webhook(Option<String>),
+}
+
+#[derive(Debug)]
+struct Edge {
+    from: usize,
+    to: usize,
+    kind: EdgeKind,
+}
+
+/// Flattened view of a document: element nodes, URL nodes and the edges between them
+#[derive(Debug, Default)]
+struct Graph {
+    elements: Vec<String>,
+    urls: Vec<String>,
+    edges: Vec<Edge>,
+}
+
+impl Graph {
+    fn build(root: &dyn TwiMLElement) -> Self {
+        let mut graph = Graph::default();
+        graph.add(root);
+        graph
+    }
+
+    fn add(&mut self, element: &dyn TwiMLElement) -> usize {
+        let node = element.factory();
+        let id = self.elements.len();
+        self.elements.push(label(node.name(), node.plain_text().as_deref()));
+
+        let target = match node.name() {
+            "Redirect" => node.text(),
+            _ => node.attribute("action"),
+        };
+        if let Some(url) = target {
+            let to = self.url_id(url);
+            let method = node.attribute("method").map(|m| m.to_string());
+            self.edges.push(Edge { from: id, to, kind: EdgeKind::Webhook(method) });
+        }
+
+        for child in node.children() {
+            let child_id = self.add(child.as_ref());
+            self.edges.push(Edge { from: id, to: child_id, kind: EdgeKind::Child });
+        }
+
+        id
+    }
+
+    fn url_id(&mut self, url: &str) -> usize {
+        match self.urls.iter().position(|u| u == url) {
+            Some(index) => index,
+            None => {
+                self.urls.push(url.to_string());
+                self.urls.len() - 1
+            }
+        }
+    }
+}
+
+/// Build a node label from the element name and a shortened form of its text
+fn label(name: &str, text: Option<&str>) -> String {
+    match text {
+        Some(text) if name != "Redirect" => {
+            let mut short: String = text.chars().take(LABEL_TEXT_LIMIT).collect();
+            if text.chars().count() > LABEL_TEXT_LIMIT {
+                short.push('…');
+            }
+            format!("{}: {}", name, short)
+        }
+        _ => name.to_string(),
+    }
+}
+
+/// Escape text for a quoted Mermaid label: quotes, brackets and pipes become entity
+/// codes and line breaks become spaces, since any of them would end the label early
+fn mermaid_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.replace("\r\n", "\n").chars() {
+        match c {
+            '"' => escaped.push_str("#quot;"),
+            '[' => escaped.push_str("#91;"),
+            ']' => escaped.push_str("#93;"),
+            '|' => escaped.push_str("#124;"),
+            '\n' | '\r' => escaped.push(' '),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl Response {
+    /// Render the call flow as a Mermaid flowchart
+    ///
+    /// Elements become boxes connected to their children; `action` attributes and
+    /// `Redirect` targets become dashed edges to rounded URL nodes.
+    pub fn to_mermaid(&self) -> String {
+        let graph = Graph::build(self);
+        let mut out = String::from("flowchart TD\n");
+
+        for (id, label) in graph.elements.iter().enumerate() {
+            out.push_str(&format!("    n{}[\"{}\"]\n", id, mermaid_escape(label)));
+        }
+        for (id, url) in graph.urls.iter().enumerate() {
+            out.push_str(&format!("    u{}([\"{}\"])\n", id, mermaid_escape(url)));
+        }
+        for edge in &graph.edges {
+            match &edge.kind {
+                EdgeKind::Child => out.push_str(&format!("    n{} --> n{}\n", edge.from, edge.to)),
+                EdgeKind::Webhook(Some(method)) => out.push_str(&format!(
+                    "    n{} -. {} .-> u{}\n",
+                    edge.from,
+                    mermaid_escape(method),
+                    edge.to
+                )),
+                EdgeKind::Webhook(None) => out.push_str(&format!("    n{} -.-> u{}\n", edge.from, edge.to)),
+            }
+        }
+
+        out
+    }
+
+    /// Render the call flow as a Graphviz DOT digraph
+    ///
+    /// Uses the same layout rules as [`Response::to_mermaid`].
+    pub fn to_dot(&self) -> String {
+        let graph = Graph::build(self);
+        let mut out = String::from("digraph twiml {\n    node [shape=box];\n");
+
+        for (id, label) in graph.elements.iter().enumerate() {
+            out.push_str(&format!("    n{} [label=\"{}\"];\n", id, dot_escape(label)));
+        }
+        for (id, url) in graph.urls.iter().enumerate() {
+            out.push_str(&format!("    u{} [label=\"{}\", shape=ellipse];\n", id, dot_escape(url)));
+        }
+        for edge in &graph.edges {
+            match &edge.kind {
+                EdgeKind::Child => out.push_str(&format!("    n{} -> n{};\n", edge.from, edge.to)),
+                EdgeKind::Webhook(Some(method)) => out.push_str(&format!(
+                    "    n{} -> u{} [style=dashed, label=\"{}\"];\n",
+                    edge.from,
+                    edge.to,
+                    dot_escape(method)
+                )),
+                EdgeKind::Webhook(None) => {
+                    out.push_str(&format!("    n{} -> u{} [style=dashed];\n", edge.from, edge.to))
+                }
+            }
+        }
+
+        out.push_str("}\n");
+        out
+    }
+}
+
+#[cfg(all(test, feature = "voice"))]
+mod tests {
+    use crate::twiml::*;
+
+    fn menu() -> Response {
+        Response::new()
+            .gather(
+                Gather::new()
+                    .action("/menu")
+                    .method("POST")
+                    .say(Say::new("Press 1 for \"sales\"")),
+            )
+            .redirect(Redirect::new("/menu"))
+    }
+
+    #[test]
+    fn test_mermaid_output() {
+        let mermaid = menu().to_mermaid();
+        assert!(mermaid.starts_with("flowchart TD\n"));
+        assert!(mermaid.contains("n0[\"Response\"]"));
+        assert!(mermaid.contains("n2[\"Say: Press 1 for #quot;sales#quot;\"]"));
+        assert!(mermaid.contains("n0 --> n1"));
+        assert!(mermaid.contains("n1 -. POST .-> u0"));
+        // Both edges point at the same URL node
+        assert!(mermaid.contains("n3 -.-> u0"));
+        assert!(!mermaid.contains("u1"));
+
+        let multiline = Response::new().say("Menu [main]\r\nPress 1 | 2\nGoodbye").to_mermaid();
+        assert!(multiline.contains("n1[\"Say: Menu #91;main#93; Press 1 #124; 2 Goodbye\"]\n"));
+        assert_eq!(multiline.lines().count(), 4);
+    }
+
+    #[test]
+    fn test_dot_output() {
+        let dot = menu().to_dot();
+        assert!(dot.starts_with("digraph twiml {"));
+        assert!(dot.contains("n2 [label=\"Say: Press 1 for \\\"sales\\\"\"];"));
+        assert!(dot.contains("u0 [label=\"/menu\", shape=ellipse];"));
+        assert!(dot.contains("n1 -> u0 [style=dashed, label=\"POST\"];"));
+        assert!(dot.trim_end().ends_with('}'));
+    }
+}
@@ -0,0 +1,446 @@
+use std::error::Error;
+use std::fmt;
+
+use xml_builder::XMLElement;
+
+use crate::sanitize::{sanitize, SanitizePolicy};
+use crate::twiml::{escape_text, render_document, Response, TwiMLElement};
+
+/// Provider whose XML flavor a document is serialized for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dialect {
+    /// Twilio TwiML
+    #[default]
+    Twilio,
+    /// SignalWire cXML: TwiML plus SignalWire extensions such as `<Denoise>`
+    SignalWire,
+    /// Plivo XML
+    Plivo,
+    /// Bandwidth BXML
+    Bandwidth,
+}
+
+/// Elements only understood by SignalWire
+const SIGNALWIRE_EXTENSIONS: &[&str] = &["Denoise"];
+
+/// Elements backed by Twilio products that SignalWire cXML has no equivalent for
+const TWILIO_ONLY: &[&str] = &["Application", "ApplicationSid", "Conversation", "ConversationRelay", "VirtualAgent"];
+
+/// Where a TwiML element's text goes in the target dialect
+enum TextRule {
+    /// Text content of the target element
+    Content,
+    /// Value of the named attribute
+    Attribute(&'static str),
+    /// Text of a new child element with the given name
+    Child(&'static str),
+}
+
+/// Mapping of a TwiML element onto another provider's element
+struct ElementRule {
+    /// TwiML element name
+    twiml: &'static str,
+    /// Target element name, or `None` to merge the element's text into its parent
+    target: Option<&'static str>,
+    /// TwiML attribute names and the target attribute names they map to
+    attributes: &'static [(&'static str, &'static str)],
+    /// Placement of the element's text
+    text: TextRule,
+}
+
+const PLIVO_RULES: &[ElementRule] = &[
+    ElementRule { twiml: "Response", target: Some("Response"), attributes: &[], text: TextRule::Content },
+    ElementRule {
+        twiml: "Say",
+        target: Some("Speak"),
+        attributes: &[("voice", "voice"), ("language", "language"), ("loop", "loop")],
+        text: TextRule::Content,
+    },
+    ElementRule { twiml: "Play", target: Some("Play"), attributes: &[("loop", "loop")], text: TextRule::Content },
+    ElementRule { twiml: "Pause", target: Some("Wait"), attributes: &[("length", "length")], text: TextRule::Content },
+    ElementRule {
+        twiml: "Gather",
+        target: Some("GetInput"),
+        attributes: &[
+            ("action", "action"),
+            ("method", "method"),
+            ("input", "inputType"),
+            ("timeout", "executionTimeout"),
+            ("numDigits", "numDigits"),
+            ("finishOnKey", "finishOnKey"),
+            ("interdigitTimeout", "digitEndTimeout"),
+            ("speechTimeout", "speechEndTimeout"),
+            ("speechModel", "speechModel"),
+            ("hints", "hints"),
+            ("language", "language"),
+            ("profanityFilter", "profanityFilter"),
+            ("partialResultsCallback", "interimSpeechResultsCallback"),
+            ("partialResultsCallbackMethod", "interimSpeechResultsCallbackMethod"),
+        ],
+        text: TextRule::Content,
+    },
+    ElementRule { twiml: "Redirect", target: Some("Redirect"), attributes: &[("method", "method")], text: TextRule::Content },
+    ElementRule { twiml: "Hangup", target: Some("Hangup"), attributes: &[], text: TextRule::Content },
+    ElementRule {
+        twiml: "Record",
+        target: Some("Record"),
+        attributes: &[
+            ("action", "action"),
+            ("method", "method"),
+            ("timeout", "timeout"),
+            ("finishOnKey", "finishOnKey"),
+            ("maxLength", "maxLength"),
+            ("playBeep", "playBeep"),
+            ("transcribe", "transcriptionType"),
+            ("transcribeCallback", "transcriptionUrl"),
+            ("recordingStatusCallback", "callbackUrl"),
+            ("recordingStatusCallbackMethod", "callbackMethod"),
+        ],
+        text: TextRule::Content,
+    },
+    ElementRule {
+        twiml: "Dial",
+        target: Some("Dial"),
+        attributes: &[("action", "action"), ("method", "method"), ("timeout", "timeout"), ("callerId", "callerId")],
+        text: TextRule::Child("Number"),
+    },
+    ElementRule { twiml: "Number", target: Some("Number"), attributes: &[("sendDigits", "sendDigits")], text: TextRule::Content },
+    ElementRule { twiml: "Sip", target: Some("User"), attributes: &[], text: TextRule::Content },
+    ElementRule {
+        twiml: "Message",
+        target: Some("Message"),
+        attributes: &[("to", "dst"), ("from", "src"), ("statusCallback", "callbackUrl"), ("method", "callbackMethod")],
+        text: TextRule::Content,
+    },
+    ElementRule {
+        twiml: "Sms",
+        target: Some("Message"),
+        attributes: &[("to", "dst"), ("from", "src"), ("action", "callbackUrl"), ("method", "callbackMethod")],
+        text: TextRule::Content,
+    },
+    ElementRule { twiml: "Body", target: None, attributes: &[], text: TextRule::Content },
+];
+
+const BANDWIDTH_RULES: &[ElementRule] = &[
+    ElementRule { twiml: "Response", target: Some("Response"), attributes: &[], text: TextRule::Content },
+    ElementRule {
+        twiml: "Say",
+        target: Some("SpeakSentence"),
+        attributes: &[("voice", "voice"), ("language", "locale")],
+        text: TextRule::Content,
+    },
+    ElementRule { twiml: "Play", target: Some("PlayAudio"), attributes: &[], text: TextRule::Content },
+    ElementRule { twiml: "Pause", target: Some("Pause"), attributes: &[("length", "duration")], text: TextRule::Content },
+    ElementRule {
+        twiml: "Gather",
+        target: Some("Gather"),
+        attributes: &[
+            ("action", "gatherUrl"),
+            ("method", "gatherMethod"),
+            ("timeout", "firstDigitTimeout"),
+            ("numDigits", "maxDigits"),
+            ("finishOnKey", "terminatingDigits"),
+            ("interdigitTimeout", "interDigitTimeout"),
+        ],
+        text: TextRule::Content,
+    },
+    ElementRule {
+        twiml: "Redirect",
+        target: Some("Redirect"),
+        attributes: &[("method", "redirectMethod")],
+        text: TextRule::Attribute("redirectUrl"),
+    },
+    ElementRule { twiml: "Hangup", target: Some("Hangup"), attributes: &[], text: TextRule::Content },
+    ElementRule {
+        twiml: "Record",
+        target: Some("Record"),
+        attributes: &[
+            ("action", "recordCompleteUrl"),
+            ("method", "recordCompleteMethod"),
+            ("timeout", "silenceTimeout"),
+            ("finishOnKey", "terminatingDigits"),
+            ("maxLength", "maxDuration"),
+            ("transcribe", "transcribe"),
+            ("transcribeCallback", "transcriptionAvailableUrl"),
+            ("recordingStatusCallback", "recordingAvailableUrl"),
+            ("recordingStatusCallbackMethod", "recordingAvailableMethod"),
+        ],
+        text: TextRule::Content,
+    },
+    ElementRule {
+        twiml: "Dial",
+        target: Some("Transfer"),
+        attributes: &[
+            ("action", "transferCompleteUrl"),
+            ("method", "transferCompleteMethod"),
+            ("timeout", "callTimeout"),
+            ("callerId", "transferCallerId"),
+        ],
+        text: TextRule::Child("PhoneNumber"),
+    },
+    ElementRule {
+        twiml: "Number",
+        target: Some("PhoneNumber"),
+        attributes: &[("url", "transferAnswerUrl")],
+        text: TextRule::Content,
+    },
+    ElementRule {
+        twiml: "Sip",
+        target: Some("SipUri"),
+        attributes: &[("username", "username"), ("password", "password")],
+        text: TextRule::Content,
+    },
+];
+
+impl fmt::Display for Dialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Dialect::Twilio => write!(f, "Twilio TwiML"),
+            Dialect::SignalWire => write!(f, "SignalWire cXML"),
+            Dialect::Plivo => write!(f, "Plivo XML"),
+            Dialect::Bandwidth => write!(f, "Bandwidth BXML"),
+        }
+    }
+}
+
+/// Error returned when a document uses a feature the target dialect cannot express
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DialectError {
+    /// Dialect the document was being converted to
+    pub dialect: Dialect,
+    /// Name of the offending element
+    pub element: String,
+    /// Why the element cannot be converted
+    pub reason: String,
+}
+
+impl DialectError {
+    fn new(dialect: Dialect, element: &str, reason: impl Into<String>) -> Self {
+        Self {
+            dialect,
+            element: element.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+impl fmt::Display for DialectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<{}> cannot be expressed in {}: {}", self.element, self.dialect, self.reason)
+    }
+}
+
+impl Error for DialectError {}
+
+impl Dialect {
+    /// Convert an element tree into this dialect's XML
+    pub fn convert(self, element: &dyn TwiMLElement) -> Result<XMLElement, DialectError> {
+        match self {
+            Dialect::Twilio | Dialect::SignalWire => self.convert_twiml(element),
+            Dialect::Plivo => self.convert_mapped(PLIVO_RULES, element),
+            Dialect::Bandwidth => self.convert_mapped(BANDWIDTH_RULES, element),
+        }
+    }
+
+    /// Why a TwiML element can't be written in this TwiML-compatible dialect, if it can't
+    pub(crate) fn unsupported(self, name: &str) -> Option<&'static str> {
+        match self {
+            Dialect::SignalWire if TWILIO_ONLY.contains(&name) => Some("Twilio-only element"),
+            Dialect::Twilio if SIGNALWIRE_EXTENSIONS.contains(&name) => Some("SignalWire-only extension"),
+            _ => None,
+        }
+    }
+
+    fn convert_twiml(self, element: &dyn TwiMLElement) -> Result<XMLElement, DialectError> {
+        let node = element.factory();
+
+        if let Some(reason) = self.unsupported(node.name()) {
+            return Err(DialectError::new(self, node.name(), reason));
+        }
+
+        let mut xml = XMLElement::new(node.name());
+        for (key, value) in node.attributes() {
+            xml.add_attribute(key, &sanitize(value, SanitizePolicy::Strip));
+        }
+        for child in node.children() {
+            xml.add_child(self.convert_twiml(child.as_ref())?).unwrap();
+        }
+        if let Some(text) = node.xml_text() {
+            xml.add_text(text).unwrap();
+        }
+
+        Ok(xml)
+    }
+
+    fn convert_mapped(self, rules: &[ElementRule], element: &dyn TwiMLElement) -> Result<XMLElement, DialectError> {
+        let node = element.factory();
+        let rule = find_rule(self, rules, node.name())?;
+        let target = rule
+            .target
+            .ok_or_else(|| DialectError::new(self, node.name(), "only supported inside its parent element"))?;
+
+        let mut xml = XMLElement::new(target);
+        for (key, value) in node.attributes() {
+            let (_, mapped) = rule
+                .attributes
+                .iter()
+                .find(|(twiml, _)| twiml == key)
+                .ok_or_else(|| DialectError::new(self, node.name(), format!("attribute `{}` has no equivalent", key)))?;
+            if let Some(value) = self.map_value(key, &sanitize(value, SanitizePolicy::Strip)) {
+                xml.add_attribute(mapped, &value);
+            }
+        }
+
+        let mut source = node;
+        for child in node.children() {
+            let child_node = child.factory();
+            if find_rule(self, rules, child_node.name())?.target.is_none() {
+                source = child_node;
+            } else {
+                xml.add_child(self.convert_mapped(rules, child.as_ref())?).unwrap();
+            }
+        }
+        if let Some(text) = source.text() {
+            let text = sanitize(text, SanitizePolicy::Strip);
+            // SSML built from Say parts is already escaped
+            let content = if source.is_markup() { text.to_string() } else { escape_text(&text) };
+            match rule.text {
+                TextRule::Content => xml.add_text(content).unwrap(),
+                TextRule::Attribute(name) => {
+                    let plain = source.plain_text().unwrap_or_default();
+                    xml.add_attribute(name, &sanitize(&plain, SanitizePolicy::Strip));
+                }
+                TextRule::Child(name) => {
+                    let mut child = XMLElement::new(name);
+                    child.add_text(content).unwrap();
+                    xml.add_child(child).unwrap();
+                }
+            }
+        }
+
+        Ok(xml)
+    }
+
+    /// Translate an attribute value, returning `None` when the attribute should be omitted
+    fn map_value(self, key: &str, value: &str) -> Option<String> {
+        match (self, key, value) {
+            (Dialect::Plivo, "transcribe", "true") => Some("auto".to_string()),
+            (Dialect::Plivo, "transcribe", _) => None,
+            _ => Some(value.to_string()),
+        }
+    }
+}
+
+fn find_rule<'r>(dialect: Dialect, rules: &'r [ElementRule], name: &str) -> Result<&'r ElementRule, DialectError> {
+    rules
+        .iter()
+        .find(|rule| rule.twiml == name)
+        .ok_or_else(|| DialectError::new(dialect, name, "element has no equivalent"))
+}
+
+impl Response {
+    /// Serialize the document for the given provider dialect
+    pub fn to_dialect_string(&self, dialect: Dialect) -> Result<String, DialectError> {
+        dialect.convert(self).map(render_document)
+    }
+}
+
+#[cfg(all(test, feature = "messaging", feature = "voice"))]
+mod tests {
+    use super::*;
+    use crate::twiml::*;
+
+    #[test]
+    fn test_signalwire_extension() {
+        let response = Response::new().denoise().say(Say::new("Hello"));
+
+        let xml = response.to_dialect_string(Dialect::SignalWire).unwrap();
+        assert!(xml.contains("<Response><Denoise /><Say>Hello</Say></Response>"));
+
+        let error = response.to_dialect_string(Dialect::Twilio).unwrap_err();
+        assert_eq!(error.element, "Denoise");
+        assert_eq!(error.dialect, Dialect::Twilio);
+
+        let relay = Response::new().connect(Connect::new().conversation_relay(ConversationRelay::new("wss://ai.example.com")));
+        let error = relay.to_dialect_string(Dialect::SignalWire).unwrap_err();
+        assert_eq!((error.element.as_str(), error.reason.as_str()), ("ConversationRelay", "Twilio-only element"));
+        assert!(relay.to_dialect_string(Dialect::Twilio).is_ok());
+    }
+
+    #[test]
+    fn test_plivo_mapping() {
+        let response = Response::new()
+            .gather(
+                Gather::new()
+                    .action("/menu")
+                    .input(GatherInput::Dtmf)
+                    .num_digits(1)
+                    .say(Say::new("Press 1").voice("alice"))
+                    .pause(Pause::new().length(2)),
+            )
+            .message(Message::new_empty().to("+15551234567").body(Body::new("Thanks")))
+            .record(Record::new().transcribe(true));
+
+        let xml = response.to_dialect_string(Dialect::Plivo).unwrap();
+        assert!(xml.contains(
+            "<GetInput action=\"/menu\" inputType=\"dtmf\" numDigits=\"1\"><Speak voice=\"alice\">Press 1</Speak><Wait length=\"2\" /></GetInput>"
+        ));
+        assert!(xml.contains("<Message dst=\"+15551234567\">Thanks</Message>"));
+        assert!(xml.contains("<Record transcriptionType=\"auto\" />"));
+
+        let dial = Response::new()
+            .dial(Dial::new_with_number("+15551234567").caller_id("+15557654321"))
+            .dial(Dial::new_empty().number(Number::new("+15550001111").send_digits("ww12")));
+        let xml = dial.to_dialect_string(Dialect::Plivo).unwrap();
+        assert!(xml.contains("<Dial callerId=\"+15557654321\"><Number>+15551234567</Number></Dial>"));
+        assert!(xml.contains("<Dial><Number sendDigits=\"ww12\">+15550001111</Number></Dial>"));
+
+        let say = Say::from_parts([SayPart::from("Fish & chips "), SayPart::Break("1s".to_string())]).unwrap();
+        let xml = Response::new().say(say).to_dialect_string(Dialect::Plivo).unwrap();
+        assert!(xml.contains(r#"<Speak>Fish &amp; chips <break time="1s" /></Speak>"#));
+    }
+
+    #[test]
+    fn test_plivo_unmappable_features() {
+        let enqueue = Response::new().enqueue(Enqueue::new("support"));
+        let error = enqueue.to_dialect_string(Dialect::Plivo).unwrap_err();
+        assert_eq!(error.element, "Enqueue");
+
+        let dial = Response::new().dial(Dial::new_empty().record("record-from-answer"));
+        let error = dial.to_dialect_string(Dialect::Plivo).unwrap_err();
+        assert_eq!(error.element, "Dial");
+        assert!(error.reason.contains("record"));
+    }
+
+    #[test]
+    fn test_bandwidth_mapping() {
+        let response = Response::new()
+            .gather(
+                Gather::new()
+                    .action("/menu")
+                    .num_digits(1)
+                    .say(Say::new("Press 1").language("en-US")),
+            )
+            .dial(Dial::new_with_number("+15551234567").timeout(20))
+            .redirect(Redirect::new("/fallback").method("GET"));
+
+        let xml = response.to_dialect_string(Dialect::Bandwidth).unwrap();
+        assert!(xml.contains(
+            "<Gather gatherUrl=\"/menu\" maxDigits=\"1\"><SpeakSentence locale=\"en-US\">Press 1</SpeakSentence></Gather>"
+        ));
+        assert!(xml.contains("<Transfer callTimeout=\"20\"><PhoneNumber>+15551234567</PhoneNumber></Transfer>"));
+        assert!(xml.contains("<Redirect redirectMethod=\"GET\" redirectUrl=\"/fallback\" />"));
+
+        let sms = Response::new().message(Message::new_with_text("Hi"));
+        assert_eq!(sms.to_dialect_string(Dialect::Bandwidth).unwrap_err().element, "Message");
+    }
+
+    #[test]
+    fn test_twilio_dialect_matches_default_output() {
+        let response = Response::new()
+            .say(Say::new("Hello").voice("alice"))
+            .redirect(Redirect::new("/next"));
+
+        assert_eq!(response.to_dialect_string(Dialect::Twilio).unwrap(), response.to_xml_string());
+    }
+}
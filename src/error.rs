@@ -0,0 +1,114 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::validation::ValidationError;
+
+/// Error building, checking or serializing a document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TwiMLError {
+    /// The XML writer rejected the element tree
+    Serialization(String),
+    /// An attribute has a value Twilio would reject
+    InvalidAttribute {
+        /// Name of the offending element
+        element: String,
+        /// Name of the offending attribute
+        attribute: String,
+        /// Human-readable description of the problem
+        reason: String,
+    },
+    /// An element's content is invalid, such as text that is too long or a missing child
+    InvalidElement {
+        /// Name of the offending element
+        element: String,
+        /// Human-readable description of the problem
+        reason: String,
+    },
+    /// An element is nested inside a parent that doesn't accept it
+    InvalidNesting {
+        /// Name of the parent element
+        parent: String,
+        /// Name of the misplaced child element
+        child: String,
+    },
+    /// Text could not be read as TwiML
+    Parse(String),
+    /// The serialized document is larger than Twilio accepts
+    TooLarge {
+        /// Size of the document in bytes
+        bytes: usize,
+        /// Largest size accepted, in bytes
+        limit: usize,
+    },
+}
+
+impl fmt::Display for TwiMLError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TwiMLError::Serialization(reason) => write!(f, "cannot serialize TwiML: {}", reason),
+            TwiMLError::InvalidAttribute { element, attribute, reason } => {
+                write!(f, "invalid `{}` on <{}>: {}", attribute, element, reason)
+            }
+            TwiMLError::InvalidElement { element, reason } => write!(f, "invalid <{}>: {}", element, reason),
+            TwiMLError::InvalidNesting { parent, child } => write!(f, "<{}> is not allowed inside <{}>", child, parent),
+            TwiMLError::Parse(reason) => write!(f, "cannot parse TwiML: {}", reason),
+            TwiMLError::TooLarge { bytes, limit } => {
+                write!(f, "document is {} bytes, more than the {} Twilio accepts", bytes, limit)
+            }
+        }
+    }
+}
+
+impl Error for TwiMLError {}
+
+impl From<ValidationError> for TwiMLError {
+    fn from(error: ValidationError) -> Self {
+        match (error.attribute, error.child) {
+            (_, Some(child)) => TwiMLError::InvalidNesting {
+                parent: error.element,
+                child,
+            },
+            (Some(attribute), None) => TwiMLError::InvalidAttribute {
+                element: error.element,
+                attribute,
+                reason: error.reason,
+            },
+            (None, None) => TwiMLError::InvalidElement {
+                element: error.element,
+                reason: error.reason,
+            },
+        }
+    }
+}
+
+#[cfg(all(test, feature = "voice"))]
+mod tests {
+    use super::*;
+    use crate::twiml::*;
+    use crate::MAX_DOCUMENT_BYTES;
+
+    #[test]
+    fn test_serialization_errors() {
+        let response = Response::new().say("Hello").text("stray text");
+        let error = response.try_to_xml_string().unwrap_err();
+        assert!(matches!(&error, TwiMLError::Serialization(reason) if reason.starts_with("<Response>")));
+
+        let long = Response::new().say("a".repeat(MAX_DOCUMENT_BYTES));
+        assert!(matches!(long.try_to_xml_string(), Err(TwiMLError::TooLarge { limit: MAX_DOCUMENT_BYTES, .. })));
+        assert!(Response::new().hangup().try_to_xml_string().unwrap().ends_with("<Hangup /></Response>"));
+    }
+
+    #[test]
+    fn test_from_validation_error() {
+        let error: TwiMLError = Response::new().gather(Gather::new().num_digits(0)).validate().unwrap_err().into();
+        assert_eq!(
+            error.to_string(),
+            "invalid `numDigits` on <Gather>: must be an integer of at least 1"
+        );
+        assert!(matches!(error, TwiMLError::InvalidAttribute { ref attribute, .. } if attribute == "numDigits"));
+
+        let nesting: TwiMLError = Response::new().gather(Gather::new().shared(&Shared::new(Hangup::new()))).validate().unwrap_err().into();
+        assert_eq!(nesting, TwiMLError::InvalidNesting { parent: "Gather".to_string(), child: "Hangup".to_string() });
+        assert_eq!(nesting.to_string(), "<Hangup> is not allowed inside <Gather>");
+    }
+}
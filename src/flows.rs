@@ -0,0 +1,493 @@
+//! Multi-step call flows driven by webhook input
+//!
+//! A [`Flow`] is a set of named [`State`]s. Each state renders a document and lists
+//! transitions from webhook input (digits, speech or dial status) to the next state.
+//! [`FlowEngine`] keeps each call's current state in a [`SessionStore`], so a webhook
+//! handler only has to pass the CallSid and the request parameters.
+
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::twiml::Response;
+
+/// Result fields of a webhook request that drive transitions
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Input {
+    /// Keys pressed during a Gather (`Digits`)
+    pub digits: Option<String>,
+    /// Transcribed speech from a Gather (`SpeechResult`)
+    pub speech: Option<String>,
+    /// Outcome of a Dial (`DialCallStatus`), e.g. `completed` or `no-answer`
+    pub dial_status: Option<String>,
+}
+
+impl Input {
+    /// Extract the result fields from webhook form parameters
+    pub fn from_params<K: AsRef<str>, V: AsRef<str>>(params: &[(K, V)]) -> Self {
+        let get = |name: &str| {
+            params
+                .iter()
+                .find(|(key, _)| key.as_ref() == name)
+                .map(|(_, value)| value.as_ref().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        Self {
+            digits: get("Digits"),
+            speech: get("SpeechResult"),
+            dial_status: get("DialCallStatus"),
+        }
+    }
+
+    /// Create input for pressed keys
+    pub fn digits(digits: impl Into<String>) -> Self {
+        Self {
+            digits: Some(digits.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Create input for recognized speech
+    pub fn speech(speech: impl Into<String>) -> Self {
+        Self {
+            speech: Some(speech.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Create input for a dial outcome
+    pub fn dial_status(status: impl Into<String>) -> Self {
+        Self {
+            dial_status: Some(status.into()),
+            ..Self::default()
+        }
+    }
+}
+
+/// Condition under which a state moves to another
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Condition {
+    Digits(String),
+    Speech(String),
+    DialStatus(String),
+}
+
+impl Condition {
+    fn matches(&self, input: &Input) -> bool {
+        match self {
+            Condition::Digits(digits) => input.digits.as_deref() == Some(digits.as_str()),
+            Condition::Speech(phrase) => input
+                .speech
+                .as_deref()
+                .is_some_and(|speech| speech.to_lowercase().contains(&phrase.to_lowercase())),
+            Condition::DialStatus(status) => input.dial_status.as_deref() == Some(status.as_str()),
+        }
+    }
+}
+
+type Render = Box<dyn Fn(&Session) -> Response + Send + Sync>;
+
+/// Named step of a flow
+pub struct State {
+    name: String,
+    render: Render,
+    transitions: Vec<(Condition, String)>,
+    otherwise: Option<String>,
+}
+
+impl State {
+    /// Create a state rendering its document from the session
+    pub fn new(name: impl Into<String>, render: impl Fn(&Session) -> Response + Send + Sync + 'static) -> Self {
+        Self {
+            name: name.into(),
+            render: Box::new(render),
+            transitions: Vec::new(),
+            otherwise: None,
+        }
+    }
+
+    /// Move to `next` when the caller pressed exactly `digits`
+    pub fn on_digits(mut self, digits: impl Into<String>, next: impl Into<String>) -> Self {
+        self.transitions.push((Condition::Digits(digits.into()), next.into()));
+        self
+    }
+
+    /// Move to `next` when the recognized speech contains `phrase`, ignoring case
+    pub fn on_speech(mut self, phrase: impl Into<String>, next: impl Into<String>) -> Self {
+        self.transitions.push((Condition::Speech(phrase.into()), next.into()));
+        self
+    }
+
+    /// Move to `next` when a Dial ended with `status`
+    pub fn on_dial_status(mut self, status: impl Into<String>, next: impl Into<String>) -> Self {
+        self.transitions.push((Condition::DialStatus(status.into()), next.into()));
+        self
+    }
+
+    /// Move to `next` when no other transition matches; without it the state repeats
+    pub fn otherwise(mut self, next: impl Into<String>) -> Self {
+        self.otherwise = Some(next.into());
+        self
+    }
+
+    /// Get the state name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the state has no transitions, ending the flow
+    pub fn is_final(&self) -> bool {
+        self.transitions.is_empty() && self.otherwise.is_none()
+    }
+
+    fn next(&self, input: &Input) -> &str {
+        self.transitions
+            .iter()
+            .find(|(condition, _)| condition.matches(input))
+            .map(|(_, next)| next.as_str())
+            .or(self.otherwise.as_deref())
+            .unwrap_or(&self.name)
+    }
+}
+
+impl fmt::Debug for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("State")
+            .field("name", &self.name)
+            .field("transitions", &self.transitions)
+            .field("otherwise", &self.otherwise)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Set of states with a starting state
+#[derive(Debug)]
+pub struct Flow {
+    start: String,
+    states: HashMap<String, State>,
+}
+
+impl Flow {
+    /// Create a flow starting at the named state
+    pub fn new(start: impl Into<String>) -> Self {
+        Self {
+            start: start.into(),
+            states: HashMap::new(),
+        }
+    }
+
+    /// Add a state, replacing any state with the same name
+    pub fn state(mut self, state: State) -> Self {
+        self.states.insert(state.name.clone(), state);
+        self
+    }
+
+    /// Get a state by name
+    pub fn get(&self, name: &str) -> Option<&State> {
+        self.states.get(name)
+    }
+
+    /// Check that the start state and every transition target exist
+    pub fn check(&self) -> Result<(), FlowError> {
+        if !self.states.contains_key(&self.start) {
+            return Err(FlowError::UnknownState(self.start.clone()));
+        }
+        for state in self.states.values() {
+            let targets = state.transitions.iter().map(|(_, next)| next).chain(&state.otherwise);
+            for target in targets {
+                if !self.states.contains_key(target) {
+                    return Err(FlowError::UnknownState(target.clone()));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Progress of one call through a flow
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    /// Call identifier, normally the CallSid
+    pub id: String,
+    /// Name of the state whose document was last returned
+    pub state: String,
+    /// Input received in each state, keyed by state name
+    pub data: BTreeMap<String, String>,
+}
+
+impl Session {
+    /// Get the digits or speech received while in a state
+    pub fn input(&self, state: &str) -> Option<&str> {
+        self.data.get(state).map(String::as_str)
+    }
+}
+
+/// Error from running a flow
+#[derive(Debug)]
+pub enum FlowError {
+    /// A flow references a state that doesn't exist
+    UnknownState(String),
+    /// The session store failed
+    Store(Box<dyn Error + Send + Sync>),
+}
+
+impl fmt::Display for FlowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlowError::UnknownState(name) => write!(f, "unknown flow state `{}`", name),
+            FlowError::Store(err) => write!(f, "session store failed: {}", err),
+        }
+    }
+}
+
+impl Error for FlowError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FlowError::UnknownState(_) => None,
+            FlowError::Store(err) => Some(err.as_ref()),
+        }
+    }
+}
+
+/// Storage for sessions between webhook requests
+pub trait SessionStore {
+    /// Load the session for a call, if one exists
+    fn load(&self, id: &str) -> Result<Option<Session>, FlowError>;
+
+    /// Create or replace the session for a call
+    fn save(&self, session: &Session) -> Result<(), FlowError>;
+
+    /// Delete the session for a call
+    fn remove(&self, id: &str) -> Result<(), FlowError>;
+}
+
+/// Session store keeping sessions in process memory
+#[derive(Debug, Default)]
+pub struct MemorySessionStore {
+    sessions: Mutex<HashMap<String, Session>>,
+}
+
+impl MemorySessionStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of stored sessions
+    pub fn len(&self) -> usize {
+        self.sessions.lock().unwrap().len()
+    }
+
+    /// Whether no sessions are stored
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl SessionStore for MemorySessionStore {
+    fn load(&self, id: &str) -> Result<Option<Session>, FlowError> {
+        Ok(self.sessions.lock().unwrap().get(id).cloned())
+    }
+
+    fn save(&self, session: &Session) -> Result<(), FlowError> {
+        self.sessions.lock().unwrap().insert(session.id.clone(), session.clone());
+        Ok(())
+    }
+
+    fn remove(&self, id: &str) -> Result<(), FlowError> {
+        self.sessions.lock().unwrap().remove(id);
+        Ok(())
+    }
+}
+
+/// Session store keeping sessions in Redis as JSON, expiring after a time to live
+#[cfg(feature = "redis")]
+#[derive(Debug, Clone)]
+pub struct RedisSessionStore {
+    client: redis::Client,
+    prefix: String,
+    ttl_seconds: u64,
+}
+
+#[cfg(feature = "redis")]
+impl RedisSessionStore {
+    /// Create a store using keys `twiml:session:<id>` that expire after an hour
+    pub fn new(client: redis::Client) -> Self {
+        Self {
+            client,
+            prefix: "twiml:session:".to_string(),
+            ttl_seconds: 3600,
+        }
+    }
+
+    /// Set the key prefix
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Set how long a session lives after its last update
+    pub fn ttl_seconds(mut self, ttl_seconds: u64) -> Self {
+        self.ttl_seconds = ttl_seconds;
+        self
+    }
+
+    fn connection(&self) -> Result<redis::Connection, FlowError> {
+        self.client.get_connection().map_err(|err| FlowError::Store(Box::new(err)))
+    }
+}
+
+#[cfg(feature = "redis")]
+impl SessionStore for RedisSessionStore {
+    fn load(&self, id: &str) -> Result<Option<Session>, FlowError> {
+        use redis::Commands;
+
+        let json: Option<String> = self
+            .connection()?
+            .get(format!("{}{}", self.prefix, id))
+            .map_err(|err| FlowError::Store(Box::new(err)))?;
+        json.map(|json| serde_json::from_str(&json).map_err(|err| FlowError::Store(Box::new(err))))
+            .transpose()
+    }
+
+    fn save(&self, session: &Session) -> Result<(), FlowError> {
+        use redis::Commands;
+
+        let json = serde_json::to_string(session).map_err(|err| FlowError::Store(Box::new(err)))?;
+        self.connection()?
+            .set_ex::<_, _, ()>(format!("{}{}", self.prefix, session.id), json, self.ttl_seconds)
+            .map_err(|err| FlowError::Store(Box::new(err)))
+    }
+
+    fn remove(&self, id: &str) -> Result<(), FlowError> {
+        use redis::Commands;
+
+        self.connection()?
+            .del::<_, ()>(format!("{}{}", self.prefix, id))
+            .map_err(|err| FlowError::Store(Box::new(err)))
+    }
+}
+
+/// Runs a flow for many calls, keeping their progress in a session store
+#[derive(Debug)]
+pub struct FlowEngine<S> {
+    flow: Flow,
+    store: S,
+}
+
+impl<S: SessionStore> FlowEngine<S> {
+    /// Create an engine, checking that every referenced state exists
+    pub fn new(flow: Flow, store: S) -> Result<Self, FlowError> {
+        flow.check()?;
+        Ok(Self { flow, store })
+    }
+
+    /// Get the session store
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Handle a webhook request for a call, returning the document for its next state
+    ///
+    /// The first request for a call renders the start state. Later requests follow the
+    /// current state's transitions for the input. Sessions are removed once a final
+    /// state has been rendered.
+    pub fn handle(&self, id: &str, input: &Input) -> Result<Response, FlowError> {
+        let session = match self.store.load(id)? {
+            Some(mut session) => {
+                let current = self
+                    .flow
+                    .get(&session.state)
+                    .ok_or_else(|| FlowError::UnknownState(session.state.clone()))?;
+                if let Some(value) = input.digits.as_ref().or(input.speech.as_ref()) {
+                    session.data.insert(current.name.clone(), value.clone());
+                }
+                session.state = current.next(input).to_string();
+                session
+            }
+            None => Session {
+                id: id.to_string(),
+                state: self.flow.start.clone(),
+                data: BTreeMap::new(),
+            },
+        };
+
+        let state = self
+            .flow
+            .get(&session.state)
+            .ok_or_else(|| FlowError::UnknownState(session.state.clone()))?;
+        let response = (state.render)(&session);
+        if state.is_final() {
+            self.store.remove(id)?;
+        } else {
+            self.store.save(&session)?;
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(all(test, feature = "voice"))]
+mod tests {
+    use super::*;
+    use crate::twiml::*;
+
+    fn flow() -> Flow {
+        Flow::new("menu")
+            .state(
+                State::new("menu", |_| {
+                    Response::new().gather(Gather::new().num_digits(1).action("/ivr").say("Press 1 for sales"))
+                })
+                .on_digits("1", "sales")
+                .on_speech("sales", "sales")
+                .otherwise("menu"),
+            )
+            .state(
+                State::new("sales", |_| Response::new().dial(Dial::new_with_number("+15550001111").action("/ivr")))
+                    .on_dial_status("no-answer", "voicemail")
+                    .otherwise("goodbye"),
+            )
+            .state(State::new("voicemail", |session: &Session| {
+                let choice = session.input("menu").unwrap_or_default();
+                Response::new().say(format!("You chose {}. Leave a message.", choice)).record(Record::new())
+            }))
+            .state(State::new("goodbye", |_| Response::new().say("Goodbye").hangup()))
+    }
+
+    #[test]
+    fn test_flow_transitions() {
+        let engine = FlowEngine::new(flow(), MemorySessionStore::new()).unwrap();
+
+        let xml = |response: Response| response.to_xml_string();
+        assert!(xml(engine.handle("CA1", &Input::default()).unwrap()).contains("<Gather"));
+        assert!(xml(engine.handle("CA1", &Input::digits("9")).unwrap()).contains("<Gather"));
+        assert!(xml(engine.handle("CA1", &Input::digits("1")).unwrap()).contains("<Dial"));
+        assert_eq!(engine.store().len(), 1);
+
+        let voicemail = xml(engine.handle("CA1", &Input::dial_status("no-answer")).unwrap());
+        assert!(voicemail.contains("<Say>You chose 1. Leave a message.</Say>"));
+        assert!(engine.store().is_empty());
+    }
+
+    #[test]
+    fn test_input_from_params() {
+        let params = [("CallSid", "CA1"), ("SpeechResult", "Sales please"), ("Digits", "")];
+        let input = Input::from_params(&params);
+        assert_eq!(input, Input::speech("Sales please"));
+
+        let engine = FlowEngine::new(flow(), MemorySessionStore::new()).unwrap();
+        engine.handle("CA2", &Input::default()).unwrap();
+        assert!(engine.handle("CA2", &input).unwrap().to_xml_string().contains("<Dial"));
+    }
+
+    #[test]
+    fn test_unknown_states_are_rejected() {
+        let flow = Flow::new("start").state(State::new("start", |_| Response::new()).on_digits("1", "missing"));
+        assert!(matches!(
+            FlowEngine::new(flow, MemorySessionStore::new()),
+            Err(FlowError::UnknownState(name)) if name == "missing"
+        ));
+    }
+}
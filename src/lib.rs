@@ -0,0 +1,67 @@
+mod twiml;
+#[cfg(feature = "voice")]
+mod amd;
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
+mod cache;
+#[cfg(feature = "messaging")]
+pub mod compliance;
+#[cfg(feature = "config")]
+pub mod config;
+mod context;
+mod describe;
+mod diagram;
+mod dialect;
+mod error;
+pub mod flows;
+mod localization;
+pub mod media_streams;
+mod metrics;
+mod ncco;
+pub mod prelude;
+#[cfg(feature = "voice")]
+pub mod presets;
+mod query;
+#[cfg(feature = "voice")]
+mod recording;
+mod sanitize;
+mod serializer;
+#[cfg(feature = "signature")]
+pub mod signature;
+mod sms;
+mod static_twiml;
+#[cfg(feature = "state")]
+pub mod state;
+#[cfg(feature = "tera")]
+pub mod template;
+#[cfg(feature = "test-server")]
+pub mod test_server;
+mod validation;
+#[cfg(feature = "voice")]
+mod variant;
+mod verb;
+mod visit;
+pub mod webhook;
+
+pub use crate::twiml::*;
+#[cfg(feature = "voice")]
+pub use crate::amd::{amd_response, AnsweredBy};
+pub use crate::cache::TwimlCache;
+pub use crate::context::TwimlContext;
+pub use crate::dialect::{Dialect, DialectError};
+pub use crate::error::TwiMLError;
+pub use crate::localization::{say_locale, LocalizedResponse, MessageBundle, MessageCatalog};
+pub use crate::metrics::{DocumentMetrics, DocumentSummary, DEFAULT_WORDS_PER_MINUTE};
+pub use crate::ncco::{NccoConversion, UnsupportedFeature};
+pub use crate::query::select;
+#[cfg(feature = "voice")]
+pub use crate::recording::{RecordingChange, RecordingPolicy, ALL_PARTY_CONSENT_JURISDICTIONS};
+pub use crate::sanitize::{is_xml_char, SanitizePolicy, TextNormalization};
+pub use crate::serializer::{Encoding, Serializer};
+pub use crate::sms::{is_gsm7, sms_segments, split_body, SegmentInfo, SmsEncoding, MAX_BODY_CHARACTERS};
+pub use crate::validation::{ValidationError, ValidationOptions, MAX_DIAL_TIMEOUT, MAX_DIAL_TIME_LIMIT, MAX_HINT_CHARACTERS, MAX_HINT_PHRASES, MAX_CONFERENCE_PARTICIPANTS, MAX_MEDIA_PER_MESSAGE, MAX_PAY_ATTEMPTS, MAX_ROOM_NAME_CHARACTERS, MAX_SAY_CHARACTERS, MAX_SMS_SEGMENTS, MAX_TASK_PRIORITY, MAX_TASK_TIMEOUT};
+#[cfg(feature = "voice")]
+pub use crate::variant::{Variant, VariantPrompt, VariantSet};
+pub use crate::static_twiml::{check_static, check_twiml};
+pub use crate::verb::{Verb, Verbs};
+pub use crate::visit::{walk, ElementPath, PathSegment, Visitor};
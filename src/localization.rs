@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+
+#[cfg(feature = "messaging")]
+use crate::twiml::{Body, Message};
+use crate::twiml::Response;
+#[cfg(feature = "voice")]
+use crate::twiml::Say;
+
+/// Locale Twilio speaks a bare language code in, by language
+const DEFAULT_LOCALES: &[(&str, &str)] = &[
+    ("ca", "ca-ES"),
+    ("cy", "cy-GB"),
+    ("da", "da-DK"),
+    ("de", "de-DE"),
+    ("en", "en-US"),
+    ("es", "es-ES"),
+    ("fi", "fi-FI"),
+    ("fr", "fr-FR"),
+    ("hi", "hi-IN"),
+    ("is", "is-IS"),
+    ("it", "it-IT"),
+    ("ja", "ja-JP"),
+    ("ko", "ko-KR"),
+    ("nb", "nb-NO"),
+    ("nl", "nl-NL"),
+    ("pl", "pl-PL"),
+    ("pt", "pt-BR"),
+    ("ro", "ro-RO"),
+    ("ru", "ru-RU"),
+    ("sv", "sv-SE"),
+    ("tr", "tr-TR"),
+    ("zh", "zh-CN"),
+];
+
+/// Get the full locale such as `de-DE` for a bare language code, which Twilio doesn't
+/// accept in `Say`'s language attribute; full locales and unknown languages are
+/// returned unchanged
+pub fn say_locale(locale: &str) -> &str {
+    DEFAULT_LOCALES
+        .binary_search_by_key(&locale.to_ascii_lowercase().as_str(), |(language, _)| language)
+        .map_or(locale, |index| DEFAULT_LOCALES[index].1)
+}
+
+/// Source of translated prompt text, implemented by message bundles such as Fluent or gettext catalogs
+pub trait MessageCatalog {
+    /// Look up the text of a message for an exact locale
+    fn message(&self, locale: &str, id: &str) -> Option<String>;
+
+    /// Voice to use when speaking text in the given locale
+    fn voice(&self, _locale: &str) -> Option<String> {
+        None
+    }
+}
+
+/// Simple in-memory message catalog
+#[derive(Debug, Clone, Default)]
+pub struct MessageBundle {
+    messages: HashMap<String, HashMap<String, String>>,
+    voices: HashMap<String, String>,
+}
+
+impl MessageBundle {
+    /// Create an empty bundle
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a translated message for a locale
+    pub fn message(mut self, locale: impl Into<String>, id: impl Into<String>, text: impl Into<String>) -> Self {
+        self.messages.entry(locale.into()).or_default().insert(id.into(), text.into());
+        self
+    }
+
+    /// Set the voice used for a locale
+    pub fn voice(mut self, locale: impl Into<String>, voice: impl Into<String>) -> Self {
+        self.voices.insert(locale.into(), voice.into());
+        self
+    }
+}
+
+impl MessageCatalog for MessageBundle {
+    fn message(&self, locale: &str, id: &str) -> Option<String> {
+        self.messages.get(locale).and_then(|messages| messages.get(id)).cloned()
+    }
+
+    fn voice(&self, locale: &str) -> Option<String> {
+        self.voices.get(locale).cloned()
+    }
+}
+
+/// Response builder that emits `Say` and `Body` text translated for the caller's locale
+///
+/// Messages are resolved against the call locale (`fr-CA`), then its language (`fr`),
+/// then the fallback locale. As with gettext, a message missing everywhere is emitted
+/// as its ID so the call still proceeds. Text found under a bare language is spoken in
+/// the call locale when it has that language, and in [`say_locale`] otherwise.
+pub struct LocalizedResponse<'a, C: MessageCatalog + ?Sized> {
+    catalog: &'a C,
+    locale: String,
+    fallback_locale: Option<String>,
+    response: Response,
+}
+
+impl<'a, C: MessageCatalog + ?Sized> LocalizedResponse<'a, C> {
+    /// Create a new localized response for the given call locale
+    pub fn new(catalog: &'a C, locale: impl Into<String>) -> Self {
+        Self {
+            catalog,
+            locale: locale.into(),
+            fallback_locale: None,
+            response: Response::new(),
+        }
+    }
+
+    /// Set the locale used when a message is not translated for the call locale
+    pub fn fallback_locale(mut self, locale: impl Into<String>) -> Self {
+        self.fallback_locale = Some(locale.into());
+        self
+    }
+
+    /// Add a Say element with the translated message
+    #[cfg(feature = "voice")]
+    pub fn say(self, id: &str) -> Self {
+        self.say_with(id, &[])
+    }
+
+    /// Add a Say element with the translated message, substituting `{name}` placeholders
+    #[cfg(feature = "voice")]
+    pub fn say_with(mut self, id: &str, args: &[(&str, &str)]) -> Self {
+        let (locale, text) = self.translate(id, args);
+        let language = match self.locale.split_once('-') {
+            Some((call_language, _)) if call_language.eq_ignore_ascii_case(&locale) => self.locale.as_str(),
+            _ => say_locale(&locale),
+        };
+        let mut say = Say::new(text).language(language);
+        if let Some(voice) = self.catalog.voice(&locale) {
+            say = say.voice(voice);
+        }
+        self.response = self.response.say(say);
+        self
+    }
+
+    /// Add a Message element whose Body is the translated message
+    #[cfg(feature = "messaging")]
+    pub fn message(self, id: &str) -> Self {
+        self.message_with(id, &[])
+    }
+
+    /// Add a Message element whose Body is the translated message, substituting `{name}` placeholders
+    #[cfg(feature = "messaging")]
+    pub fn message_with(mut self, id: &str, args: &[(&str, &str)]) -> Self {
+        let (_, text) = self.translate(id, args);
+        self.response = self.response.message(Message::new_empty().body(Body::new(text)));
+        self
+    }
+
+    /// Add non-localized elements to the underlying response
+    pub fn with_response(mut self, build: impl FnOnce(Response) -> Response) -> Self {
+        self.response = build(self.response);
+        self
+    }
+
+    /// Finish building and return the response
+    pub fn into_response(self) -> Response {
+        self.response
+    }
+
+    /// Resolve a message, returning the locale it was found in along with the formatted text
+    pub fn translate(&self, id: &str, args: &[(&str, &str)]) -> (String, String) {
+        let (locale, mut text) = self
+            .candidate_locales()
+            .into_iter()
+            .find_map(|locale| self.catalog.message(&locale, id).map(|text| (locale, text)))
+            .unwrap_or_else(|| (self.locale.clone(), id.to_string()));
+
+        for (name, value) in args {
+            text = text.replace(&format!("{{{}}}", name), value);
+        }
+
+        (locale, text)
+    }
+
+    fn candidate_locales(&self) -> Vec<String> {
+        let mut locales = vec![self.locale.clone()];
+        if let Some((language, _)) = self.locale.split_once('-') {
+            locales.push(language.to_string());
+        }
+        if let Some(fallback) = &self.fallback_locale {
+            locales.push(fallback.clone());
+        }
+        locales
+    }
+}
+
+#[cfg(all(test, feature = "messaging", feature = "voice"))]
+mod tests {
+    use super::*;
+    use crate::twiml::ToXmlString;
+
+    fn bundle() -> MessageBundle {
+        MessageBundle::new()
+            .message("en-US", "welcome", "Welcome, {name}")
+            .message("fr", "welcome", "Bienvenue, {name}")
+            .message("en-US", "goodbye", "Goodbye")
+            .voice("fr", "Polly.Celine")
+    }
+
+    #[test]
+    fn test_localized_say_uses_language_fallback() {
+        let bundle = bundle();
+        let xml = LocalizedResponse::new(&bundle, "fr-CA")
+            .fallback_locale("en-US")
+            .say_with("welcome", &[("name", "Marie")])
+            .say("goodbye")
+            .into_response()
+            .to_xml_string();
+
+        assert!(xml.contains("<Say language=\"fr-CA\" voice=\"Polly.Celine\">Bienvenue, Marie</Say>"));
+        assert!(xml.contains("<Say language=\"en-US\">Goodbye</Say>"));
+
+        let bare = MessageBundle::new().message("en-US", "welcome", "Welcome").message("fr", "welcome", "Bienvenue");
+        let xml = LocalizedResponse::new(&bare, "de").fallback_locale("fr").say("welcome").into_response().to_xml_string();
+        assert!(xml.contains("<Say language=\"fr-FR\">Bienvenue</Say>"));
+    }
+
+    #[test]
+    fn test_say_locale() {
+        assert!(DEFAULT_LOCALES.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert_eq!(say_locale("de"), "de-DE");
+        assert_eq!(say_locale("FR"), "fr-FR");
+        assert_eq!(say_locale("fr-CA"), "fr-CA");
+        assert_eq!(say_locale("xx"), "xx");
+    }
+
+    #[test]
+    fn test_missing_message_emits_id() {
+        let bundle = bundle();
+        let xml = LocalizedResponse::new(&bundle, "de-DE")
+            .message("unknown-id")
+            .with_response(|response| response.hangup())
+            .into_response()
+            .to_xml_string();
+
+        assert!(xml.contains("<Body>unknown-id</Body>"));
+        assert!(xml.contains("<Hangup />"));
+    }
+}
@@ -1,6 +1,4 @@
-mod twiml;
-
-use crate::twiml::{Say, Response, Gather, Dial, Conference, Number, Record, Play, Client, Pause, Redirect, ToXmlString, Message, Body};
+use twiml::prelude::*;
 
 fn main() {
     // Example 1: Simple voice response
@@ -19,7 +17,7 @@ fn main() {
             Gather::new()
                 .action("/process-speech")
                 .method("POST")
-                .input("speech")
+                .input(GatherInput::Speech)
                 .language("en-US")
                 .hints("support, sales, billing")
                 .say(Say::new("You can say support, sales, or billing"))
@@ -35,7 +33,7 @@ fn main() {
             Gather::new()
                 .action("/menu-selection")
                 .method("POST")
-                .num_digits("1")
+                .num_digits(1)
                 .timeout(10)
                 .say(
                     Say::new("For sales, press 1. For support, press 2. For billing, press 3.")
@@ -51,7 +49,7 @@ fn main() {
     let response4 = Response::new()
         .say(Say::new("You are about to join the conference."))
         .dial(
-            Dial::new(None::<String>)
+            Dial::new_empty()
                 .conference(
                     Conference::new("Room123")
                         .muted(false)
@@ -126,7 +124,7 @@ fn main() {
                 .action("/process-selection")
                 .method("POST")
                 .timeout(10)
-                .num_digits("1")
+                .num_digits(1)
                 .say(Say::new("Press a number to continue"))
                 .play(Play::new("https://api.example.com/sounds/options.mp3"))
                 .pause(Pause::new().length(1))
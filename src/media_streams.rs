@@ -0,0 +1,214 @@
+//! Messages exchanged over a bidirectional Media Streams WebSocket
+//!
+//! A `<Connect><Stream>` opens a WebSocket on which Twilio sends call audio and the
+//! application can send audio back. The builders here produce the JSON text frames the
+//! application sends.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde_json::{json, Value};
+
+/// Message sent to Twilio over a bidirectional stream
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutboundMessage {
+    /// Audio to play to the caller
+    Media {
+        /// Stream the audio belongs to
+        stream_sid: String,
+        /// Base64-encoded 8 kHz µ-law audio
+        payload: String,
+    },
+    /// Marker Twilio echoes back once all audio sent before it has played
+    Mark {
+        /// Stream the marker belongs to
+        stream_sid: String,
+        /// Name returned in the echoed mark event
+        name: String,
+    },
+    /// Discard audio that has been sent but not yet played, e.g. when the caller barges in
+    Clear {
+        /// Stream whose buffer is cleared
+        stream_sid: String,
+    },
+}
+
+impl OutboundMessage {
+    /// Create a media message from raw 8 kHz µ-law samples
+    pub fn media(stream_sid: impl Into<String>, mulaw: &[u8]) -> Self {
+        Self::media_base64(stream_sid, STANDARD.encode(mulaw))
+    }
+
+    /// Create a media message from an already Base64-encoded µ-law payload
+    pub fn media_base64(stream_sid: impl Into<String>, payload: impl Into<String>) -> Self {
+        OutboundMessage::Media {
+            stream_sid: stream_sid.into(),
+            payload: payload.into(),
+        }
+    }
+
+    /// Create a media message from 16-bit PCM samples at 8 kHz
+    #[cfg(feature = "audio")]
+    pub fn media_pcm(stream_sid: impl Into<String>, pcm: &[i16]) -> Self {
+        Self::media(stream_sid, &pcm_to_mulaw(pcm))
+    }
+
+    /// Create a mark message
+    pub fn mark(stream_sid: impl Into<String>, name: impl Into<String>) -> Self {
+        OutboundMessage::Mark {
+            stream_sid: stream_sid.into(),
+            name: name.into(),
+        }
+    }
+
+    /// Create a clear message
+    pub fn clear(stream_sid: impl Into<String>) -> Self {
+        OutboundMessage::Clear {
+            stream_sid: stream_sid.into(),
+        }
+    }
+
+    /// Get the `event` name of the message
+    pub fn event(&self) -> &'static str {
+        match self {
+            OutboundMessage::Media { .. } => "media",
+            OutboundMessage::Mark { .. } => "mark",
+            OutboundMessage::Clear { .. } => "clear",
+        }
+    }
+
+    /// Build the message as a JSON value
+    pub fn to_value(&self) -> Value {
+        match self {
+            OutboundMessage::Media { stream_sid, payload } => json!({
+                "event": "media",
+                "streamSid": stream_sid,
+                "media": { "payload": payload },
+            }),
+            OutboundMessage::Mark { stream_sid, name } => json!({
+                "event": "mark",
+                "streamSid": stream_sid,
+                "mark": { "name": name },
+            }),
+            OutboundMessage::Clear { stream_sid } => json!({
+                "event": "clear",
+                "streamSid": stream_sid,
+            }),
+        }
+    }
+
+    /// Serialize the message as a WebSocket text frame
+    pub fn to_json(&self) -> String {
+        self.to_value().to_string()
+    }
+}
+
+/// Bias added before encoding so that every segment has a leading one bit
+#[cfg(feature = "audio")]
+const MULAW_BIAS: i32 = 0x84;
+
+/// Largest magnitude that can be encoded once the bias is added
+#[cfg(feature = "audio")]
+const MULAW_CLIP: i32 = 32635;
+
+/// Decode one G.711 µ-law byte to a 16-bit PCM sample
+#[cfg(feature = "audio")]
+pub fn mulaw_decode(byte: u8) -> i16 {
+    let byte = !byte;
+    let exponent = (byte >> 4) & 0x07;
+    let mantissa = (byte & 0x0f) as i32;
+    let magnitude = (((mantissa << 3) + MULAW_BIAS) << exponent) - MULAW_BIAS;
+    if byte & 0x80 != 0 { -magnitude as i16 } else { magnitude as i16 }
+}
+
+/// Encode one 16-bit PCM sample as a G.711 µ-law byte
+#[cfg(feature = "audio")]
+pub fn mulaw_encode(sample: i16) -> u8 {
+    let sign = if sample < 0 { 0x80 } else { 0x00 };
+    let magnitude = (sample as i32).abs().min(MULAW_CLIP) + MULAW_BIAS;
+    // The highest set bit is between 7 and 14, giving segments 0 to 7
+    let exponent = (31 - magnitude.leading_zeros() as i32) - 7;
+    let mantissa = (magnitude >> (exponent + 3)) & 0x0f;
+    !(sign | (exponent << 4) as u8 | mantissa as u8)
+}
+
+/// Decode µ-law audio, as carried in stream payloads, to 16-bit PCM
+#[cfg(feature = "audio")]
+pub fn mulaw_to_pcm(mulaw: &[u8]) -> Vec<i16> {
+    mulaw.iter().map(|&byte| mulaw_decode(byte)).collect()
+}
+
+/// Encode 16-bit PCM audio as µ-law
+#[cfg(feature = "audio")]
+pub fn pcm_to_mulaw(pcm: &[i16]) -> Vec<u8> {
+    pcm.iter().map(|&sample| mulaw_encode(sample)).collect()
+}
+
+/// Resample 8 kHz PCM to 16 kHz by linear interpolation
+#[cfg(feature = "audio")]
+pub fn upsample_8k_to_16k(pcm: &[i16]) -> Vec<i16> {
+    let mut output = Vec::with_capacity(pcm.len() * 2);
+    for (index, &sample) in pcm.iter().enumerate() {
+        let next = pcm.get(index + 1).copied().unwrap_or(sample);
+        output.push(sample);
+        output.push(((sample as i32 + next as i32) / 2) as i16);
+    }
+    output
+}
+
+/// Resample 16 kHz PCM to 8 kHz by averaging sample pairs
+#[cfg(feature = "audio")]
+pub fn downsample_16k_to_8k(pcm: &[i16]) -> Vec<i16> {
+    pcm.chunks(2)
+        .map(|pair| (pair.iter().map(|&sample| sample as i32).sum::<i32>() / pair.len() as i32) as i16)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_outbound_messages() {
+        let media = OutboundMessage::media("MZ1", &[0xff, 0x7f, 0x00]);
+        assert_eq!(
+            media.to_value(),
+            json!({ "event": "media", "streamSid": "MZ1", "media": { "payload": "/38A" } })
+        );
+        assert_eq!(
+            OutboundMessage::mark("MZ1", "greeting-done").to_json(),
+            r#"{"event":"mark","mark":{"name":"greeting-done"},"streamSid":"MZ1"}"#
+        );
+        assert_eq!(OutboundMessage::clear("MZ1").to_value(), json!({ "event": "clear", "streamSid": "MZ1" }));
+        assert_eq!(OutboundMessage::clear("MZ1").event(), "clear");
+    }
+
+    #[cfg(feature = "audio")]
+    #[test]
+    fn test_mulaw_conversion() {
+        assert_eq!(mulaw_decode(0xff), 0);
+        assert_eq!(mulaw_decode(0x00), -32124);
+        assert_eq!(mulaw_decode(0x80), 32124);
+        assert_eq!(mulaw_encode(0), 0xff);
+        assert_eq!(mulaw_encode(i16::MIN), 0x00);
+
+        // Every code decodes to a sample that encodes back to the same code, except the
+        // duplicate negative zero
+        for byte in 0..=255u8 {
+            let expected = if byte == 0x7f { 0xff } else { byte };
+            assert_eq!(mulaw_encode(mulaw_decode(byte)), expected);
+        }
+
+        let pcm = [0, 1000, -1000, 20000];
+        let decoded = mulaw_to_pcm(&pcm_to_mulaw(&pcm));
+        assert!(pcm.iter().zip(&decoded).all(|(a, b)| (a - b).abs() <= (*a as i32).abs() as i16 / 16 + 8));
+    }
+
+    #[cfg(feature = "audio")]
+    #[test]
+    fn test_resampling() {
+        assert_eq!(upsample_8k_to_16k(&[0, 100, 50]), vec![0, 50, 100, 75, 50, 50]);
+        assert_eq!(downsample_16k_to_8k(&[0, 50, 100, 75, 50]), vec![25, 87, 50]);
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::serializer::Serializer;
+use crate::twiml::{ElementFactory, Response, TwiMLElement};
+
+/// Typical text-to-speech speaking rate used by [`Response::metrics`]
+pub const DEFAULT_WORDS_PER_MINUTE: u32 = 150;
+
+/// Length Twilio uses for a `Pause` without a `length` attribute
+const DEFAULT_PAUSE_SECONDS: u64 = 1;
+
+/// Size and duration figures for a TwiML document
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DocumentMetrics {
+    /// Number of elements per element name, excluding the root
+    pub verb_counts: BTreeMap<String, usize>,
+    /// Total number of characters in `Say` text, counting loops once
+    pub say_characters: usize,
+    /// Total number of words spoken, including loop repetitions
+    pub say_words: usize,
+    /// Estimated time spent speaking `Say` text and waiting in `Pause`
+    pub estimated_duration: Duration,
+}
+
+impl DocumentMetrics {
+    /// Number of elements with the given name
+    pub fn count(&self, name: &str) -> usize {
+        self.verb_counts.get(name).copied().unwrap_or(0)
+    }
+
+    fn collect(&mut self, element: &dyn TwiMLElement, words_per_minute: u32) {
+        let node = element.factory();
+
+        match node.name() {
+            "Say" => {
+                let text = node.plain_text().unwrap_or_default();
+                let words = text.split_whitespace().count() * repetitions(element);
+                self.say_characters += text.chars().count();
+                self.say_words += words;
+                self.estimated_duration += Duration::from_secs_f64(words as f64 * 60.0 / words_per_minute as f64);
+            }
+            "Pause" => {
+                let seconds = node
+                    .attribute("length")
+                    .and_then(|length| length.parse().ok())
+                    .unwrap_or(DEFAULT_PAUSE_SECONDS);
+                self.estimated_duration += Duration::from_secs(seconds);
+            }
+            _ => {}
+        }
+
+        for child in node.children() {
+            *self.verb_counts.entry(child.factory().name().to_string()).or_insert(0) += 1;
+            self.collect(child.as_ref(), words_per_minute);
+        }
+    }
+}
+
+/// Attributes naming a URL Twilio sends the call or its events to
+const TARGET_ATTRIBUTES: &[&str] = &["action", "url", "waitUrl", "statusCallback"];
+
+/// Shape of a document for structured logs: its verbs, where it sends the call and its
+/// size, without any spoken or message text
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DocumentSummary {
+    /// Names of the top-level verbs, in order
+    pub verbs: Vec<String>,
+    /// Target URLs of actions, callbacks and redirects, without query strings
+    pub urls: Vec<String>,
+    /// Number of elements, including the root
+    pub elements: usize,
+    /// Size of the serialized document in bytes, or `None` if it can't be serialized
+    pub bytes: Option<usize>,
+}
+
+impl DocumentSummary {
+    fn collect(&mut self, node: &ElementFactory) {
+        self.elements += 1;
+        let targets = node.attributes().iter().filter(|(key, _)| TARGET_ATTRIBUTES.contains(&key.as_str()));
+        let redirect = (node.name() == "Redirect").then(|| node.text()).flatten();
+        for url in targets.map(|(_, value)| value.as_str()).chain(redirect) {
+            self.urls.push(url.split(['?', '#']).next().unwrap_or_default().to_string());
+        }
+        for child in node.children() {
+            self.collect(child.factory());
+        }
+    }
+}
+
+/// Number of times an element is played; an infinite loop (`0`) is estimated as one pass
+fn repetitions(element: &dyn TwiMLElement) -> usize {
+    match element.factory().attribute("loop").and_then(|l| l.parse().ok()) {
+        Some(0) | None => 1,
+        Some(loops) => loops,
+    }
+}
+
+impl Response {
+    /// Compute document metrics using [`DEFAULT_WORDS_PER_MINUTE`]
+    pub fn metrics(&self) -> DocumentMetrics {
+        self.metrics_with_rate(DEFAULT_WORDS_PER_MINUTE)
+    }
+
+    /// Summarize the document for structured logs, without its text
+    pub fn summary(&self) -> DocumentSummary {
+        let mut summary = DocumentSummary {
+            verbs: self.factory().children().iter().map(|child| child.factory().name().to_string()).collect(),
+            bytes: Serializer::new().size_limit(None).to_string(self).ok().map(|xml| xml.len()),
+            ..DocumentSummary::default()
+        };
+        summary.collect(self.factory());
+        summary
+    }
+
+    /// Emit the document's [`summary`](Self::summary) as an info-level `tracing` event
+    /// with `verbs`, `urls`, `elements` and `bytes` fields, just before returning it
+    #[cfg(feature = "tracing")]
+    pub fn log_summary(&self) {
+        let summary = self.summary();
+        tracing::info!(
+            verbs = %summary.verbs.join(","),
+            urls = %summary.urls.join(","),
+            elements = summary.elements,
+            bytes = summary.bytes,
+            "returning TwiML"
+        );
+    }
+
+    /// Compute document metrics with a custom speaking rate in words per minute
+    pub fn metrics_with_rate(&self, words_per_minute: u32) -> DocumentMetrics {
+        let mut metrics = DocumentMetrics::default();
+        metrics.collect(self, words_per_minute.max(1));
+        metrics
+    }
+}
+
+#[cfg(all(test, feature = "voice"))]
+mod tests {
+    use std::time::Duration;
+
+    use crate::twiml::*;
+
+    #[test]
+    fn test_metrics_counts_and_duration() {
+        let response = Response::new()
+            .say(Say::new("Welcome to our service"))
+            .gather(
+                Gather::new()
+                    .say(Say::new("Press one for sales").loop_times(2))
+                    .pause(Pause::new().length(3)),
+            )
+            .hangup();
+
+        let metrics = response.metrics_with_rate(60);
+        assert_eq!(metrics.count("Say"), 2);
+        assert_eq!(metrics.count("Gather"), 1);
+        assert_eq!(metrics.count("Hangup"), 1);
+        assert_eq!(metrics.count("Response"), 0);
+        assert_eq!(metrics.say_characters, 22 + 19);
+        assert_eq!(metrics.say_words, 4 + 8);
+        // 12 words at one word per second plus a three second pause
+        assert_eq!(metrics.estimated_duration, Duration::from_secs(15));
+    }
+
+    #[test]
+    fn test_summary() {
+        let response = Response::new()
+            .gather(Gather::new().action("/menu?session=abc").say("Your balance is $12"))
+            .redirect(Redirect::new("https://ivr.example.com/next#retry"));
+        let summary = response.summary();
+        assert_eq!(summary.verbs, ["Gather", "Redirect"]);
+        assert_eq!(summary.urls, ["/menu", "https://ivr.example.com/next"]);
+        assert_eq!(summary.elements, 4);
+        assert_eq!(summary.bytes, Some(response.to_xml_string().len()));
+        assert!(!format!("{:?}", summary).contains("balance"));
+    }
+}
@@ -0,0 +1,330 @@
+use serde_json::{json, Map, Value};
+
+use crate::twiml::{ElementFactory, Response, TwiMLElement};
+
+/// Feature of a TwiML document that has no NCCO equivalent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedFeature {
+    /// Name of the element the feature belongs to
+    pub element: String,
+    /// Attribute name, or `None` when the whole element was dropped
+    pub attribute: Option<String>,
+    /// Why the feature was dropped
+    pub reason: String,
+}
+
+/// Result of converting a document to a Vonage Voice API NCCO
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NccoConversion {
+    /// NCCO actions in call order
+    pub actions: Vec<Value>,
+    /// Features that were dropped during conversion
+    pub unsupported: Vec<UnsupportedFeature>,
+}
+
+impl NccoConversion {
+    /// Whether every element and attribute was converted
+    pub fn is_complete(&self) -> bool {
+        self.unsupported.is_empty()
+    }
+
+    /// Serialize the actions as an NCCO JSON array
+    pub fn to_json(&self) -> String {
+        Value::Array(self.actions.clone()).to_string()
+    }
+
+    fn element(&mut self, node: &ElementFactory, reason: &str) {
+        self.unsupported.push(UnsupportedFeature {
+            element: node.name().to_string(),
+            attribute: None,
+            reason: reason.to_string(),
+        });
+    }
+
+    /// Report every attribute of `node` not listed in `handled`
+    fn attributes(&mut self, node: &ElementFactory, handled: &[&str]) {
+        for (key, _) in node.attributes() {
+            if !handled.contains(&key.as_str()) {
+                self.unsupported.push(UnsupportedFeature {
+                    element: node.name().to_string(),
+                    attribute: Some(key.clone()),
+                    reason: "no NCCO equivalent".to_string(),
+                });
+            }
+        }
+    }
+
+    fn convert(&mut self, element: &dyn TwiMLElement, barge_in: bool) {
+        let node = element.factory();
+        match node.name() {
+            "Say" => self.talk(node, barge_in),
+            "Play" => self.stream(node, barge_in),
+            "Pause" => {
+                self.attributes(node, &["length"]);
+                let seconds = node.attribute("length").unwrap_or("1");
+                self.actions.push(json!({
+                    "action": "talk",
+                    "text": format!("<speak><break time=\"{}s\"/></speak>", seconds),
+                }));
+            }
+            "Gather" => self.input(node),
+            "Dial" => self.connect(node),
+            "Record" => self.record(node),
+            _ => self.element(node, "no NCCO equivalent"),
+        }
+    }
+
+    fn talk(&mut self, node: &ElementFactory, barge_in: bool) {
+        self.attributes(node, &["language", "loop"]);
+        let mut action = action("talk");
+        action.insert("text".into(), json!(node.plain_text().unwrap_or_default()));
+        if let Some(language) = node.attribute("language") {
+            action.insert("language".into(), json!(language));
+        }
+        insert_number(&mut action, "loop", node.attribute("loop"));
+        if barge_in {
+            action.insert("bargeIn".into(), json!(true));
+        }
+        self.actions.push(Value::Object(action));
+    }
+
+    fn stream(&mut self, node: &ElementFactory, barge_in: bool) {
+        self.attributes(node, &["loop"]);
+        let mut action = action("stream");
+        action.insert("streamUrl".into(), json!([node.text().unwrap_or_default()]));
+        insert_number(&mut action, "loop", node.attribute("loop"));
+        if barge_in {
+            action.insert("bargeIn".into(), json!(true));
+        }
+        self.actions.push(Value::Object(action));
+    }
+
+    fn input(&mut self, node: &ElementFactory) {
+        self.attributes(
+            node,
+            &["action", "method", "input", "numDigits", "timeout", "finishOnKey", "language", "hints"],
+        );
+        for child in node.children() {
+            self.convert(child.as_ref(), true);
+        }
+
+        let input = node.attribute("input").unwrap_or("dtmf");
+        let types: Vec<&str> = input.split_whitespace().collect();
+        let mut action = action("input");
+        action.insert("type".into(), json!(types));
+
+        if types.contains(&"dtmf") {
+            let mut dtmf = Map::new();
+            insert_number(&mut dtmf, "maxDigits", node.attribute("numDigits"));
+            insert_number(&mut dtmf, "timeOut", node.attribute("timeout"));
+            if node.attribute("finishOnKey") == Some("#") {
+                dtmf.insert("submitOnHash".into(), json!(true));
+            }
+            action.insert("dtmf".into(), Value::Object(dtmf));
+        }
+        if types.contains(&"speech") {
+            let mut speech = Map::new();
+            if let Some(language) = node.attribute("language") {
+                speech.insert("language".into(), json!(language));
+            }
+            if let Some(hints) = node.attribute("hints") {
+                let context: Vec<&str> = hints.split(',').map(str::trim).filter(|h| !h.is_empty()).collect();
+                speech.insert("context".into(), json!(context));
+            }
+            action.insert("speech".into(), Value::Object(speech));
+        }
+        insert_event_url(&mut action, node);
+        self.actions.push(Value::Object(action));
+    }
+
+    fn connect(&mut self, node: &ElementFactory) {
+        self.attributes(node, &["action", "method", "timeout", "callerId"]);
+
+        let mut endpoints = Vec::new();
+        if let Some(number) = node.text() {
+            endpoints.push(json!({ "type": "phone", "number": number.trim_start_matches('+') }));
+        }
+        for child in node.children() {
+            let noun = child.factory();
+            match noun.name() {
+                "Number" => {
+                    self.attributes(noun, &[]);
+                    let number = noun.text().unwrap_or_default().trim_start_matches('+');
+                    endpoints.push(json!({ "type": "phone", "number": number }));
+                }
+                "Client" => {
+                    self.attributes(noun, &[]);
+                    let user = noun.text().or_else(|| noun.child_text("Identity")).unwrap_or_default();
+                    endpoints.push(json!({ "type": "app", "user": user }));
+                }
+                "Sip" => {
+                    self.attributes(noun, &[]);
+                    endpoints.push(json!({ "type": "sip", "uri": noun.text().unwrap_or_default() }));
+                }
+                "Conference" => self.conversation(noun),
+                _ => self.element(noun, "no NCCO endpoint equivalent"),
+            }
+        }
+
+        if endpoints.is_empty() {
+            return;
+        }
+        let mut action = action("connect");
+        action.insert("endpoint".into(), json!(endpoints));
+        if let Some(caller_id) = node.attribute("callerId") {
+            action.insert("from".into(), json!(caller_id.trim_start_matches('+')));
+        }
+        insert_number(&mut action, "timeout", node.attribute("timeout"));
+        insert_event_url(&mut action, node);
+        self.actions.push(Value::Object(action));
+    }
+
+    fn conversation(&mut self, node: &ElementFactory) {
+        self.attributes(node, &["startConferenceOnEnter", "endConferenceOnExit", "record"]);
+        let mut action = action("conversation");
+        action.insert("name".into(), json!(node.text().unwrap_or_default()));
+        if let Some(start) = node.attribute("startConferenceOnEnter") {
+            action.insert("startOnEnter".into(), json!(start == "true"));
+        }
+        if let Some(end) = node.attribute("endConferenceOnExit") {
+            action.insert("endOnExit".into(), json!(end == "true"));
+        }
+        if let Some(record) = node.attribute("record") {
+            action.insert("record".into(), json!(record == "record-from-start"));
+        }
+        self.actions.push(Value::Object(action));
+    }
+
+    fn record(&mut self, node: &ElementFactory) {
+        self.attributes(
+            node,
+            &["action", "method", "timeout", "finishOnKey", "playBeep", "transcribe", "transcribeCallback"],
+        );
+        let mut action = action("record");
+        insert_number(&mut action, "timeOut", node.attribute("timeout"));
+        if let Some(key) = node.attribute("finishOnKey").and_then(|keys| keys.chars().next()) {
+            action.insert("endOnKey".into(), json!(key.to_string()));
+        }
+        if let Some(beep) = node.attribute("playBeep") {
+            action.insert("beepStart".into(), json!(beep == "true"));
+        }
+        if node.attribute("transcribe") == Some("true") {
+            let mut transcription = Map::new();
+            if let Some(url) = node.attribute("transcribeCallback") {
+                transcription.insert("eventUrl".into(), json!([url]));
+            }
+            action.insert("transcription".into(), Value::Object(transcription));
+        }
+        insert_event_url(&mut action, node);
+        self.actions.push(Value::Object(action));
+    }
+}
+
+fn action(name: &str) -> Map<String, Value> {
+    let mut action = Map::new();
+    action.insert("action".into(), json!(name));
+    action
+}
+
+/// Insert a numeric attribute value, falling back to the raw string if it isn't a number
+fn insert_number(map: &mut Map<String, Value>, key: &str, value: Option<&str>) {
+    if let Some(value) = value {
+        let value = value.parse::<u64>().map(Value::from).unwrap_or_else(|_| json!(value));
+        map.insert(key.into(), value);
+    }
+}
+
+fn insert_event_url(map: &mut Map<String, Value>, node: &ElementFactory) {
+    if let Some(url) = node.attribute("action") {
+        map.insert("eventUrl".into(), json!([url]));
+        if let Some(method) = node.attribute("method") {
+            map.insert("eventMethod".into(), json!(method));
+        }
+    }
+}
+
+impl Response {
+    /// Convert the document to a Vonage Voice API NCCO
+    ///
+    /// Elements and attributes that have no NCCO equivalent are skipped and listed in
+    /// [`NccoConversion::unsupported`]. Child elements of `Gather` become `talk`/`stream`
+    /// actions with `bargeIn` enabled, placed before the `input` action.
+    pub fn to_ncco(&self) -> NccoConversion {
+        let mut conversion = NccoConversion::default();
+        let children = self.factory().children();
+
+        for (index, child) in children.iter().enumerate() {
+            if child.factory().name() == "Hangup" {
+                // An NCCO ends the call after its last action, so only a trailing Hangup is implicit
+                if index + 1 < children.len() {
+                    conversion.element(child.factory(), "NCCO cannot end the call before its last action");
+                }
+                continue;
+            }
+            conversion.convert(child.as_ref(), false);
+        }
+
+        conversion
+    }
+}
+
+#[cfg(all(test, feature = "voice"))]
+mod tests {
+    use serde_json::json;
+
+    use crate::twiml::*;
+
+    #[test]
+    fn test_ncco_conversion() {
+        let response = Response::new()
+            .say(Say::new("Welcome").language("en-US"))
+            .gather(
+                Gather::new()
+                    .action("/menu")
+                    .method("POST")
+                    .num_digits(1)
+                    .finish_on_key("#")
+                    .say(Say::new("Press 1")),
+            )
+            .dial(Dial::new_empty().caller_id("+15550001111").number(Number::new("+15551234567")))
+            .hangup();
+
+        let ncco = response.to_ncco();
+        assert!(ncco.is_complete());
+        assert_eq!(
+            ncco.actions,
+            vec![
+                json!({ "action": "talk", "text": "Welcome", "language": "en-US" }),
+                json!({ "action": "talk", "text": "Press 1", "bargeIn": true }),
+                json!({
+                    "action": "input",
+                    "type": ["dtmf"],
+                    "dtmf": { "maxDigits": 1, "submitOnHash": true },
+                    "eventUrl": ["/menu"],
+                    "eventMethod": "POST",
+                }),
+                json!({
+                    "action": "connect",
+                    "endpoint": [{ "type": "phone", "number": "15551234567" }],
+                    "from": "15550001111",
+                }),
+            ]
+        );
+        assert!(ncco.to_json().starts_with("[{\"action\":\"talk\""));
+    }
+
+    #[test]
+    fn test_ncco_reports_unsupported_features() {
+        let response = Response::new()
+            .say(Say::new("Hello").voice("alice"))
+            .redirect(Redirect::new("/next"));
+
+        let ncco = response.to_ncco();
+        assert_eq!(ncco.actions.len(), 1);
+        assert_eq!(ncco.unsupported.len(), 2);
+        assert_eq!(ncco.unsupported[0].element, "Say");
+        assert_eq!(ncco.unsupported[0].attribute.as_deref(), Some("voice"));
+        assert_eq!(ncco.unsupported[1].element, "Redirect");
+        assert_eq!(ncco.unsupported[1].attribute, None);
+    }
+}
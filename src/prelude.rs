@@ -0,0 +1,24 @@
+//! The types most handlers need, in one import
+//!
+//! ```ignore
+//! use twiml::prelude::*;
+//! ```
+
+pub use crate::error::TwiMLError;
+pub use crate::twiml::{Fragment, Redirect, Response, Shared, ToXmlString, TwiMLElement, UrlValue};
+pub use crate::validation::ValidationOptions;
+
+#[cfg(feature = "voice")]
+pub use crate::twiml::{
+    Client, Conference, Dial, Digits, Enqueue, EventSet, Gather, GatherInput, Hangup, Leave, Loop, Number, Pause, Play,
+    Record, Reject, RejectReason, Say, Seconds, Sip,
+};
+
+#[cfg(feature = "messaging")]
+pub use crate::twiml::{Body, Media, Message};
+
+#[cfg(feature = "pay")]
+pub use crate::twiml::{Pay, Prompt, PromptFor};
+
+#[cfg(feature = "fax")]
+pub use crate::twiml::Receive;
@@ -0,0 +1,180 @@
+//! Ready-made documents for fallback URLs and other error paths
+//!
+//! Twilio requests a voice number's fallback URL when the primary webhook fails or
+//! returns invalid TwiML. These presets give callers a graceful ending instead of
+//! Twilio's generic error message.
+//!
+//! The supervisor presets join a contact-center call's conference to monitor, coach or
+//! barge in on an agent.
+
+use crate::localization::say_locale;
+use crate::twiml::{Conference, Dial, Pause, Play, Response, Say};
+use crate::webhook::WaitUrlRequest;
+
+/// Apology spoken by [`apology_and_hangup`], by language
+const APOLOGIES: &[(&str, &str)] = &[
+    ("de", "Es tut uns leid, es ist ein Fehler aufgetreten. Bitte versuchen Sie es später erneut. Auf Wiederhören."),
+    ("en", "We're sorry, an error has occurred. Please try your call again later. Goodbye."),
+    ("es", "Lo sentimos, se ha producido un error. Por favor, vuelva a llamar más tarde. Adiós."),
+    ("fr", "Nous sommes désolés, une erreur s'est produite. Veuillez rappeler plus tard. Au revoir."),
+    ("it", "Siamo spiacenti, si è verificato un errore. Si prega di richiamare più tardi. Arrivederci."),
+    ("ja", "申し訳ございません。エラーが発生しました。後ほどおかけ直しください。"),
+    ("nl", "Het spijt ons, er is een fout opgetreden. Probeer het later opnieuw. Tot ziens."),
+    ("pt", "Lamentamos, ocorreu um erro. Por favor, ligue novamente mais tarde. Adeus."),
+];
+
+/// Query parameter counting the retries left in [`retry_redirect`]
+pub const ATTEMPTS_PARAM: &str = "attempts";
+
+/// Participant label of the agent in a conference joined with [`agent_conference`]
+pub const AGENT_LABEL: &str = "agent";
+
+/// Participant label of the supervisor in a conference joined with [`supervisor_conference`]
+pub const SUPERVISOR_LABEL: &str = "supervisor";
+
+/// How a supervisor takes part in an agent's call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SupervisorMode {
+    /// Listen without being heard; unmuting later through the Participants API whispers
+    /// to the agent only
+    Monitor,
+    /// Speak to the agent without the customer hearing
+    Whisper,
+    /// Speak to everyone on the call
+    Barge,
+}
+
+/// Join the agent to a conference under [`AGENT_LABEL`], so supervisors and the
+/// Participants API can find them
+///
+/// The conference ends when the agent leaves, taking any supervisor with it.
+pub fn agent_conference(room: &str) -> Response {
+    let conference = Conference::new(room)
+        .participant_label(AGENT_LABEL)
+        .start_conference_on_enter(true)
+        .end_conference_on_exit(true);
+    Response::new().dial(Dial::new_empty().conference(conference))
+}
+
+/// Join a supervisor to an agent's conference under [`SUPERVISOR_LABEL`]
+///
+/// `agent_call_sid` is the agent's CallSid, not their participant label: `coach` only
+/// accepts a CallSid. Monitoring joins muted with `coach` set, so unmuting can never
+/// reach the customer; whisper sets `coach` unmuted and barge leaves it unset. The
+/// supervisor joins without a beep and never starts or ends the conference.
+pub fn supervisor_conference(room: &str, agent_call_sid: &str, mode: SupervisorMode) -> Response {
+    let mut conference = Conference::new(room)
+        .participant_label(SUPERVISOR_LABEL)
+        .muted(mode == SupervisorMode::Monitor)
+        .beep(false)
+        .start_conference_on_enter(false)
+        .end_conference_on_exit(false);
+    if mode != SupervisorMode::Barge {
+        conference = conference.coach(agent_call_sid);
+    }
+    Response::new().dial(Dial::new_empty().conference(conference))
+}
+
+/// Apologize in the caller's language and end the call
+///
+/// `locale` is a Twilio language code such as `fr-CA`; its language selects the text
+/// and the code itself is passed to `Say`, with a bare language such as `de` expanded by
+/// [`say_locale`]. Unsupported languages get English.
+pub fn apology_and_hangup(locale: &str) -> Response {
+    let language = locale.split(['-', '_']).next().unwrap_or_default().to_ascii_lowercase();
+    let apology = |language: &str| {
+        APOLOGIES
+            .binary_search_by_key(&language, |(language, _)| language)
+            .map(|index| APOLOGIES[index].1)
+    };
+    let say = match apology(&language) {
+        Ok(text) => Say::new(text).language(say_locale(locale)),
+        Err(_) => Say::new(apology("en").unwrap_or_default()).language("en-US"),
+    };
+    Response::new().say(say).hangup()
+}
+
+/// Pause briefly and retry `url`, giving up with an English apology after `attempts` tries
+///
+/// The redirect carries the remaining count as an `attempts` query parameter, so a
+/// fallback handler can pass the value it received back in to count down.
+pub fn retry_redirect(url: &str, attempts: u32) -> Response {
+    if attempts == 0 {
+        return apology_and_hangup("en-US");
+    }
+    let separator = if url.contains('?') { '&' } else { '?' };
+    Response::new()
+        .pause(Pause::new().length(1))
+        .redirect(format!("{}{}{}={}", url, separator, ATTEMPTS_PARAM, attempts - 1))
+}
+
+/// Tell the caller their place in the queue and, once the queue has history, roughly how
+/// long they have left to wait
+///
+/// The estimate comes from the queue's average wait, rounded up to whole minutes.
+pub fn queue_position_announcement(wait: &WaitUrlRequest) -> Say {
+    let mut text = format!("You are number {} in the queue.", wait.position.max(1));
+    if let Some(seconds) = wait.estimated_wait() {
+        let minutes = seconds.div_ceil(60).max(1);
+        let unit = if minutes == 1 { "minute" } else { "minutes" };
+        text.push_str(&format!(" The estimated wait time is about {} {}.", minutes, unit));
+    }
+    Say::new(text)
+}
+
+/// Announce the caller's place in the queue and play hold music, for an Enqueue `waitUrl`
+///
+/// Twilio requests the wait URL again when the document finishes, so the announcement
+/// repeats between plays of the music.
+pub fn queue_wait_announcement(wait: &WaitUrlRequest, hold_music: impl Into<Play>) -> Response {
+    Response::new().say(queue_position_announcement(wait)).play(hold_music)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::twiml::ToXmlString;
+
+    #[test]
+    fn test_apology_and_hangup() {
+        let xml_string = apology_and_hangup("fr-CA").to_xml_string();
+        assert!(xml_string.contains(r#"<Say language="fr-CA">Nous sommes désolés"#));
+        assert!(xml_string.ends_with("<Hangup /></Response>"));
+        assert!(apology_and_hangup("de").to_xml_string().contains(r#"<Say language="de-DE">Es tut uns leid"#));
+        assert!(apology_and_hangup("xx").to_xml_string().contains(r#"<Say language="en-US">We're sorry"#));
+        assert!(APOLOGIES.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn test_retry_redirect() {
+        assert!(retry_redirect("/voice?step=menu", 2).to_xml_string().ends_with(
+            r#"<Response><Pause length="1" /><Redirect>/voice?step=menu&amp;attempts=1</Redirect></Response>"#
+        ));
+        assert!(retry_redirect("/voice", 0).to_xml_string().ends_with("<Hangup /></Response>"));
+    }
+
+    #[test]
+    fn test_supervisor_conference() {
+        assert!(agent_conference("support-42").to_xml_string().ends_with(
+            r#"<Dial><Conference participantLabel="agent" startConferenceOnEnter="true" endConferenceOnExit="true">support-42</Conference></Dial></Response>"#
+        ));
+        assert!(supervisor_conference("support-42", "CA123", SupervisorMode::Monitor).to_xml_string().ends_with(concat!(
+            r#"<Conference participantLabel="supervisor" muted="true" beep="false" startConferenceOnEnter="false" "#,
+            r#"endConferenceOnExit="false" coach="CA123">support-42</Conference></Dial></Response>"#,
+        )));
+        assert!(supervisor_conference("support-42", "CA123", SupervisorMode::Whisper).to_xml_string().contains(r#"muted="false""#));
+        let barge = supervisor_conference("support-42", "CA123", SupervisorMode::Barge).to_xml_string();
+        assert!(barge.contains(r#"muted="false""#) && !barge.contains("coach"));
+    }
+
+    #[test]
+    fn test_queue_wait_announcement() {
+        let wait = WaitUrlRequest { position: 2, queue_time: 20, average_queue_time: 200, ..WaitUrlRequest::default() };
+        assert!(queue_wait_announcement(&wait, "https://example.com/hold.mp3").to_xml_string().ends_with(
+            "<Response><Say>You are number 2 in the queue. The estimated wait time is about 3 minutes.</Say>\
+             <Play>https://example.com/hold.mp3</Play></Response>"
+        ));
+        let first = WaitUrlRequest { position: 1, ..WaitUrlRequest::default() };
+        assert!(queue_position_announcement(&first).to_xml_string().ends_with("<Say>You are number 1 in the queue.</Say>"));
+    }
+}
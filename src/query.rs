@@ -0,0 +1,106 @@
+use crate::twiml::{Response, TwiMLElement};
+
+/// Parsed element selector
+///
+/// A selector is a `/`-separated list of element names, where `*` matches any name.
+/// It matches elements whose path ends with those names, so `Say` finds every Say and
+/// `Dial/Conference` finds Conference nouns directly inside a Dial. A leading `/`
+/// anchors the selector at the root, as in `/Response/Gather/Say`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Selector<'s> {
+    anchored: bool,
+    names: Vec<&'s str>,
+}
+
+impl<'s> Selector<'s> {
+    fn parse(selector: &'s str) -> Self {
+        let trimmed = selector.trim();
+        Self {
+            anchored: trimmed.starts_with('/'),
+            names: trimmed.split('/').map(str::trim).filter(|name| !name.is_empty()).collect(),
+        }
+    }
+
+    fn matches(&self, path: &[&str]) -> bool {
+        if self.names.is_empty() || path.len() < self.names.len() || (self.anchored && path.len() != self.names.len()) {
+            return false;
+        }
+        path[path.len() - self.names.len()..]
+            .iter()
+            .zip(&self.names)
+            .all(|(name, wanted)| *wanted == "*" || name == wanted)
+    }
+}
+
+/// Find every element in a subtree matching a selector, in document order
+pub fn select<'a>(root: &'a dyn TwiMLElement, selector: &str) -> Vec<&'a dyn TwiMLElement> {
+    let selector = Selector::parse(selector);
+    let mut path = Vec::new();
+    let mut matches = Vec::new();
+    collect(root, &selector, &mut path, &mut matches);
+    matches
+}
+
+fn collect<'a>(
+    element: &'a dyn TwiMLElement,
+    selector: &Selector<'_>,
+    path: &mut Vec<&'a str>,
+    matches: &mut Vec<&'a dyn TwiMLElement>,
+) {
+    let node = element.factory();
+    path.push(node.name());
+    if selector.matches(path) {
+        matches.push(element);
+    }
+    for child in node.children() {
+        collect(child.as_ref(), selector, path, matches);
+    }
+    path.pop();
+}
+
+impl Response {
+    /// Find every element matching a selector such as `Say` or `Dial/Conference`
+    pub fn find_all(&self, selector: &str) -> Vec<&dyn TwiMLElement> {
+        select(self, selector)
+    }
+
+    /// Find the first element matching a selector
+    pub fn find(&self, selector: &str) -> Option<&dyn TwiMLElement> {
+        self.find_all(selector).into_iter().next()
+    }
+}
+
+#[cfg(all(test, feature = "voice"))]
+mod tests {
+    use crate::twiml::*;
+
+    fn document() -> Response {
+        Response::new()
+            .say(Say::new("Welcome"))
+            .gather(Gather::new().say(Say::new("Press 1")))
+            .dial(Dial::new_empty().conference(Conference::new("Room")))
+            .dial(Dial::new_empty().number(Number::new("+15551234567")))
+    }
+
+    fn texts(elements: Vec<&dyn TwiMLElement>) -> Vec<&str> {
+        elements.into_iter().filter_map(|element| element.factory().text()).collect()
+    }
+
+    #[test]
+    fn test_find_by_name() {
+        let response = document();
+        assert_eq!(texts(response.find_all("Say")), vec!["Welcome", "Press 1"]);
+        assert_eq!(response.find_all("Dial").len(), 2);
+        assert!(response.find("Hangup").is_none());
+    }
+
+    #[test]
+    fn test_find_by_path() {
+        let response = document();
+        assert_eq!(texts(response.find_all("Gather/Say")), vec!["Press 1"]);
+        assert_eq!(texts(response.find_all("/Response/Say")), vec!["Welcome"]);
+        assert_eq!(texts(response.find_all("Dial/*")), vec!["Room", "+15551234567"]);
+        assert_eq!(response.find("Dial/Conference").unwrap().factory().text(), Some("Room"));
+        assert!(response.find_all("/Say").is_empty());
+    }
+}
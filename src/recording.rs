@@ -0,0 +1,161 @@
+//! Recording consent rules applied to finished documents
+//!
+//! Some jurisdictions require every party to consent before a call is recorded. A
+//! [`RecordingPolicy`] post-processes a document, announcing or disabling its
+//! recording, and reports what changed so the adjustment can be logged.
+
+use crate::twiml::{ElementFactory, Response, Say, TwiMLElement};
+
+/// US states requiring every party's consent to record a phone call, as ISO 3166-2 codes
+///
+/// Derived from state wiretap statutes; confirm it against current law before relying
+/// on it.
+pub const ALL_PARTY_CONSENT_JURISDICTIONS: &[&str] = &[
+    "US-CA", "US-CT", "US-DE", "US-FL", "US-IL", "US-MA", "US-MD", "US-MI", "US-MT", "US-NH", "US-NV", "US-PA", "US-WA",
+];
+
+/// How a document that records a call is adjusted for consent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordingPolicy {
+    /// Leave recording unchanged, where one party's consent suffices
+    OneParty,
+    /// Speak this announcement before the first verb that records
+    Announce(String),
+    /// Turn recording off on `Dial` and `Conference`, and remove `Record` verbs
+    Forbid,
+}
+
+impl RecordingPolicy {
+    /// Choose the policy for a jurisdiction such as `US-CA`: all-party consent
+    /// jurisdictions get the announcement, others record unchanged
+    pub fn for_jurisdiction(code: &str, announcement: impl Into<String>) -> Self {
+        if ALL_PARTY_CONSENT_JURISDICTIONS.iter().any(|jurisdiction| jurisdiction.eq_ignore_ascii_case(code)) {
+            RecordingPolicy::Announce(announcement.into())
+        } else {
+            RecordingPolicy::OneParty
+        }
+    }
+}
+
+/// Change made by [`Response::apply_recording_policy`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordingChange {
+    /// An announcement was inserted before the named verb
+    Announced {
+        /// Name of the verb that records
+        before: String,
+    },
+    /// Recording was turned off on the named element
+    Disabled {
+        /// Name of the element, `Dial` or `Conference`
+        element: String,
+    },
+    /// A `Record` verb was removed
+    Removed,
+}
+
+/// Value of the record attribute that turns recording off
+const DO_NOT_RECORD: &str = "do-not-record";
+
+/// Whether an element's record attribute starts a recording
+fn records(node: &ElementFactory) -> bool {
+    node.attribute("record").is_some_and(|value| value != DO_NOT_RECORD && value != "false")
+}
+
+/// Whether a verb records the call itself or through one of its nouns
+fn verb_records(node: &ElementFactory) -> bool {
+    match node.name() {
+        "Record" => true,
+        "Dial" => records(node) || node.children().iter().any(|child| records(child.factory())),
+        _ => false,
+    }
+}
+
+impl Response {
+    /// Adjust the document to a recording policy, returning what changed
+    pub fn apply_recording_policy(&mut self, policy: &RecordingPolicy) -> Vec<RecordingChange> {
+        let root = self.factory_mut();
+        match policy {
+            RecordingPolicy::OneParty => Vec::new(),
+            RecordingPolicy::Announce(announcement) => {
+                let Some(index) = root.children().iter().position(|child| verb_records(child.factory())) else {
+                    return Vec::new();
+                };
+                let before = root.children()[index].factory().name().to_string();
+                root.insert_child_at(index, Box::new(Say::new(announcement.as_str())));
+                vec![RecordingChange::Announced { before }]
+            }
+            RecordingPolicy::Forbid => {
+                let mut changes = Vec::new();
+                root.retain_children(|child| {
+                    let record = child.factory().name() == "Record";
+                    if record {
+                        changes.push(RecordingChange::Removed);
+                    }
+                    !record
+                });
+                for index in 0..root.children().len() {
+                    let verb = root.child_mut(index).expect("index is in bounds").factory_mut();
+                    if verb.name() != "Dial" {
+                        continue;
+                    }
+                    disable(verb, &mut changes);
+                    for noun in 0..verb.children().len() {
+                        disable(verb.child_mut(noun).expect("index is in bounds").factory_mut(), &mut changes);
+                    }
+                }
+                changes
+            }
+        }
+    }
+}
+
+/// Turn off an element's recording if it has one
+fn disable(node: &mut ElementFactory, changes: &mut Vec<RecordingChange>) {
+    if records(node) {
+        node.set_attribute("record", DO_NOT_RECORD);
+        changes.push(RecordingChange::Disabled { element: node.name().to_string() });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::twiml::*;
+
+    fn document() -> Response {
+        Response::new()
+            .say("Connecting you now")
+            .dial(Dial::new_empty().record("record-from-answer").conference(Conference::new("Support").record("record-from-start")))
+            .record(Record::new())
+    }
+
+    #[test]
+    fn test_announce() {
+        let policy = RecordingPolicy::for_jurisdiction("us-ca", "This call may be recorded.");
+        let mut response = document();
+        assert_eq!(response.apply_recording_policy(&policy), [RecordingChange::Announced { before: "Dial".to_string() }]);
+        assert!(response.to_xml_string().contains("<Say>Connecting you now</Say><Say>This call may be recorded.</Say><Dial"));
+
+        assert_eq!(RecordingPolicy::for_jurisdiction("US-NY", "unused"), RecordingPolicy::OneParty);
+        assert!(Response::new().say("Hi").apply_recording_policy(&policy).is_empty());
+    }
+
+    #[test]
+    fn test_forbid() {
+        let mut response = document();
+        let changes = response.apply_recording_policy(&RecordingPolicy::Forbid);
+        assert_eq!(
+            changes,
+            [
+                RecordingChange::Removed,
+                RecordingChange::Disabled { element: "Dial".to_string() },
+                RecordingChange::Disabled { element: "Conference".to_string() },
+            ]
+        );
+        assert!(response.to_xml_string().ends_with(
+            r#"<Dial record="do-not-record"><Conference record="do-not-record">Support</Conference></Dial></Response>"#
+        ));
+        assert!(response.apply_recording_policy(&RecordingPolicy::Forbid).is_empty());
+    }
+}
@@ -0,0 +1,193 @@
+use std::borrow::Cow;
+
+use crate::twiml::{Response, TwiMLElement};
+
+/// How characters that XML doesn't allow are handled in text and attribute values
+///
+/// Serialization always strips them, since a document containing them is rejected by
+/// Twilio. Calling [`Response::sanitize`] first lets them be replaced instead, and strict
+/// validation reports them as errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SanitizePolicy {
+    /// Remove the characters
+    #[default]
+    Strip,
+    /// Replace each character, typically with a space or U+FFFD
+    Replace(char),
+}
+
+/// Whether a character may appear in an XML document
+///
+/// Control characters other than tab, line feed and carriage return are excluded, as are
+/// the noncharacters U+FFFE and U+FFFF.
+pub fn is_xml_char(c: char) -> bool {
+    matches!(c, '\t' | '\n' | '\r' | '\u{20}'..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..)
+}
+
+/// Apply a policy to a value, borrowing it when nothing needs to change
+pub(crate) fn sanitize(value: &str, policy: SanitizePolicy) -> Cow<'_, str> {
+    if value.chars().all(is_xml_char) {
+        return Cow::Borrowed(value);
+    }
+    Cow::Owned(match policy {
+        SanitizePolicy::Strip => value.chars().filter(|&c| is_xml_char(c)).collect(),
+        SanitizePolicy::Replace(with) => value.chars().map(|c| if is_xml_char(c) { c } else { with }).collect(),
+    })
+}
+
+/// Opt-in cleanup of spoken and message text pasted from word processors and CMSs
+///
+/// Typographic characters such as curly quotes fall outside GSM-7 and switch a whole
+/// SMS to UCS-2, which fits 70 characters per segment instead of 160. Every step is
+/// enabled by default; see [`Response::normalize_text`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextNormalization {
+    compose_accents: bool,
+    remove_zero_width: bool,
+    downgrade_punctuation: bool,
+}
+
+impl Default for TextNormalization {
+    fn default() -> Self {
+        Self {
+            compose_accents: true,
+            remove_zero_width: true,
+            downgrade_punctuation: true,
+        }
+    }
+}
+
+/// Precomposed Latin letters by base letter and combining mark
+const COMPOSITIONS: &[(char, char, char)] = &[
+    ('A', '\u{300}', 'À'), ('A', '\u{301}', 'Á'), ('A', '\u{302}', 'Â'), ('A', '\u{303}', 'Ã'), ('A', '\u{308}', 'Ä'), ('A', '\u{30A}', 'Å'),
+    ('C', '\u{327}', 'Ç'),
+    ('E', '\u{300}', 'È'), ('E', '\u{301}', 'É'), ('E', '\u{302}', 'Ê'), ('E', '\u{308}', 'Ë'),
+    ('I', '\u{300}', 'Ì'), ('I', '\u{301}', 'Í'), ('I', '\u{302}', 'Î'), ('I', '\u{308}', 'Ï'),
+    ('N', '\u{303}', 'Ñ'),
+    ('O', '\u{300}', 'Ò'), ('O', '\u{301}', 'Ó'), ('O', '\u{302}', 'Ô'), ('O', '\u{303}', 'Õ'), ('O', '\u{308}', 'Ö'),
+    ('U', '\u{300}', 'Ù'), ('U', '\u{301}', 'Ú'), ('U', '\u{302}', 'Û'), ('U', '\u{308}', 'Ü'),
+    ('Y', '\u{301}', 'Ý'),
+    ('a', '\u{300}', 'à'), ('a', '\u{301}', 'á'), ('a', '\u{302}', 'â'), ('a', '\u{303}', 'ã'), ('a', '\u{308}', 'ä'), ('a', '\u{30A}', 'å'),
+    ('c', '\u{327}', 'ç'),
+    ('e', '\u{300}', 'è'), ('e', '\u{301}', 'é'), ('e', '\u{302}', 'ê'), ('e', '\u{308}', 'ë'),
+    ('i', '\u{300}', 'ì'), ('i', '\u{301}', 'í'), ('i', '\u{302}', 'î'), ('i', '\u{308}', 'ï'),
+    ('n', '\u{303}', 'ñ'),
+    ('o', '\u{300}', 'ò'), ('o', '\u{301}', 'ó'), ('o', '\u{302}', 'ô'), ('o', '\u{303}', 'õ'), ('o', '\u{308}', 'ö'),
+    ('u', '\u{300}', 'ù'), ('u', '\u{301}', 'ú'), ('u', '\u{302}', 'û'), ('u', '\u{308}', 'ü'),
+    ('y', '\u{301}', 'ý'), ('y', '\u{308}', 'ÿ'),
+];
+
+impl TextNormalization {
+    /// Create options with every step enabled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether to combine a Latin letter and a following accent mark into one
+    /// precomposed letter, as NFC does, so `e` plus U+0301 becomes `é`
+    ///
+    /// Only the Latin-1 letters are composed, which covers the accented letters of GSM-7.
+    pub fn compose_accents(mut self, compose: bool) -> Self {
+        self.compose_accents = compose;
+        self
+    }
+
+    /// Set whether to remove zero-width spaces, joiners and byte order marks
+    pub fn remove_zero_width(mut self, remove: bool) -> Self {
+        self.remove_zero_width = remove;
+        self
+    }
+
+    /// Set whether to replace curly quotes, long dashes, ellipses and non-breaking spaces
+    /// with their ASCII equivalents
+    pub fn downgrade_punctuation(mut self, downgrade: bool) -> Self {
+        self.downgrade_punctuation = downgrade;
+        self
+    }
+
+    /// Normalize text, borrowing it when nothing needs to change
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut out = String::with_capacity(text.len());
+        for c in text.chars() {
+            if self.remove_zero_width && matches!(c, '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{2060}' | '\u{FEFF}') {
+                continue;
+            }
+            if self.compose_accents
+                && let Some(base) = out.chars().next_back()
+                && let Some(&(_, _, composed)) = COMPOSITIONS.iter().find(|&&(b, mark, _)| b == base && mark == c)
+            {
+                out.pop();
+                out.push(composed);
+                continue;
+            }
+            match c {
+                '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{2032}' if self.downgrade_punctuation => out.push('\''),
+                '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{2033}' if self.downgrade_punctuation => out.push('"'),
+                '\u{2013}' | '\u{2014}' | '\u{2212}' if self.downgrade_punctuation => out.push('-'),
+                '\u{2026}' if self.downgrade_punctuation => out.push_str("..."),
+                '\u{A0}' | '\u{202F}' if self.downgrade_punctuation => out.push(' '),
+                c => out.push(c),
+            }
+        }
+        if out == text { Cow::Borrowed(text) } else { Cow::Owned(out) }
+    }
+}
+
+impl Response {
+    /// Sanitize every text and attribute value in the document, returning how many changed
+    pub fn sanitize(&mut self, policy: SanitizePolicy) -> usize {
+        self.factory_mut().sanitize(policy)
+    }
+
+    /// Normalize the text of every `Say`, `Message` and `Body`, returning how many changed
+    ///
+    /// Attributes and the text of other elements, such as URLs, are left alone.
+    pub fn normalize_text(&mut self, normalization: &TextNormalization) -> usize {
+        self.factory_mut().normalize_text(normalization)
+    }
+}
+
+#[cfg(all(test, feature = "voice"))]
+mod tests {
+    use super::*;
+    use crate::twiml::*;
+    use crate::validation::ValidationOptions;
+
+    #[test]
+    fn test_serialization_strips_control_characters() {
+        let response = Response::new().say(Say::new("Hello\u{b} there").voice("Polly\u{0}.Joanna"));
+        let xml_string = response.to_xml_string();
+        assert!(xml_string.contains("<Say voice=\"Polly.Joanna\">Hello there</Say>"));
+    }
+
+    #[test]
+    fn test_sanitize_policy() {
+        let mut response = Response::new()
+            .say("Line\u{b}break")
+            .gather(Gather::new().action("/menu\u{1}").say("ok\tfine"));
+        assert!(response.validate_with(&ValidationOptions::strict()).is_err());
+
+        assert_eq!(response.sanitize(SanitizePolicy::Replace(' ')), 2);
+        assert!(response.to_xml_string().contains("<Say>Line break</Say><Gather action=\"/menu \"><Say>ok\tfine</Say>"));
+        assert!(response.validate_with(&ValidationOptions::strict()).is_ok());
+        assert_eq!(response.sanitize(SanitizePolicy::Strip), 0);
+    }
+
+    #[cfg(feature = "messaging")]
+    #[test]
+    fn test_normalize_text() {
+        let pasted = "Caf\u{65}\u{301} \u{201C}open\u{201D}\u{200B} \u{2014} we\u{2019}re here\u{2026}";
+        let normalization = TextNormalization::new();
+        assert_eq!(normalization.apply(pasted), "Café \"open\" - we're here...");
+        assert_eq!(crate::sms_segments(&normalization.apply(pasted)).encoding, crate::SmsEncoding::Gsm7);
+        assert!(matches!(normalization.apply("plain"), Cow::Borrowed("plain")));
+        assert_eq!(normalization.downgrade_punctuation(false).apply("\u{2018}a\u{2019}"), "\u{2018}a\u{2019}");
+
+        let mut response = Response::new()
+            .say("It\u{2019}s")
+            .play("https://example.com/\u{2019}.mp3")
+            .message(Message::new_empty().body("\u{201C}Hi\u{201D}"));
+        assert_eq!(response.normalize_text(&TextNormalization::new()), 2);
+        assert!(response.to_xml_string().contains("<Say>It's</Say><Play>https://example.com/\u{2019}.mp3</Play>"));
+    }
+}
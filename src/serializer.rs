@@ -0,0 +1,288 @@
+use xml_builder::{XMLBuilder, XMLElement, XMLVersion};
+
+use std::fmt;
+
+use crate::dialect::{Dialect, DialectError};
+use crate::error::TwiMLError;
+use crate::twiml::{render_small, TwiMLElement, MAX_DOCUMENT_BYTES};
+
+/// Character encoding of serialized output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    /// UTF-8, which Twilio expects
+    #[default]
+    Utf8,
+    /// ISO-8859-1 (Latin-1), for PBX integrations that can't read UTF-8
+    Iso8859_1,
+}
+
+impl Encoding {
+    /// Get the name written in the XML declaration
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Iso8859_1 => "ISO-8859-1",
+        }
+    }
+
+    /// Check whether a character can be written directly in this encoding
+    pub fn can_encode(&self, c: char) -> bool {
+        match self {
+            Encoding::Utf8 => true,
+            Encoding::Iso8859_1 => (c as u32) <= 0xFF,
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Formatting choices for serializing a document, passed per call instead of set globally
+///
+/// The default writes what [`ToXmlString`](crate::ToXmlString) does: a compact document
+/// with an XML declaration, attributes in the order they were set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Serializer {
+    declaration: bool,
+    indent: bool,
+    canonical: bool,
+    dialect: Option<Dialect>,
+    encoding: Encoding,
+    size_limit: Option<usize>,
+}
+
+impl Default for Serializer {
+    fn default() -> Self {
+        Self {
+            declaration: true,
+            indent: false,
+            canonical: false,
+            dialect: None,
+            encoding: Encoding::Utf8,
+            size_limit: Some(MAX_DOCUMENT_BYTES),
+        }
+    }
+}
+
+impl Serializer {
+    /// Create a serializer with the default options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether to start the document with an XML declaration
+    pub fn declaration(mut self, declaration: bool) -> Self {
+        self.declaration = declaration;
+        self
+    }
+
+    /// Set whether to put each element on its own line, indented with tabs
+    pub fn indent(mut self, indent: bool) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Set whether to write attributes in canonical order; see
+    /// [`ElementFactory::canonicalize`](crate::ElementFactory::canonicalize)
+    ///
+    /// Ignored when converting to a [`dialect`](Serializer::dialect).
+    pub fn canonical(mut self, canonical: bool) -> Self {
+        self.canonical = canonical;
+        self
+    }
+
+    /// Convert the document to a provider dialect, rejecting elements it can't express,
+    /// as [`Response::to_dialect_string`](crate::Response::to_dialect_string) does
+    pub fn dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = Some(dialect);
+        self
+    }
+
+    /// Set the character encoding named in the declaration and used by
+    /// [`to_bytes`](Serializer::to_bytes)
+    ///
+    /// Characters the encoding can't represent are written as numeric character
+    /// references, which XML parsers read back as the original character. Inside CDATA
+    /// sections references aren't interpreted, so such characters there are an error.
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Set the largest document to accept, in bytes, or `None` for no limit
+    pub fn size_limit(mut self, limit: Option<usize>) -> Self {
+        self.size_limit = limit;
+        self
+    }
+
+    /// Serialize a document, failing if it can't be written in the chosen dialect or is
+    /// larger than the size limit
+    ///
+    /// With an encoding other than UTF-8 the string only contains characters that
+    /// encoding can represent; see [`to_bytes`](Serializer::to_bytes) for the encoded form.
+    pub fn to_string(&self, element: &dyn TwiMLElement) -> Result<String, TwiMLError> {
+        let xml = self.render(element)?;
+        self.check_size(self.encoded_len(&xml))?;
+        Ok(xml)
+    }
+
+    /// Serialize a document into bytes in the chosen encoding
+    pub fn to_bytes(&self, element: &dyn TwiMLElement) -> Result<Vec<u8>, TwiMLError> {
+        let xml = self.render(element)?;
+        let bytes = match self.encoding {
+            Encoding::Utf8 => xml.into_bytes(),
+            Encoding::Iso8859_1 => xml.chars().map(|c| c as u8).collect(),
+        };
+        self.check_size(bytes.len())?;
+        Ok(bytes)
+    }
+
+    fn check_size(&self, bytes: usize) -> Result<(), TwiMLError> {
+        match self.size_limit {
+            Some(limit) if bytes > limit => Err(TwiMLError::TooLarge { bytes, limit }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Length of already transcoded output in the chosen encoding
+    fn encoded_len(&self, xml: &str) -> usize {
+        match self.encoding {
+            Encoding::Utf8 => xml.len(),
+            Encoding::Iso8859_1 => xml.chars().count(),
+        }
+    }
+
+    /// Serialize a document without checking its size
+    pub(crate) fn render(&self, element: &dyn TwiMLElement) -> Result<String, TwiMLError> {
+        let node = element.factory();
+        if self.is_default_format()
+            && let Some(xml) = render_small(node)
+        {
+            return Ok(xml);
+        }
+
+        let root = match self.dialect {
+            Some(dialect) => dialect.convert(element)?,
+            None if self.canonical => node.try_to_canonical_xml()?,
+            None => node.try_to_xml()?,
+        };
+        transcode(self.render_root(root), self.encoding)
+    }
+
+    /// Whether the output is the compact format the fast path writes
+    fn is_default_format(&self) -> bool {
+        self.declaration && !self.indent && !self.canonical && self.dialect.is_none() && self.encoding == Encoding::Utf8
+    }
+
+    fn render_root(&self, root: XMLElement) -> String {
+        let mut writer = Vec::new();
+        if self.declaration {
+            let mut xml = XMLBuilder::new()
+                .version(XMLVersion::XML1_1)
+                .encoding(self.encoding.as_str().into())
+                .break_lines(self.indent)
+                .indent(self.indent)
+                .build();
+            xml.set_root_element(root);
+            xml.generate(&mut writer).expect("writing to a Vec cannot fail");
+        } else {
+            root.render(&mut writer, false, self.indent, self.indent, false)
+                .expect("writing to a Vec cannot fail");
+        }
+        String::from_utf8(writer).expect("XML output is UTF-8")
+    }
+}
+
+/// Replace characters `encoding` can't represent with character references, failing
+/// for those inside CDATA sections
+fn transcode(xml: String, encoding: Encoding) -> Result<String, TwiMLError> {
+    if xml.chars().all(|c| encoding.can_encode(c)) {
+        return Ok(xml);
+    }
+    let mut out = String::with_capacity(xml.len() + 16);
+    let mut rest = xml.as_str();
+    while let Some(c) = rest.chars().next() {
+        if rest.starts_with("<![CDATA[") {
+            let end = rest.find("]]>").map_or(rest.len(), |end| end + 3);
+            let section = &rest[..end];
+            if let Some(c) = section.chars().find(|c| !encoding.can_encode(*c)) {
+                return Err(TwiMLError::Serialization(format!(
+                    "U+{:04X} cannot be written in {} inside CDATA",
+                    c as u32, encoding
+                )));
+            }
+            out.push_str(section);
+            rest = &rest[end..];
+            continue;
+        }
+        if encoding.can_encode(c) {
+            out.push(c);
+        } else {
+            out.push_str(&format!("&#x{:X};", c as u32));
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    Ok(out)
+}
+
+impl From<DialectError> for TwiMLError {
+    fn from(error: DialectError) -> Self {
+        TwiMLError::InvalidElement {
+            element: error.element,
+            reason: format!("cannot be expressed in {}: {}", error.dialect, error.reason),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "voice"))]
+mod tests {
+    use super::*;
+    use crate::twiml::*;
+
+    #[test]
+    fn test_serializer_options() {
+        let response = Response::new().say(Say::new("Hello").voice("alice").language("en-US"));
+        assert_eq!(Serializer::new().to_string(&response).unwrap(), response.to_xml_string());
+
+        let fragment = Serializer::new().declaration(false).canonical(true).to_string(&response).unwrap();
+        assert_eq!(fragment, r#"<Response><Say language="en-US" voice="alice">Hello</Say></Response>"#);
+
+        let indented = Serializer::new().declaration(false).indent(true).to_string(&response).unwrap();
+        assert_eq!(indented, "<Response>\n\t<Say voice=\"alice\" language=\"en-US\">Hello</Say>\n</Response>\n");
+
+        let limited = Serializer::new().size_limit(Some(10)).to_string(&response);
+        assert!(matches!(limited, Err(TwiMLError::TooLarge { limit: 10, .. })));
+    }
+
+    #[test]
+    fn test_serializer_dialect() {
+        let response = Response::new().denoise().say("Hello");
+        assert!(Serializer::new().to_string(&response).is_ok());
+        let error = Serializer::new().dialect(Dialect::Twilio).to_string(&response).unwrap_err();
+        assert!(matches!(error, TwiMLError::InvalidElement { ref element, .. } if element == "Denoise"));
+
+        let signalwire = Serializer::new().dialect(Dialect::SignalWire).declaration(false);
+        assert_eq!(signalwire.to_string(&response).unwrap(), "<Response><Denoise /><Say>Hello</Say></Response>");
+    }
+
+    #[test]
+    fn test_serializer_encoding() {
+        let response = Response::new().say("Café ☎ 📞");
+        let latin1 = Serializer::new().encoding(Encoding::Iso8859_1);
+        let xml = latin1.to_string(&response).unwrap();
+        assert_eq!(
+            xml,
+            r#"<?xml version="1.1" encoding="ISO-8859-1"?><Response><Say>Café &#x260E; &#x1F4DE;</Say></Response>"#
+        );
+        let bytes = latin1.to_bytes(&response).unwrap();
+        assert_eq!(bytes.len(), xml.chars().count());
+        assert!(bytes.windows(4).any(|w| w == b"Caf\xE9"));
+
+        let task = Response::new().enqueue(Enqueue::new_empty().workflow_sid("WW1").task(Task::new("☎").cdata(true)));
+        assert!(matches!(latin1.to_string(&task), Err(TwiMLError::Serialization(_))));
+        assert!(Serializer::new().to_string(&task).unwrap().contains("<![CDATA[☎]]>"));
+    }
+}
@@ -0,0 +1,147 @@
+/// Characters of the GSM 03.38 basic character set, each encoded in one septet
+const GSM7_BASIC: &str = "@£$¥èéùìòÇ\nØø\rÅåΔ_ΦΓΛΩΠΨΣΘΞÆæßÉ !\"#¤%&'()*+,-./0123456789:;<=>?¡ABCDEFGHIJKLMNOPQRSTUVWXYZÄÖÑÜ§¿abcdefghijklmnopqrstuvwxyzäöñüà";
+
+/// Characters of the GSM 03.38 extension table, each encoded as an escape plus one septet
+const GSM7_EXTENSION: &str = "\u{c}^{}\\[~]|€";
+
+/// Maximum length of a Message body accepted by Twilio
+pub const MAX_BODY_CHARACTERS: usize = 1600;
+
+/// Character encoding a message body is sent with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmsEncoding {
+    /// GSM 03.38 7-bit default alphabet
+    Gsm7,
+    /// UCS-2, used as soon as any character falls outside GSM-7
+    Ucs2,
+}
+
+impl SmsEncoding {
+    /// Number of encoding units available in a single-segment message
+    pub fn single_segment_capacity(self) -> usize {
+        match self {
+            SmsEncoding::Gsm7 => 160,
+            SmsEncoding::Ucs2 => 70,
+        }
+    }
+
+    /// Number of encoding units available per segment of a concatenated message
+    pub fn multi_segment_capacity(self) -> usize {
+        match self {
+            SmsEncoding::Gsm7 => 153,
+            SmsEncoding::Ucs2 => 67,
+        }
+    }
+}
+
+/// Encoding and segment count of a message body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SegmentInfo {
+    /// Encoding the body requires
+    pub encoding: SmsEncoding,
+    /// Length in encoding units: septets for GSM-7, UTF-16 code units for UCS-2
+    pub units: usize,
+    /// Number of SMS segments the body is split into
+    pub segments: usize,
+}
+
+impl SegmentInfo {
+    /// Multiplier on the per-message price: Twilio bills every segment as one message,
+    /// whatever its encoding, so a UCS-2 body costs more than the same length in GSM-7
+    pub fn cost_weight(&self) -> usize {
+        self.segments
+    }
+}
+
+/// Whether a character can be sent in the GSM-7 alphabet
+pub fn is_gsm7(c: char) -> bool {
+    GSM7_BASIC.contains(c) || GSM7_EXTENSION.contains(c)
+}
+
+/// Compute the encoding and number of segments needed to send a message body
+pub fn sms_segments(text: &str) -> SegmentInfo {
+    let (encoding, units) = if text.chars().all(is_gsm7) {
+        let units = text.chars().map(|c| if GSM7_EXTENSION.contains(c) { 2 } else { 1 }).sum();
+        (SmsEncoding::Gsm7, units)
+    } else {
+        (SmsEncoding::Ucs2, text.encode_utf16().count())
+    };
+
+    let segments = if units == 0 {
+        0
+    } else if units <= encoding.single_segment_capacity() {
+        1
+    } else {
+        units.div_ceil(encoding.multi_segment_capacity())
+    };
+
+    SegmentInfo { encoding, units, segments }
+}
+
+/// Split a body into parts of at most `max_segments` segments each
+///
+/// Parts are broken at word boundaries, cutting mid-word only when a single word is too
+/// long, and each part is measured in its own encoding since it is sent separately.
+pub fn split_body(text: &str, max_segments: usize) -> Vec<&str> {
+    let max_segments = max_segments.max(1);
+    let fits = |part: &str| sms_segments(part).segments <= max_segments;
+    let mut parts = Vec::new();
+    let mut rest = text.trim();
+    while !fits(rest) {
+        let mut cut = None;
+        let mut hard_cut = 0;
+        for (index, c) in rest.char_indices().skip(1) {
+            if !fits(&rest[..index]) {
+                break;
+            }
+            hard_cut = index;
+            if c.is_whitespace() {
+                cut = Some(index);
+            }
+        }
+        let cut = cut.unwrap_or(hard_cut.max(rest.chars().next().map_or(0, char::len_utf8)));
+        parts.push(rest[..cut].trim_end());
+        rest = rest[cut..].trim_start();
+    }
+    if !rest.is_empty() || parts.is_empty() {
+        parts.push(rest);
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gsm7_segments() {
+        assert_eq!(sms_segments("").segments, 0);
+        let info = sms_segments(&"a".repeat(160));
+        assert_eq!((info.encoding, info.units, info.segments), (SmsEncoding::Gsm7, 160, 1));
+        assert_eq!(sms_segments(&"a".repeat(161)).segments, 2);
+        // Extension characters take two septets
+        assert_eq!(sms_segments("[€]").units, 6);
+    }
+
+    #[test]
+    fn test_ucs2_segments() {
+        let info = sms_segments(&format!("{}😀", "a".repeat(68)));
+        assert_eq!(info.encoding, SmsEncoding::Ucs2);
+        assert_eq!(info.units, 70);
+        assert_eq!(info.segments, 1);
+        assert_eq!(sms_segments(&"ж".repeat(71)).segments, 2);
+        assert_eq!(sms_segments(&"ж".repeat(71)).cost_weight(), 2);
+    }
+
+    #[test]
+    fn test_split_body() {
+        let text = "word ".repeat(100);
+        let parts = split_body(&text, 1);
+        assert_eq!(parts.len(), 4);
+        assert!(parts.iter().all(|part| sms_segments(part).segments == 1 && part.ends_with("word")));
+        assert_eq!(parts.join(" "), text.trim());
+
+        assert_eq!(split_body(&"a".repeat(200), 1), ["a".repeat(160), "a".repeat(40)]);
+        assert_eq!(split_body("short", 1), ["short"]);
+    }
+}
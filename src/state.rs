@@ -0,0 +1,260 @@
+//! Per-call state carried in signed tokens on `action` and `Redirect` URLs
+//!
+//! Instead of keeping a session store, an application can serialize the state of a call
+//! into a token appended to the next webhook URL. [`StateSigner`] signs the JSON with
+//! HMAC-SHA256 so callers can't tamper with it and, with the `state-encryption` feature,
+//! can also encrypt it with ChaCha20-Poly1305 so callers can't read it.
+
+use std::error::Error;
+use std::fmt;
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use sha2::Sha256;
+
+/// Query parameter holding the token unless another name is configured
+pub const DEFAULT_STATE_PARAM: &str = "state";
+
+/// Error creating or reading a state token
+#[derive(Debug)]
+pub enum StateError {
+    /// The state couldn't be serialized or the token's payload couldn't be deserialized
+    Json(serde_json::Error),
+    /// The token is malformed or its signature doesn't match
+    InvalidToken,
+    /// The URL carries no token
+    MissingToken,
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateError::Json(err) => write!(f, "invalid state payload: {}", err),
+            StateError::InvalidToken => f.write_str("state token is malformed or has an invalid signature"),
+            StateError::MissingToken => f.write_str("URL carries no state token"),
+        }
+    }
+}
+
+impl Error for StateError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            StateError::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Signs state into URL-safe tokens and verifies tokens from webhook URLs
+///
+/// Tokens have the form `<payload>.<mac>`, both Base64url without padding. Encrypted
+/// tokens start with `e.` and hold the nonce and ciphertext instead.
+#[derive(Clone)]
+pub struct StateSigner {
+    key: Vec<u8>,
+    param: String,
+    #[cfg(feature = "state-encryption")]
+    encrypt: bool,
+}
+
+impl StateSigner {
+    /// Create a signer with a secret key, which should be at least 32 random bytes
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        Self {
+            key: key.into(),
+            param: DEFAULT_STATE_PARAM.to_string(),
+            #[cfg(feature = "state-encryption")]
+            encrypt: false,
+        }
+    }
+
+    /// Set the query parameter carrying the token
+    pub fn param(mut self, name: impl Into<String>) -> Self {
+        self.param = name.into();
+        self
+    }
+
+    /// Encrypt tokens so the state is hidden from callers as well as tamper-proof
+    #[cfg(feature = "state-encryption")]
+    pub fn encrypt(mut self, encrypt: bool) -> Self {
+        self.encrypt = encrypt;
+        self
+    }
+
+    fn mac(&self) -> Hmac<Sha256> {
+        Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts keys of any length")
+    }
+
+    /// Serialize state into a token
+    pub fn sign<T: Serialize + ?Sized>(&self, state: &T) -> Result<String, StateError> {
+        let payload = serde_json::to_vec(state).map_err(StateError::Json)?;
+        #[cfg(feature = "state-encryption")]
+        if self.encrypt {
+            return Ok(self.seal(&payload));
+        }
+        let mut mac = self.mac();
+        mac.update(&payload);
+        Ok(format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(&payload),
+            URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+        ))
+    }
+
+    /// Verify a token and deserialize its state
+    ///
+    /// Signed and encrypted tokens are both accepted when encryption is available, so
+    /// turning encryption on doesn't break calls already in progress.
+    pub fn verify<T: DeserializeOwned>(&self, token: &str) -> Result<T, StateError> {
+        let payload = match token.split_once('.') {
+            #[cfg(feature = "state-encryption")]
+            Some(("e", sealed)) => self.open(sealed)?,
+            Some((payload, signature)) => {
+                let payload = URL_SAFE_NO_PAD.decode(payload).map_err(|_| StateError::InvalidToken)?;
+                let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| StateError::InvalidToken)?;
+                let mut mac = self.mac();
+                mac.update(&payload);
+                mac.verify_slice(&signature).map_err(|_| StateError::InvalidToken)?;
+                payload
+            }
+            None => return Err(StateError::InvalidToken),
+        };
+        serde_json::from_slice(&payload).map_err(StateError::Json)
+    }
+
+    /// Append a token for the state to a URL as a query parameter
+    pub fn url<T: Serialize + ?Sized>(&self, url: &str, state: &T) -> Result<String, StateError> {
+        let token = self.sign(state)?;
+        let (base, fragment) = match url.split_once('#') {
+            Some((base, fragment)) => (base, Some(fragment)),
+            None => (url, None),
+        };
+        let separator = match base.find('?') {
+            Some(index) if index + 1 == base.len() || base.ends_with('&') => "",
+            Some(_) => "&",
+            None => "?",
+        };
+        let mut url = format!("{}{}{}={}", base, separator, self.param, token);
+        if let Some(fragment) = fragment {
+            url.push('#');
+            url.push_str(fragment);
+        }
+        Ok(url)
+    }
+
+    /// Find the token in a webhook URL and deserialize its state
+    pub fn extract<T: DeserializeOwned>(&self, url: &str) -> Result<T, StateError> {
+        let query = url.split('#').next().unwrap_or_default().split_once('?').map(|(_, query)| query);
+        let token = query
+            .into_iter()
+            .flat_map(|query| query.split('&'))
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(name, _)| *name == self.param)
+            .map(|(_, token)| token)
+            .ok_or(StateError::MissingToken)?;
+        self.verify(token)
+    }
+
+    #[cfg(feature = "state-encryption")]
+    fn cipher(&self) -> chacha20poly1305::ChaCha20Poly1305 {
+        use chacha20poly1305::KeyInit;
+
+        // Derive a separate encryption key so the signing key is never used as a cipher key
+        let mut mac = self.mac();
+        mac.update(b"twiml state encryption");
+        chacha20poly1305::ChaCha20Poly1305::new(&mac.finalize().into_bytes())
+    }
+
+    #[cfg(feature = "state-encryption")]
+    fn seal(&self, payload: &[u8]) -> String {
+        use chacha20poly1305::AeadCore;
+        use chacha20poly1305::aead::{Aead, OsRng};
+
+        let nonce = chacha20poly1305::ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let mut sealed = nonce.to_vec();
+        sealed.extend(self.cipher().encrypt(&nonce, payload).expect("payload fits in a single message"));
+        format!("e.{}", URL_SAFE_NO_PAD.encode(sealed))
+    }
+
+    #[cfg(feature = "state-encryption")]
+    fn open(&self, sealed: &str) -> Result<Vec<u8>, StateError> {
+        use chacha20poly1305::aead::Aead;
+
+        let sealed = URL_SAFE_NO_PAD.decode(sealed).map_err(|_| StateError::InvalidToken)?;
+        if sealed.len() < 12 {
+            return Err(StateError::InvalidToken);
+        }
+        let (nonce, ciphertext) = sealed.split_at(12);
+        self.cipher()
+            .decrypt(nonce.into(), ciphertext)
+            .map_err(|_| StateError::InvalidToken)
+    }
+}
+
+impl fmt::Debug for StateSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StateSigner").field("param", &self.param).finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for StateSigner {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::twiml::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Menu {
+        step: u32,
+        choice: Option<String>,
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let signer = StateSigner::new("0123456789abcdef0123456789abcdef");
+        let state = Menu { step: 2, choice: Some("sales".to_string()) };
+
+        let url = signer.url("/menu?lang=en", &state).unwrap();
+        assert!(url.starts_with("/menu?lang=en&state="));
+        let xml_string = Response::new().redirect(url.as_str()).to_xml_string();
+        assert!(xml_string.contains(&format!("<Redirect>{}</Redirect>", url.replace('&', "&amp;"))));
+
+        let webhook_url = format!("https://example.com{}", url);
+        assert_eq!(signer.extract::<Menu>(&webhook_url).unwrap(), state);
+        assert!(matches!(signer.extract::<Menu>("/menu?lang=en"), Err(StateError::MissingToken)));
+    }
+
+    #[test]
+    fn test_tampered_tokens_are_rejected() {
+        let signer = StateSigner::new("secret").param("s");
+        let token = signer.sign(&Menu { step: 1, choice: None }).unwrap();
+        let (_, signature) = token.split_once('.').unwrap();
+        let forged = format!("{}.{}", URL_SAFE_NO_PAD.encode(r#"{"step":9,"choice":null}"#), signature);
+
+        assert!(matches!(signer.verify::<Menu>(&forged), Err(StateError::InvalidToken)));
+        assert!(StateSigner::new("other").verify::<Menu>(&token).is_err());
+        assert_eq!(signer.extract::<Menu>(&format!("/next?s={}", token)).unwrap().step, 1);
+    }
+
+    #[cfg(feature = "state-encryption")]
+    #[test]
+    fn test_encrypted_tokens() {
+        let signer = StateSigner::new("secret").encrypt(true);
+        let token = signer.sign(&Menu { step: 3, choice: Some("billing".to_string()) }).unwrap();
+        assert!(token.starts_with("e."));
+        assert!(!String::from_utf8_lossy(&URL_SAFE_NO_PAD.decode(&token[2..]).unwrap()).contains("billing"));
+        assert_eq!(signer.verify::<Menu>(&token).unwrap().choice.as_deref(), Some("billing"));
+        assert!(StateSigner::new("other").encrypt(true).verify::<Menu>(&token).is_err());
+    }
+}
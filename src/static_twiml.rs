@@ -0,0 +1,382 @@
+//! Documents checked and embedded at compile time
+//!
+//! Static responses such as an after-hours greeting never change, so there is no need
+//! to build and serialize them on every request. [`include_twiml!`](crate::include_twiml)
+//! embeds an XML file and [`check_static`] verifies it while compiling: the file must be
+//! well-formed, have a single `Response` root and use only known TwiML element names,
+//! with SSML tags allowed inside `Say`. Well-formed includes the rules Twilio's parser
+//! enforces that are easy to miss when writing XML by hand: `&` must start an entity
+//! reference, attribute values can't contain `<`, and no attribute may appear twice.
+
+use crate::error::TwiMLError;
+
+/// TwiML element names accepted by [`check_static`]
+const ELEMENT_NAMES: &[&str] = &[
+    "Application", "ApplicationSid", "Body", "Client", "Conference", "Config", "Connect", "Conversation", "ConversationRelay", "Denoise", "Dial",
+    "Enqueue", "Gather", "Hangup", "Identity", "Language", "Leave", "Media", "Message", "Number", "Parameter",
+    "Pause", "Pay", "Play", "Prompt", "Queue", "Receive", "Record", "Redirect", "Refer", "Reject", "Response",
+    "Room", "Say", "Sip", "Siprec", "Sms", "Start", "Stop", "Stream", "Task", "Transcription", "VirtualAgent",
+];
+
+/// Deepest element nesting [`check_static`] accepts
+const MAX_DEPTH: usize = 16;
+
+/// Most attributes on one element [`check_static`] accepts
+const MAX_ATTRIBUTES: usize = 32;
+
+/// Entities predefined by XML, the only named references allowed without a DTD
+const ENTITIES: &[&[u8]] = &[b"amp;", b"lt;", b"gt;", b"quot;", b"apos;"];
+
+const fn starts_with(bytes: &[u8], at: usize, prefix: &[u8]) -> bool {
+    if at + prefix.len() > bytes.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < prefix.len() {
+        if bytes[at + i] != prefix[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn find(bytes: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    let mut at = from;
+    while at + needle.len() <= bytes.len() {
+        if starts_with(bytes, at, needle) {
+            return Some(at);
+        }
+        at += 1;
+    }
+    None
+}
+
+/// Skip past the end of markup closed by `terminator`
+macro_rules! skip_past {
+    ($bytes:expr, $at:expr, $terminator:expr) => {
+        match find($bytes, $at, $terminator) {
+            Some(end) => end + $terminator.len(),
+            None => return Err("unterminated markup in TwiML document"),
+        }
+    };
+}
+
+const fn same_name(bytes: &[u8], a: (usize, usize), b: (usize, usize)) -> bool {
+    if a.1 - a.0 != b.1 - b.0 {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.1 - a.0 {
+        if bytes[a.0 + i] != bytes[b.0 + i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn is_twiml_name(bytes: &[u8], name: (usize, usize)) -> bool {
+    let mut index = 0;
+    while index < ELEMENT_NAMES.len() {
+        let candidate = ELEMENT_NAMES[index].as_bytes();
+        if candidate.len() == name.1 - name.0 && starts_with(bytes, name.0, candidate) {
+            return true;
+        }
+        index += 1;
+    }
+    false
+}
+
+const fn is_space(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\r' | b'\n')
+}
+
+const fn is_name_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b':' | b'.')
+}
+
+/// Get the position after the entity or character reference starting with the `&` at
+/// `at`, or `None` if it isn't a valid reference
+const fn skip_reference(bytes: &[u8], at: usize) -> Option<usize> {
+    let mut index = 0;
+    while index < ENTITIES.len() {
+        if starts_with(bytes, at + 1, ENTITIES[index]) {
+            return Some(at + 1 + ENTITIES[index].len());
+        }
+        index += 1;
+    }
+    if !starts_with(bytes, at + 1, b"#") {
+        return None;
+    }
+    let hex = starts_with(bytes, at + 2, b"x");
+    let start = if hex { at + 3 } else { at + 2 };
+    let mut end = start;
+    while end < bytes.len() && (bytes[end].is_ascii_digit() || (hex && bytes[end].is_ascii_hexdigit())) {
+        end += 1;
+    }
+    if end == start || end == bytes.len() || bytes[end] != b';' {
+        return None;
+    }
+    Some(end + 1)
+}
+
+const fn skip_space(bytes: &[u8], mut at: usize) -> usize {
+    while at < bytes.len() && is_space(bytes[at]) {
+        at += 1;
+    }
+    at
+}
+
+/// Check a TwiML document at compile time, returning it unchanged
+///
+/// Used in a `const` item, a problem with the document fails the build with a message
+/// describing it. Called at runtime, the same problems panic; use [`check_twiml`] to get
+/// an error instead.
+pub const fn check_static(xml: &'static str) -> &'static str {
+    match check(xml) {
+        Ok(()) => xml,
+        Err(reason) => panic!("{}", reason),
+    }
+}
+
+/// Check a TwiML document with the same rules as [`check_static`], at runtime
+pub fn check_twiml(xml: &str) -> Result<(), TwiMLError> {
+    check(xml).map_err(|reason| TwiMLError::Parse(reason.to_string()))
+}
+
+const fn check(xml: &str) -> Result<(), &'static str> {
+    let bytes = xml.as_bytes();
+    let mut at = skip_space(bytes, 0);
+    if starts_with(bytes, at, b"<?xml") {
+        at = skip_space(bytes, skip_past!(bytes, at, b"?>"));
+    }
+
+    let mut stack = [(0usize, 0usize); MAX_DEPTH];
+    let mut depth = 0;
+    let mut say_depth = 0;
+    let mut root_closed = false;
+    while at < bytes.len() {
+        if bytes[at] != b'<' {
+            if depth == 0 && !is_space(bytes[at]) {
+                return Err("TwiML document has text outside the Response element");
+            }
+            at = if bytes[at] == b'&' {
+                match skip_reference(bytes, at) {
+                    Some(end) => end,
+                    None => return Err("unescaped & in TwiML document"),
+                }
+            } else {
+                at + 1
+            };
+            continue;
+        }
+        if starts_with(bytes, at, b"<!--") {
+            at = skip_past!(bytes, at, b"-->");
+            continue;
+        }
+        if starts_with(bytes, at, b"<![CDATA[") {
+            if depth == 0 {
+                return Err("TwiML document has text outside the Response element");
+            }
+            at = skip_past!(bytes, at, b"]]>");
+            continue;
+        }
+
+        let closing = starts_with(bytes, at, b"</");
+        let start = if closing { at + 2 } else { at + 1 };
+        let mut end = start;
+        while end < bytes.len() && is_name_byte(bytes[end]) {
+            end += 1;
+        }
+        if end == start {
+            return Err("malformed tag in TwiML document");
+        }
+        let name = (start, end);
+
+        // Read the attributes up to the end of the tag
+        let mut attributes = [(0usize, 0usize); MAX_ATTRIBUTES];
+        let mut attribute_count = 0;
+        at = skip_space(bytes, end);
+        while at < bytes.len() && bytes[at] != b'>' && bytes[at] != b'/' {
+            if closing || at == end {
+                return Err("malformed tag in TwiML document");
+            }
+            let attribute_start = at;
+            while at < bytes.len() && is_name_byte(bytes[at]) {
+                at += 1;
+            }
+            let attribute = (attribute_start, at);
+            if at == attribute_start {
+                return Err("malformed tag in TwiML document");
+            }
+            let mut index = 0;
+            while index < attribute_count {
+                if same_name(bytes, attributes[index], attribute) {
+                    return Err("duplicate attribute in TwiML document");
+                }
+                index += 1;
+            }
+            if attribute_count == MAX_ATTRIBUTES {
+                return Err("too many attributes in TwiML document");
+            }
+            attributes[attribute_count] = attribute;
+            attribute_count += 1;
+
+            at = skip_space(bytes, at);
+            if at == bytes.len() || bytes[at] != b'=' {
+                return Err("malformed tag in TwiML document");
+            }
+            at = skip_space(bytes, at + 1);
+            if at == bytes.len() || (bytes[at] != b'"' && bytes[at] != b'\'') {
+                return Err("malformed tag in TwiML document");
+            }
+            let quote = bytes[at];
+            at += 1;
+            while at < bytes.len() && bytes[at] != quote {
+                if bytes[at] == b'<' {
+                    return Err("unescaped < in TwiML attribute value");
+                }
+                at = if bytes[at] == b'&' {
+                    match skip_reference(bytes, at) {
+                        Some(end) => end,
+                        None => return Err("unescaped & in TwiML document"),
+                    }
+                } else {
+                    at + 1
+                };
+            }
+            if at == bytes.len() {
+                return Err("unterminated tag in TwiML document");
+            }
+            let next = at + 1;
+            at = skip_space(bytes, next);
+            if at < bytes.len() && at == next && bytes[at] != b'>' && bytes[at] != b'/' {
+                return Err("malformed tag in TwiML document");
+            }
+        }
+        let self_closing = at < bytes.len() && bytes[at] == b'/' && !closing;
+        if self_closing {
+            at += 1;
+        }
+        if at == bytes.len() || bytes[at] != b'>' {
+            return Err("unterminated tag in TwiML document");
+        }
+        at += 1;
+
+        if closing {
+            if depth == 0 || !same_name(bytes, stack[depth - 1], name) {
+                return Err("mismatched closing tag in TwiML document");
+            }
+            depth -= 1;
+            if say_depth > depth {
+                say_depth = 0;
+            }
+            if depth == 0 {
+                root_closed = true;
+            }
+            continue;
+        }
+
+        if depth == 0 && (root_closed || end - start != 8 || !starts_with(bytes, start, b"Response")) {
+            return Err("TwiML document must have a single Response root element");
+        }
+        if say_depth == 0 && !is_twiml_name(bytes, name) {
+            return Err("unknown TwiML element in document");
+        }
+        if say_depth == 0 && starts_with(bytes, start, b"Say") && end - start == 3 && !self_closing {
+            say_depth = depth + 1;
+        }
+        if self_closing {
+            if depth == 0 {
+                root_closed = true;
+            }
+            continue;
+        }
+        if depth == MAX_DEPTH {
+            return Err("TwiML document is nested too deeply");
+        }
+        stack[depth] = name;
+        depth += 1;
+    }
+
+    if depth != 0 || !root_closed {
+        return Err("TwiML document must have a single Response root element");
+    }
+    Ok(())
+}
+
+/// Embed a TwiML file as a `&'static str`, checking it at compile time
+///
+/// The path is resolved like [`include_str!`], relative to the file invoking the macro.
+///
+/// ```ignore
+/// const CLOSED: &str = twiml::include_twiml!("closed.xml");
+/// ```
+#[macro_export]
+macro_rules! include_twiml {
+    ($path:expr) => {{
+        const XML: &str = $crate::check_static(include_str!($path));
+        XML
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLOSED: &str = check_static(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<Response>
+  <!-- Played outside business hours -->
+  <Say voice="Polly.Joanna">We are closed. <break time="500ms"/>Please call <emphasis>tomorrow</emphasis>.</Say>
+  <Hangup/>
+</Response>"#,
+    );
+
+    #[test]
+    fn test_check_static() {
+        assert!(CLOSED.contains("<Hangup/>"));
+        assert_eq!(check_static("<Response><Say>a &gt; b</Say></Response>"), "<Response><Say>a &gt; b</Say></Response>");
+        assert!(std::panic::catch_unwind(|| check_static("<Response><Sya>Hi</Sya></Response>")).is_err());
+        assert!(std::panic::catch_unwind(|| check_static("<Response><Say>Hi</Response>")).is_err());
+        assert!(std::panic::catch_unwind(|| check_static("<Response/><Response/>")).is_err());
+        assert!(std::panic::catch_unwind(|| check_static("<Say>Hi</Say>")).is_err());
+    }
+
+    #[test]
+    fn test_check_twiml() {
+        assert!(check_twiml("<Response><Hangup/></Response>").is_ok());
+        assert_eq!(
+            check_twiml("<Response><!-- open"),
+            Err(TwiMLError::Parse("unterminated markup in TwiML document".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_check_twiml_escaping() {
+        assert!(check_twiml("<Response><Say>Fish &amp; chips &#169; &#xA9;</Say></Response>").is_ok());
+        assert_eq!(
+            check_twiml("<Response><Say>Fish & chips</Say></Response>"),
+            Err(TwiMLError::Parse("unescaped & in TwiML document".to_string()))
+        );
+        assert!(check_twiml("<Response><Say>&nbsp;</Say></Response>").is_err());
+        assert!(check_twiml("<Response><Say>&#xZ;</Say></Response>").is_err());
+        assert!(check_twiml(r#"<Response><Redirect method="GET">/a?x=1&y=2</Redirect></Response>"#).is_err());
+    }
+
+    #[test]
+    fn test_check_twiml_attributes() {
+        assert!(check_twiml(r#"<Response><Say voice = 'alice' language="en-US">Hi</Say></Response>"#).is_ok());
+        assert_eq!(
+            check_twiml(r#"<Response><Say voice="a<b">Hi</Say></Response>"#),
+            Err(TwiMLError::Parse("unescaped < in TwiML attribute value".to_string()))
+        );
+        assert_eq!(
+            check_twiml(r#"<Response><Say voice="alice" voice="man">Hi</Say></Response>"#),
+            Err(TwiMLError::Parse("duplicate attribute in TwiML document".to_string()))
+        );
+        assert!(check_twiml(r#"<Response><Play loop="2"digits="1"/></Response>"#).is_err());
+        assert!(check_twiml("<Response><Say voice>Hi</Say></Response>").is_err());
+    }
+}
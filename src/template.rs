@@ -0,0 +1,121 @@
+//! Tera filters and functions for templates that produce TwiML
+//!
+//! [`register`] adds:
+//!
+//! - `twiml_escape`, a filter that strips characters XML disallows and escapes the rest for
+//!   use in text or attribute values
+//! - `twiml_say(text, voice?, language?)`, a function rendering a complete `<Say>` element
+//!
+//! Both mark their output as safe, so Tera's own autoescaping doesn't escape it twice.
+
+use std::collections::HashMap;
+
+use tera::{Error, Result, Tera, Value};
+
+use crate::sanitize::{sanitize, SanitizePolicy};
+use crate::twiml::{escape_text, render_fragment, Say, TwiMLElement};
+
+/// Register the TwiML filters and functions on a Tera instance
+pub fn register(tera: &mut Tera) {
+    tera.register_filter("twiml_escape", EscapeFilter);
+    tera.register_function("twiml_say", SayFunction);
+}
+
+/// Escape text for use in TwiML text or attribute values
+pub fn twiml_escape(text: &str) -> String {
+    escape_text(&sanitize(text, SanitizePolicy::Strip)).replace('"', "&quot;").replace('\'', "&apos;")
+}
+
+/// `twiml_escape` filter
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EscapeFilter;
+
+impl tera::Filter for EscapeFilter {
+    fn filter(&self, value: &Value, _args: &HashMap<String, Value>) -> Result<Value> {
+        let text = match value {
+            Value::String(text) => twiml_escape(text),
+            Value::Null => String::new(),
+            other => twiml_escape(&other.to_string()),
+        };
+        Ok(Value::String(text))
+    }
+
+    fn is_safe(&self) -> bool {
+        true
+    }
+}
+
+/// `twiml_say` function
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SayFunction;
+
+impl tera::Function for SayFunction {
+    fn call(&self, args: &HashMap<String, Value>) -> Result<Value> {
+        let string = |name: &str| -> Result<Option<&str>> {
+            match args.get(name) {
+                None | Some(Value::Null) => Ok(None),
+                Some(Value::String(value)) => Ok(Some(value)),
+                Some(_) => Err(Error::msg(format!("`twiml_say`: `{}` must be a string", name))),
+            }
+        };
+
+        let text = string("text")?.ok_or_else(|| Error::msg("`twiml_say` requires a `text` argument"))?;
+        let mut say = Say::new(text);
+        if let Some(voice) = string("voice")? {
+            say = say.voice(voice);
+        }
+        if let Some(language) = string("language")? {
+            say = say.language(language);
+        }
+        Ok(Value::String(render_fragment(&say.to_xml())))
+    }
+
+    fn is_safe(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tera::Context;
+
+    use super::*;
+
+    fn tera() -> Tera {
+        let mut tera = Tera::default();
+        register(&mut tera);
+        tera.autoescape_on(vec![".xml"]);
+        tera
+    }
+
+    #[test]
+    fn test_escape_filter() {
+        let mut context = Context::new();
+        context.insert("name", "Tom & \"Jerry\" <3\u{b}");
+        let mut tera = tera();
+        tera.add_raw_template("greeting.xml", "<Say>Hello {{ name | twiml_escape }}</Say>").unwrap();
+
+        assert_eq!(
+            tera.render("greeting.xml", &context).unwrap(),
+            "<Say>Hello Tom &amp; &quot;Jerry&quot; &lt;3</Say>"
+        );
+    }
+
+    #[test]
+    fn test_say_function() {
+        let mut context = Context::new();
+        context.insert("prompt", "Press 1 & hold");
+        let mut tera = tera();
+        tera.add_raw_template(
+            "menu.xml",
+            r#"<Response>{{ twiml_say(text=prompt, voice="alice") }}</Response>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            tera.render("menu.xml", &context).unwrap(),
+            r#"<Response><Say voice="alice">Press 1 &amp; hold</Say></Response>"#
+        );
+        assert!(tera.render_str("{{ twiml_say(voice=\"alice\") }}", &context).is_err());
+    }
+}
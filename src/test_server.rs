@@ -0,0 +1,587 @@
+//! Local webhook server and mock Twilio client for integration tests
+//!
+//! [`TestServer`] runs an application's webhook handler on a local port. [`MockTwilio`]
+//! plays Twilio's side of the exchange: it sends the form fields and `X-Twilio-Signature`
+//! header a real voice or SMS webhook would carry to a plain `http://` URL, such as one
+//! from [`TestServer::url`], and [`WebhookReply::twiml`] checks that the reply is a TwiML
+//! document.
+//!
+//! ```
+//! use twiml::test_server::*;
+//! use twiml::*;
+//!
+//! let server = TestServer::start(|webhook| {
+//!     let path = if webhook.is_signed_by("secret") { "/menu" } else { "/forged" };
+//!     WebhookReply::xml(Response::new().redirect(path).to_xml_string())
+//! })
+//! .unwrap();
+//!
+//! let twilio = MockTwilio::new("secret");
+//! let reply = twilio.voice_call("+15550001111", "+15552223333").send(&server.url("/voice")).unwrap();
+//! assert!(reply.twiml().unwrap().contains("<Redirect>/menu</Redirect>"));
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread::{self, JoinHandle};
+
+use crate::signature::{SignatureValidator, signature};
+
+/// Error sending a webhook or checking the reply
+#[derive(Debug)]
+pub enum TestServerError {
+    /// Connecting, reading or writing failed
+    Io(io::Error),
+    /// The peer sent a malformed HTTP message
+    Http(String),
+    /// The webhook replied with a status other than 200
+    Status(u16),
+    /// The webhook replied without an XML content type
+    ContentType(Option<String>),
+    /// The reply body is not a TwiML document
+    NotTwiML(String),
+}
+
+impl fmt::Display for TestServerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TestServerError::Io(err) => write!(f, "webhook I/O failed: {}", err),
+            TestServerError::Http(reason) => write!(f, "malformed HTTP message: {}", reason),
+            TestServerError::Status(status) => write!(f, "webhook returned HTTP {}", status),
+            TestServerError::ContentType(content_type) => {
+                write!(f, "expected an XML content type, got {:?}", content_type)
+            }
+            TestServerError::NotTwiML(reason) => write!(f, "reply is not TwiML: {}", reason),
+        }
+    }
+}
+
+impl Error for TestServerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TestServerError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for TestServerError {
+    fn from(err: io::Error) -> Self {
+        TestServerError::Io(err)
+    }
+}
+
+/// Local HTTP server passing each webhook request to a handler
+///
+/// Requests are served one at a time on a background thread, in the order they arrive.
+/// Dropping the server stops the thread.
+#[derive(Debug)]
+pub struct TestServer {
+    address: SocketAddr,
+    stopped: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl TestServer {
+    /// Listen on a free port on `127.0.0.1` and answer every request with `handler`
+    pub fn start<F>(handler: F) -> Result<Self, TestServerError>
+    where
+        F: Fn(&IncomingWebhook) -> WebhookReply + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let address = listener.local_addr()?;
+        let stopped = Arc::new(AtomicBool::new(false));
+        let thread = thread::spawn({
+            let stopped = Arc::clone(&stopped);
+            move || {
+                for stream in listener.incoming() {
+                    if stopped.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        // A client that hangs up early only affects its own request
+                        let _ = serve(stream, &handler);
+                    }
+                }
+            }
+        });
+        Ok(Self { address, stopped, thread: Some(thread) })
+    }
+
+    /// Get the address the server listens on
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Get the `http://` URL of `path` on this server
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{}", self.address, path)
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        // Wake the blocked accept so the thread sees the flag
+        let _ = TcpStream::connect(self.address);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Read one request from `stream`, run `handler` and write its reply
+fn serve<F>(stream: TcpStream, handler: &F) -> Result<(), TestServerError>
+where
+    F: Fn(&IncomingWebhook) -> WebhookReply,
+{
+    let mut reader = BufReader::new(stream);
+    let reply = match IncomingWebhook::read(&mut reader) {
+        Ok(webhook) => handler(&webhook),
+        Err(TestServerError::Http(reason)) => WebhookReply {
+            status: 400,
+            headers: vec![("Content-Type".to_string(), "text/plain".to_string())],
+            body: reason,
+        },
+        Err(err) => return Err(err),
+    };
+    let mut stream = reader.into_inner();
+    stream.write_all(reply.to_http().as_bytes())?;
+    Ok(())
+}
+
+/// Webhook request received by a [`TestServer`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncomingWebhook {
+    /// HTTP method
+    pub method: String,
+    /// Request path, including any query string
+    pub path: String,
+    /// Request headers in the order received
+    pub headers: Vec<(String, String)>,
+    /// Decoded form parameters in the order received
+    pub params: Vec<(String, String)>,
+}
+
+impl IncomingWebhook {
+    fn read(reader: &mut impl BufRead) -> Result<Self, TestServerError> {
+        let malformed = |reason: &str| TestServerError::Http(reason.to_string());
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let mut parts = line.split_whitespace();
+        let (method, path) = match (parts.next(), parts.next()) {
+            (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+            _ => return Err(malformed("malformed HTTP request line")),
+        };
+
+        let mut headers = Vec::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                return Err(malformed("incomplete HTTP request"));
+            }
+            let header = line.trim_end_matches(['\r', '\n']);
+            if header.is_empty() {
+                break;
+            }
+            let (name, value) = header.split_once(':').ok_or_else(|| malformed("malformed HTTP header"))?;
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+
+        let mut webhook = Self { method, path, headers, params: Vec::new() };
+        let length = match webhook.header("Content-Length") {
+            Some(length) => length.parse().map_err(|_| malformed("malformed Content-Length"))?,
+            None => 0,
+        };
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body)?;
+        webhook.params = String::from_utf8_lossy(&body)
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (form_decode(key), form_decode(value))
+            })
+            .collect();
+        Ok(webhook)
+    }
+
+    /// Get a header value, matching the name case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Get the first value of a form parameter
+    pub fn param(&self, key: &str) -> Option<&str> {
+        self.params.iter().find(|(k, _)| k == key).map(|(_, value)| value.as_str())
+    }
+
+    /// Get the URL the request was sent to, rebuilt from the `Host` header and path
+    pub fn url(&self) -> String {
+        format!("http://{}{}", self.header("Host").unwrap_or_default(), self.path)
+    }
+
+    /// Whether `X-Twilio-Signature` matches the request signed with `auth_token`
+    pub fn is_signed_by(&self, auth_token: &str) -> bool {
+        match self.header("X-Twilio-Signature") {
+            Some(signature) => SignatureValidator::new(auth_token).is_valid(&self.url(), &self.params, signature),
+            None => false,
+        }
+    }
+}
+
+/// Simulated Twilio account sending webhook requests
+#[derive(Debug)]
+pub struct MockTwilio {
+    auth_token: String,
+    account_sid: String,
+    next_sid: AtomicU64,
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for MockTwilio {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.auth_token);
+    }
+}
+
+impl MockTwilio {
+    /// Create a mock account signing requests with `auth_token`
+    pub fn new(auth_token: impl Into<String>) -> Self {
+        Self {
+            auth_token: auth_token.into(),
+            account_sid: format!("AC{:032x}", 1),
+            next_sid: AtomicU64::new(1),
+        }
+    }
+
+    /// Set the AccountSid sent with every request
+    pub fn account_sid(mut self, sid: impl Into<String>) -> Self {
+        self.account_sid = sid.into();
+        self
+    }
+
+    /// Generate a unique SID with the given two-letter prefix
+    fn sid(&self, prefix: &str) -> String {
+        format!("{}{:032x}", prefix, self.next_sid.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn request(&self, params: Vec<(&str, String)>) -> WebhookRequest {
+        let mut request = WebhookRequest {
+            auth_token: self.auth_token.clone(),
+            params: Vec::new(),
+        };
+        request = request.param("AccountSid", self.account_sid.clone()).param("ApiVersion", "2010-04-01");
+        for (key, value) in params {
+            request = request.param(key, value);
+        }
+        request
+    }
+
+    /// Webhook for an incoming call that has just started ringing
+    pub fn voice_call(&self, from: impl Into<String>, to: impl Into<String>) -> WebhookRequest {
+        let (from, to) = (from.into(), to.into());
+        self.request(vec![
+            ("CallSid", self.sid("CA")),
+            ("From", from.clone()),
+            ("Caller", from),
+            ("To", to.clone()),
+            ("Called", to),
+            ("CallStatus", "ringing".to_string()),
+            ("Direction", "inbound".to_string()),
+        ])
+    }
+
+    /// Webhook for an incoming SMS
+    pub fn sms(&self, from: impl Into<String>, to: impl Into<String>, body: impl Into<String>) -> WebhookRequest {
+        let sid = self.sid("SM");
+        self.request(vec![
+            ("MessageSid", sid.clone()),
+            ("SmsSid", sid.clone()),
+            ("SmsMessageSid", sid),
+            ("From", from.into()),
+            ("To", to.into()),
+            ("Body", body.into()),
+            ("NumMedia", "0".to_string()),
+            ("NumSegments", "1".to_string()),
+            ("SmsStatus", "received".to_string()),
+        ])
+    }
+}
+
+/// Signed webhook request ready to be sent to an application
+#[derive(Debug, Clone)]
+pub struct WebhookRequest {
+    auth_token: String,
+    params: Vec<(String, String)>,
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for WebhookRequest {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.auth_token);
+    }
+}
+
+impl WebhookRequest {
+    /// Add or replace a form parameter
+    pub fn param(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        let (key, value) = (key.into(), value.into());
+        match self.params.iter_mut().find(|(k, _)| *k == key) {
+            Some(param) => param.1 = value,
+            None => self.params.push((key, value)),
+        }
+        self
+    }
+
+    /// Get the form parameters in the order they were added
+    pub fn params(&self) -> &[(String, String)] {
+        &self.params
+    }
+
+    /// Get the `X-Twilio-Signature` this request carries when sent to `url`
+    pub fn signature(&self, url: &str) -> String {
+        signature(&self.auth_token, url, &self.params)
+    }
+
+    /// POST the request to an `http://` URL and read the reply
+    pub fn send(&self, url: &str) -> Result<WebhookReply, TestServerError> {
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "only http:// URLs are supported"))?;
+        let (host, path) = match rest.find('/') {
+            Some(index) => (&rest[..index], &rest[index..]),
+            None => (rest, "/"),
+        };
+        let address = if host.contains(':') { host.to_string() } else { format!("{}:80", host) };
+
+        let body = self
+            .params
+            .iter()
+            .map(|(key, value)| format!("{}={}", form_encode(key), form_encode(value)))
+            .collect::<Vec<_>>()
+            .join("&");
+        let head = format!(
+            "POST {} HTTP/1.0\r\nHost: {}\r\nUser-Agent: TwilioProxy/1.1\r\nContent-Type: application/x-www-form-urlencoded\r\nX-Twilio-Signature: {}\r\nContent-Length: {}\r\n\r\n",
+            path,
+            host,
+            self.signature(url),
+            body.len()
+        );
+
+        let mut stream = TcpStream::connect(address)?;
+        stream.write_all(head.as_bytes())?;
+        stream.write_all(body.as_bytes())?;
+        let mut raw = String::new();
+        stream.read_to_string(&mut raw)?;
+        WebhookReply::parse(&raw)
+    }
+}
+
+/// Percent-encode a form value, writing spaces as `+`
+fn form_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'*' => encoded.push(byte as char),
+            b' ' => encoded.push('+'),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Decode a form value, reading `+` as a space
+fn form_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// HTTP reply an application returned to a webhook request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookReply {
+    /// HTTP status code
+    pub status: u16,
+    /// Response headers in the order received
+    pub headers: Vec<(String, String)>,
+    /// Response body
+    pub body: String,
+}
+
+impl WebhookReply {
+    /// Create a 200 reply carrying a TwiML document, for a [`TestServer`] handler
+    pub fn xml(body: impl Into<String>) -> Self {
+        Self {
+            status: 200,
+            headers: vec![("Content-Type".to_string(), "text/xml".to_string())],
+            body: body.into(),
+        }
+    }
+
+    fn parse(raw: &str) -> Result<Self, TestServerError> {
+        let malformed = |reason: &str| TestServerError::Http(reason.to_string());
+        let (head, body) = raw.split_once("\r\n\r\n").ok_or_else(|| malformed("incomplete HTTP response"))?;
+        let mut lines = head.split("\r\n");
+        let status = lines
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(|| malformed("malformed HTTP status line"))?;
+        let headers = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+            .collect();
+        Ok(Self { status, headers, body: body.to_string() })
+    }
+
+    /// Write the reply as an HTTP/1.0 response
+    fn to_http(&self) -> String {
+        let reason = match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            403 => "Forbidden",
+            404 => "Not Found",
+            500 => "Internal Server Error",
+            _ => "Unknown",
+        };
+        let mut http = format!("HTTP/1.0 {} {}\r\n", self.status, reason);
+        for (name, value) in &self.headers {
+            if !name.eq_ignore_ascii_case("Content-Length") {
+                http.push_str(&format!("{}: {}\r\n", name, value));
+            }
+        }
+        http.push_str(&format!("Content-Length: {}\r\n\r\n", self.body.len()));
+        http.push_str(&self.body);
+        http
+    }
+
+    /// Get a header value, matching the name case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Check that the reply is a successful TwiML document and return its body
+    ///
+    /// This checks the status, the content type and that the root element is `Response`;
+    /// it does not validate the verbs inside the document.
+    pub fn twiml(&self) -> Result<&str, TestServerError> {
+        let not_twiml = |reason: &str| TestServerError::NotTwiML(reason.to_string());
+        if self.status != 200 {
+            return Err(TestServerError::Status(self.status));
+        }
+        match self.header("Content-Type") {
+            Some(content_type) if content_type.contains("xml") => {}
+            other => return Err(TestServerError::ContentType(other.map(str::to_string))),
+        }
+
+        let mut document = self.body.trim();
+        if document.starts_with("<?xml") {
+            let end = document.find("?>").ok_or_else(|| not_twiml("unterminated XML declaration"))?;
+            document = document[end + 2..].trim_start();
+        }
+        let is_response = document.starts_with("<Response")
+            && document[9..].starts_with(['>', '/', ' ', '\t', '\r', '\n'])
+            && (document.ends_with("</Response>") || document.ends_with("/>"));
+        if !is_response {
+            return Err(not_twiml("root element is not Response"));
+        }
+        Ok(&self.body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::twiml::*;
+
+    #[test]
+    fn test_send_voice_webhook() {
+        let (sender, received) = mpsc::channel();
+        let server = TestServer::start(move |webhook| {
+            sender.send(webhook.clone()).unwrap();
+            if !webhook.is_signed_by("secret") {
+                return WebhookReply { status: 403, headers: Vec::new(), body: String::new() };
+            }
+            let digits = webhook.param("Digits").unwrap_or_default();
+            WebhookReply::xml(Response::new().redirect(format!("/menu/{}", digits)).to_xml_string())
+        })
+        .unwrap();
+        let url = server.url("/voice");
+
+        let twilio = MockTwilio::new("secret");
+        let request = twilio.voice_call("+15550001111", "+15552223333").param("Digits", "1 2");
+        let reply = request.send(&url).unwrap();
+        assert!(reply.twiml().unwrap().contains("<Redirect>/menu/1 2</Redirect>"));
+
+        let webhook = received.recv().unwrap();
+        assert_eq!((webhook.method.as_str(), webhook.path.as_str()), ("POST", "/voice"));
+        assert_eq!(webhook.url(), url);
+        assert_eq!(webhook.header("x-twilio-signature"), Some(request.signature(&url).as_str()));
+        assert_eq!(webhook.params, request.params());
+        assert_eq!(webhook.param("From"), Some("+15550001111"));
+
+        let forged = MockTwilio::new("wrong").sms("+15550001111", "+15552223333", "Hi");
+        assert!(matches!(forged.send(&url).unwrap().twiml(), Err(TestServerError::Status(403))));
+        assert!(!received.recv().unwrap().is_signed_by("secret"));
+    }
+
+    #[test]
+    fn test_send_errors() {
+        let server = TestServer::start(|_| WebhookReply::xml("<Responses/>")).unwrap();
+        let reply = MockTwilio::new("secret").sms("+1", "+2", "Hi").send(&server.url("/sms")).unwrap();
+        assert!(matches!(reply.twiml(), Err(TestServerError::NotTwiML(_))));
+
+        let address = server.address();
+        drop(server);
+        let error = MockTwilio::new("secret").sms("+1", "+2", "Hi").send(&format!("http://{}/sms", address));
+        assert!(matches!(error, Err(TestServerError::Io(_))));
+        assert!(matches!(
+            MockTwilio::new("secret").sms("+1", "+2", "Hi").send("https://example.com/sms"),
+            Err(TestServerError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_reply_must_be_twiml() {
+        let reply = WebhookReply {
+            status: 200,
+            headers: vec![("content-type".to_string(), "text/plain".to_string())],
+            body: "<Response/>".to_string(),
+        };
+        assert!(matches!(reply.twiml(), Err(TestServerError::ContentType(Some(_)))));
+        let reply = WebhookReply { status: 500, ..reply };
+        assert_eq!(reply.twiml().unwrap_err().to_string(), "webhook returned HTTP 500");
+        assert_eq!(form_decode("a%2Bb+c%zz"), "a+b c%zz");
+    }
+}
@@ -1,1095 +1,5072 @@
 use xml_builder::{XMLBuilder, XMLElement, XMLVersion};
-use std::fmt::Debug;
+use std::any::Any;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt::{self, Debug};
+use std::hash::{Hash, Hasher};
+#[cfg(feature = "voice")]
+use std::num::NonZeroU32;
+#[cfg(feature = "voice")]
+use std::ops::BitOr;
+#[cfg(feature = "voice")]
+use std::str::FromStr;
+use std::sync::Arc;
+#[cfg(feature = "voice")]
+use std::time::Duration;
+
+#[cfg(feature = "voice")]
+use serde::Serialize;
+
+use crate::error::TwiMLError;
+use crate::serializer::Serializer;
+use crate::sanitize::{sanitize, SanitizePolicy, TextNormalization};
+#[cfg(feature = "voice")]
+use crate::validation::{ValidationError, MAX_SAY_CHARACTERS};
 
 /// Custom trait for XML string conversion
 pub trait ToXmlString {
     /// Convert the element to an XML string
+    ///
+    /// # Panics
+    ///
+    /// Panics if an element has both text and child elements, which XML output can't
+    /// represent; use [`try_to_xml_string`](ToXmlString::try_to_xml_string) to handle that.
     fn to_xml_string(&self) -> String;
+
+    /// Convert the element to an XML string, failing if it can't be serialized or is
+    /// larger than [`MAX_DOCUMENT_BYTES`]
+    fn try_to_xml_string(&self) -> Result<String, TwiMLError>;
+
+    /// Convert the element to an XML string with attributes in canonical order, so the
+    /// output doesn't depend on the order setters were called
+    ///
+    /// See [`ElementFactory::canonicalize`]. Panics like
+    /// [`to_xml_string`](ToXmlString::to_xml_string).
+    fn to_canonical_xml_string(&self) -> String;
 }
 
 /// Trait for TwiML elements that can be converted to XML
-pub trait TwiMLElement: Debug {
+pub trait TwiMLElement: Debug + Any + Send + Sync {
+    /// Access the generic node backing the element
+    fn factory(&self) -> &ElementFactory;
+
+    /// Mutably access the generic node backing the element
+    fn factory_mut(&mut self) -> &mut ElementFactory;
+
     /// Convert the element to an XMLElement
-    fn to_xml(&self) -> XMLElement;
+    ///
+    /// Documents of up to [`FAST_PATH_MAX_ELEMENTS`] elements are serialized straight from
+    /// [`factory`](TwiMLElement::factory) without calling this, so an override must
+    /// describe the same element.
+    fn to_xml(&self) -> XMLElement {
+        self.factory().to_xml()
+    }
 }
 
+/// Largest document Twilio accepts from a webhook, in bytes
+pub const MAX_DOCUMENT_BYTES: usize = 64 * 1024;
+
+/// Largest document, in elements, serialized without building an `xml_builder` tree
+///
+/// Most webhook responses are a handful of verbs, for which allocating an element tree
+/// and escaping into intermediate strings costs more than the output itself.
+pub const FAST_PATH_MAX_ELEMENTS: usize = 16;
+
 /// Implement ToXmlString for TwiMLElement
 impl<T: TwiMLElement> ToXmlString for T {
     fn to_xml_string(&self) -> String {
-        let mut xml = XMLBuilder::new()
-            .version(XMLVersion::XML1_1)
-            .encoding("UTF-8".into())
-            .break_lines(false)
-            .indent(false)
-            .build();
-        
-        xml.set_root_element(self.to_xml());
-        
-        let mut writer = Vec::new();
-        xml.generate(&mut writer).unwrap();
-        
-        String::from_utf8(writer).unwrap()
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("twiml.serialize", element = self.factory().name()).entered();
+
+        let xml = Serializer::new().render(self).unwrap_or_else(|error| panic!("{}", error));
+
+        #[cfg(feature = "tracing")]
+        {
+            let node = self.factory();
+            tracing::debug!(
+                bytes = xml.len(),
+                verbs = node.children().len(),
+                elements = count_elements(node),
+                "serialized TwiML"
+            );
+            if xml.len() > MAX_DOCUMENT_BYTES {
+                tracing::warn!(bytes = xml.len(), limit = MAX_DOCUMENT_BYTES, "TwiML document exceeds Twilio's size limit");
+            }
+        }
+        xml
+    }
+
+    fn to_canonical_xml_string(&self) -> String {
+        Serializer::new().canonical(true).render(self).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    fn try_to_xml_string(&self) -> Result<String, TwiMLError> {
+        Serializer::new().to_string(self)
     }
 }
 
-/// Module containing all TwiML element implementations
-mod elements {
-    use super::*;
+/// Count an element and all of its descendants
+#[cfg(feature = "tracing")]
+fn count_elements(node: &ElementFactory) -> usize {
+    1 + node.children().iter().map(|child| count_elements(child.factory())).sum::<usize>()
+}
 
-    #[derive(Debug)]
-    struct ElementFactory {
-        element: String,
-        text: Option<String>,
-        attributes: Vec<(String, String)>,
-        children: Vec<Box<dyn TwiMLElement>>,
+/// Render an element without an XML declaration, for embedding in another document
+#[cfg_attr(not(feature = "tera"), allow(dead_code))]
+pub(crate) fn render_fragment(element: &XMLElement) -> String {
+    let mut writer = Vec::new();
+    element.render(&mut writer, false, false, false, false).expect("writing to a Vec cannot fail");
+    String::from_utf8(writer).expect("XML output is UTF-8")
+}
+
+/// Escape text content; xml_builder only escapes attribute values
+pub(crate) fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Split text into chunks of at most `limit` characters, preferring sentence boundaries,
+/// then word boundaries, and cutting mid-word only when a single word is too long
+#[cfg(feature = "voice")]
+fn split_sentences(text: &str, limit: usize) -> Vec<&str> {
+    let limit = limit.max(1);
+    let mut chunks = Vec::new();
+    let mut rest = text.trim();
+    while rest.chars().count() > limit {
+        let end = rest.char_indices().nth(limit).map_or(rest.len(), |(index, _)| index);
+        let window = &rest[..end];
+        let sentence = window
+            .char_indices()
+            .rev()
+            .find(|&(index, c)| {
+                matches!(c, '.' | '!' | '?' | '…' | '。' | '！' | '？')
+                    && rest[index + c.len_utf8()..].starts_with(char::is_whitespace)
+            })
+            .map(|(index, c)| index + c.len_utf8());
+        let cut = sentence
+            .or_else(|| window.rfind(char::is_whitespace).filter(|&index| index > 0))
+            .unwrap_or(end);
+        chunks.push(rest[..cut].trim_end());
+        rest = rest[cut..].trim_start();
+    }
+    if !rest.is_empty() || chunks.is_empty() {
+        chunks.push(rest);
     }
+    chunks
+}
 
-    impl ElementFactory {
-        /// Create a new ElementFactory element
-        pub fn new(element: impl Into<String>, text: Option<impl Into<String>>) -> Self {
-            Self {
-                element: element.into(),
-                text: if let Some(text) = text {Some(text.into())} else {None},
-                attributes: Vec::new(),
-                children: Vec::new(),
+/// Percent-encode everything but RFC 3986 unreserved characters
+#[cfg(feature = "voice")]
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Mask phone numbers written in E.164 style: `+` followed by at least 7 digits,
+/// optionally separated by spaces, dashes, dots or parentheses
+fn mask_phone_numbers(text: &str) -> Cow<'_, str> {
+    if !text.contains('+') {
+        return Cow::Borrowed(text);
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('+') {
+        out.push_str(&rest[..start]);
+        let candidate = &rest[start + 1..];
+        let mut end = 0;
+        let mut digits = 0;
+        for (index, c) in candidate.char_indices() {
+            if c.is_ascii_digit() {
+                digits += 1;
+                end = index + 1;
+            } else if !matches!(c, ' ' | '-' | '.' | '(' | ')') || digits == 0 {
+                break;
             }
         }
+        if digits >= 7 {
+            out.push_str(REDACTED_NUMBER);
+            rest = &candidate[end..];
+        } else {
+            out.push('+');
+            rest = candidate;
+        }
     }
+    out.push_str(rest);
+    Cow::Owned(out)
+}
 
-    impl TwiMLElement for ElementFactory {
-        fn to_xml(&self) -> XMLElement {
-            let mut elem = XMLElement::new(&self.element);
+/// Placeholder for phone numbers masked in text
+const REDACTED_NUMBER: &str = "[redacted number]";
 
-            // Add attributes
-            for (key, value) in &self.attributes {
-                elem.add_attribute(key, value);
-            }
+/// 64-bit FNV-1a hash of the concatenated byte slices, stable across Rust versions and platforms
+pub(crate) fn fnv1a<'a>(parts: impl IntoIterator<Item = &'a [u8]>) -> u64 {
+    parts.into_iter().flatten().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
 
-            // Add children
-            for child in &self.children {
-                elem.add_child(child.to_xml()).unwrap();
-            }
+/// Wrap text in a CDATA section, splitting it around any `]]>` it contains
+fn cdata(text: &str) -> String {
+    format!("<![CDATA[{}]]>", text.replace("]]>", "]]]]><![CDATA[>"))
+}
 
-            // Add text (use owned String)
-            if let Some(text) = self.text.clone() {
-                elem.add_text(text).unwrap();
-            }
+/// Declaration `render_document` starts every document with
+const XML_DECLARATION: &str = r#"<?xml version="1.1" encoding="UTF-8"?>"#;
 
-            elem
-        }
+/// Render a small document straight into a string, producing the same output as
+/// `render_document`
+///
+/// Returns `None` for documents of more than [`FAST_PATH_MAX_ELEMENTS`] elements and for
+/// elements with both text and children, leaving them and their errors to `xml_builder`.
+pub(crate) fn render_small(root: &ElementFactory) -> Option<String> {
+    let mut elements = 0;
+    let mut bytes = XML_DECLARATION.len();
+    if !root.measure(&mut elements, &mut bytes) {
+        return None;
     }
+    let mut xml = String::with_capacity(bytes);
+    xml.push_str(XML_DECLARATION);
+    root.write_small(&mut xml);
+    Some(xml)
+}
 
-    /// Say TwiML Element for text-to-speech
-    #[derive(Debug)]
-    pub struct Say {
-        factory: ElementFactory,
+/// Append `value` with the escaping `xml_builder` applies to attribute values, or for
+/// text the escaping of [`escape_text`]
+fn push_escaped(out: &mut String, value: &str, attribute: bool) {
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' if attribute => out.push_str("&quot;"),
+            '\'' if attribute => out.push_str("&apos;"),
+            c => out.push(c),
+        }
     }
+}
 
-    impl Say {
-        /// Create a new Say element
-        pub fn new(text: impl Into<String>) -> Self {
-            Self {
-                factory: ElementFactory::new("Say", Some(text)),
+/// Render a root element as a complete XML document
+pub(crate) fn render_document(root: XMLElement) -> String {
+    let mut xml = XMLBuilder::new()
+        .version(XMLVersion::XML1_1)
+        .encoding("UTF-8".into())
+        .break_lines(false)
+        .indent(false)
+        .build();
+
+    xml.set_root_element(root);
+
+    let mut writer = Vec::new();
+    xml.generate(&mut writer).expect("writing to a Vec cannot fail");
+
+    String::from_utf8(writer).expect("XML output is UTF-8")
+}
+
+/// Module containing all TwiML element implementations
+mod elements {
+    use super::*;
+
+    /// Attributes whose values are masked in `Debug` output
+    pub(crate) const SENSITIVE_ATTRIBUTES: &[&str] = &["password", "paymentConnector"];
+
+    /// Placeholder printed in place of sensitive values
+    const REDACTED: &str = "[redacted]";
+
+    /// Mask the user part of a SIP URI such as `sip:jane.doe@example.com`, keeping the host
+    /// and any headers so the log still shows where the call went
+    fn mask_sip_user(uri: &str) -> String {
+        let (scheme, rest) = match uri.split_once(':') {
+            Some((scheme, rest)) if scheme.eq_ignore_ascii_case("sip") || scheme.eq_ignore_ascii_case("sips") => {
+                (&uri[..=scheme.len()], rest)
             }
+            _ => ("", uri),
+        };
+        match rest.split_once('@') {
+            Some((_, host)) => format!("{}{}@{}", scheme, REDACTED, host),
+            None => mask_phone_numbers(uri).into_owned(),
         }
+    }
 
-        /// Set voice attribute
-        pub fn voice(mut self, voice: impl Into<String>) -> Self {
-            self.factory.attributes.push(("voice".to_string(), voice.into()));
-            self
-        }
+    /// Attributes additionally masked by [`Response::to_redacted_string`]: phone numbers,
+    /// digits sent on the caller's behalf and SIP usernames
+    const PERSONAL_ATTRIBUTES: &[&str] = &["to", "from", "callerId", "sendDigits", "digits", "username"];
 
-        /// Set language attribute
-        pub fn language(mut self, language: impl Into<String>) -> Self {
-            self.factory.attributes.push(("language".to_string(), language.into()));
-            self
-        }
-        
-        /// Set loop attribute
-        pub fn loop_times(mut self, loops: usize) -> Self {
-            self.factory.attributes.push(("loop".to_string(), loops.to_string()));
-            self
-        }
-        
-        /// Set pitch attribute
-        pub fn pitch(mut self, pitch: impl Into<String>) -> Self {
-            self.factory.attributes.push(("pitch".to_string(), pitch.into()));
-            self
-        }
-        
-        /// Set rate attribute
-        pub fn rate(mut self, rate: impl Into<String>) -> Self {
-            self.factory.attributes.push(("rate".to_string(), rate.into()));
-            self
+    /// How an element's text is written
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[cfg_attr(not(feature = "voice"), allow(dead_code))]
+    enum TextFormat {
+        /// Escaped character data
+        Escaped,
+        /// Wrapped in a CDATA section
+        Cdata,
+        /// Already-escaped mixed content, such as text runs interleaved with SSML tags
+        Markup,
+    }
+
+    /// Generic element node backing every TwiML element
+    ///
+    /// Attribute setters on the elements replace any earlier value of the same attribute,
+    /// so calling one twice keeps the last value.
+    pub struct ElementFactory {
+        element: String,
+        text: Option<Cow<'static, str>>,
+        attributes: Vec<(String, String)>,
+        children: Vec<Box<dyn TwiMLElement>>,
+        text_format: TextFormat,
+    }
+
+    /// Wipe the text and attribute values when the element is dropped
+    ///
+    /// Say and Number text and Pay parameters are as sensitive as passwords, so every
+    /// value is wiped, and each child wipes its own when it is dropped in turn. Only the
+    /// element's own copy is wiped; text borrowed from a `&'static str`, XML built with
+    /// `to_xml` and serialized strings are not, so the latter should be dropped as soon as
+    /// they have been sent.
+    #[cfg(feature = "zeroize")]
+    impl Drop for ElementFactory {
+        fn drop(&mut self) {
+            use zeroize::Zeroize;
+
+            for (_, value) in &mut self.attributes {
+                value.zeroize();
+            }
+            if let Some(Cow::Owned(text)) = &mut self.text {
+                text.zeroize();
+            }
         }
     }
 
-    impl TwiMLElement for Say {
-        fn to_xml(&self) -> XMLElement {
-            self.factory.to_xml()
+    impl Debug for ElementFactory {
+        /// Print the element like a derived `Debug`, masking [`SENSITIVE_ATTRIBUTES`]
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let attributes: Vec<(&str, &str)> = self
+                .attributes
+                .iter()
+                .map(|(key, value)| {
+                    let value = if SENSITIVE_ATTRIBUTES.contains(&key.as_str()) { REDACTED } else { value.as_str() };
+                    (key.as_str(), value)
+                })
+                .collect();
+            f.debug_struct("ElementFactory")
+                .field("element", &self.element)
+                .field("text", &self.text)
+                .field("attributes", &attributes)
+                .field("children", &self.children)
+                .field("text_format", &self.text_format)
+                .finish()
         }
     }
 
-    /// Gather TwiML Element for collecting user input
-    #[derive(Debug)]
-    pub struct Gather {
-        factory: ElementFactory,
+    impl Hash for ElementFactory {
+        /// Hash the element's name, text, attributes and children, in document order
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.element.hash(state);
+            self.text.hash(state);
+            self.attributes.hash(state);
+            self.text_format.hash(state);
+            self.children.len().hash(state);
+            for child in &self.children {
+                child.factory().hash(state);
+            }
+        }
     }
 
-    impl Gather {
-        /// Create a new Gather element
-        pub fn new() -> Self {
+    impl ElementFactory {
+        /// Create a new ElementFactory element
+        pub fn new(element: impl Into<String>, text: Option<impl Into<String>>) -> Self {
             Self {
-                factory: ElementFactory::new("Gather", None::<String>),
+                element: element.into(),
+                text: text.map(|text| Cow::Owned(text.into())),
+                attributes: Vec::new(),
+                children: Vec::new(),
+                text_format: TextFormat::Escaped,
             }
         }
 
-        /// Set action attribute
-        pub fn action(mut self, action: impl Into<String>) -> Self {
-            self.factory.attributes.push(("action".to_string(), action.into()));
-            self
+        /// Create a node whose text borrows a static string, such as a fixed prompt,
+        /// instead of copying it into every document
+        pub fn with_static_text(element: &str, text: &'static str) -> Self {
+            let mut factory = Self::new(element, None::<String>);
+            factory.text = Some(Cow::Borrowed(text));
+            factory
         }
 
-        /// Set method attribute
-        pub fn method(mut self, method: impl Into<String>) -> Self {
-            self.factory.attributes.push(("method".to_string(), method.into()));
-            self
+        /// Get the element name
+        pub fn name(&self) -> &str {
+            &self.element
         }
 
-        /// Set number of digits to collect
-        pub fn num_digits(mut self, num: impl Into<String>) -> Self {
-            self.factory.attributes.push(("numDigits".to_string(), num.into()));
-            self
-        }
-        
-        /// Set timeout attribute
-        pub fn timeout(mut self, timeout: usize) -> Self {
-            self.factory.attributes.push(("timeout".to_string(), timeout.to_string()));
-            self
-        }
-        
-        /// Set input attribute (dtmf, speech, or dtmf speech)
-        pub fn input(mut self, input: impl Into<String>) -> Self {
-            self.factory.attributes.push(("input".to_string(), input.into()));
-            self
-        }
-        
-        /// Set language attribute
-        pub fn language(mut self, language: impl Into<String>) -> Self {
-            self.factory.attributes.push(("language".to_string(), language.into()));
-            self
-        }
-        
-        /// Set finishOnKey attribute
-        pub fn finish_on_key(mut self, key: impl Into<String>) -> Self {
-            self.factory.attributes.push(("finishOnKey".to_string(), key.into()));
-            self
-        }
-        
-        /// Set hints attribute for speech recognition
-        pub fn hints(mut self, hints: impl Into<String>) -> Self {
-            self.factory.attributes.push(("hints".to_string(), hints.into()));
-            self
+        /// Get the text content, if any
+        pub fn text(&self) -> Option<&str> {
+            self.text.as_deref()
         }
 
-        /// Add a Say child element
-        pub fn say(mut self, say: Say) -> Self {
-            self.factory.children.push(Box::new(say));
-            self
-        }
-        
-        /// Add a Play child element
-        pub fn play(mut self, play: Play) -> Self {
-            self.factory.children.push(Box::new(play));
-            self
-        }
-        
-        /// Add a Pause child element
-        pub fn pause(mut self, pause: Pause) -> Self {
-            self.factory.children.push(Box::new(pause));
-            self
+        /// Get the text as spoken: for a Say built from SSML parts, the text runs with
+        /// tags removed and entities decoded; otherwise the same as [`text`](Self::text)
+        pub fn plain_text(&self) -> Option<Cow<'_, str>> {
+            let text = self.text.as_deref()?;
+            if !self.is_markup() {
+                return Some(Cow::Borrowed(text));
+            }
+            let mut plain = String::with_capacity(text.len());
+            let mut rest = text;
+            while let Some(start) = rest.find('<') {
+                plain.push_str(&rest[..start]);
+                rest = rest[start..].find('>').map_or("", |end| &rest[start + end + 1..]);
+            }
+            plain.push_str(rest);
+            Some(Cow::Owned(plain.replace("&lt;", "<").replace("&gt;", ">").replace("&amp;", "&")))
         }
 
-        /// Set actionOnEmptyResult attribute
-        pub fn action_on_empty_result(mut self, enable: bool) -> Self {
-            self.factory.attributes.push(("actionOnEmptyResult".to_string(), enable.to_string()));
-            self
+        /// Whether the text is already-escaped markup that must be written as-is
+        pub(crate) fn is_markup(&self) -> bool {
+            self.text_format == TextFormat::Markup
         }
 
-        /// Set enhanced attribute for enhanced speech recognition
-        pub fn enhanced(mut self, enable: bool) -> Self {
-            self.factory.attributes.push(("enhanced".to_string(), enable.to_string()));
-            self
+        /// Get the text of the first child with the given name
+        pub(crate) fn child_text(&self, name: &str) -> Option<&str> {
+            self.children.iter().map(|child| child.factory()).find(|child| child.name() == name)?.text()
         }
 
-        /// Set speechModel attribute
-        pub fn speech_model(mut self, model: impl Into<String>) -> Self {
-            self.factory.attributes.push(("speechModel".to_string(), model.into()));
-            self
+        /// Get the attributes in the order they were set
+        pub fn attributes(&self) -> &[(String, String)] {
+            &self.attributes
         }
 
-        /// Set speechTimeout attribute (auto, none, or a number of milliseconds)
-        pub fn speech_timeout(mut self, timeout: impl Into<String>) -> Self {
-            self.factory.attributes.push(("speechTimeout".to_string(), timeout.into()));
-            self
+        /// Get the value of an attribute by name
+        pub fn attribute(&self, key: &str) -> Option<&str> {
+            self.attributes.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
         }
 
-        /// Set partialResultsCallback attribute
-        pub fn partial_results_callback(mut self, url: impl Into<String>) -> Self {
-            self.factory.attributes.push(("partialResultsCallback".to_string(), url.into()));
-            self
+        /// Set an attribute, replacing the first existing value in place and dropping any others
+        pub(crate) fn set_attribute(&mut self, key: &str, value: impl Into<String>) {
+            let value = value.into();
+            match self.attributes.iter().position(|(k, _)| k == key) {
+                Some(index) => {
+                    self.attributes[index].1 = value;
+                    let mut seen = 0;
+                    self.attributes.retain(|(k, _)| {
+                        seen += usize::from(k == key);
+                        k != key || seen == 1
+                    });
+                }
+                None => self.attributes.push((key.to_string(), value)),
+            }
         }
 
-        /// Set partialResultsCallbackMethod attribute
-        pub fn partial_results_callback_method(mut self, method: impl Into<String>) -> Self {
-            self.factory.attributes.push(("partialResultsCallbackMethod".to_string(), method.into()));
-            self
+        /// Get the child elements
+        pub fn children(&self) -> &[Box<dyn TwiMLElement>] {
+            &self.children
         }
 
-        /// Set profanityFilter attribute
-        pub fn profanity_filter(mut self, enable: bool) -> Self {
-            self.factory.attributes.push(("profanityFilter".to_string(), enable.to_string()));
-            self
+        /// Get the child element at an index
+        pub fn child(&self, index: usize) -> Option<&dyn TwiMLElement> {
+            self.children.get(index).map(|child| child.as_ref())
         }
 
-        /// Set speechResult attribute
-        pub fn speech_result(mut self, result_format: impl Into<String>) -> Self {
-            self.factory.attributes.push(("speechResult".to_string(), result_format.into()));
-            self
+        /// Mutably get the child element at an index
+        pub fn child_mut(&mut self, index: usize) -> Option<&mut (dyn TwiMLElement + 'static)> {
+            self.children.get_mut(index).map(|child| child.as_mut())
         }
 
-        /// Set interdigitTimeout attribute (seconds)
-        pub fn interdigit_timeout(mut self, timeout: usize) -> Self {
-            self.factory.attributes.push(("interdigitTimeout".to_string(), timeout.to_string()));
-            self
+        /// Remove and return the child element at an index
+        pub fn remove_child(&mut self, index: usize) -> Option<Box<dyn TwiMLElement>> {
+            (index < self.children.len()).then(|| self.children.remove(index))
         }
 
-        /// Set for attribute to specify the input mode: digits or speech (used with enhanced)
-        pub fn for_attr(mut self, for_value: impl Into<String>) -> Self {
-            self.factory.attributes.push(("for".to_string(), for_value.into()));
-            self
+        /// Replace the child element at an index, returning the previous child
+        pub fn replace_child(&mut self, index: usize, child: Box<dyn TwiMLElement>) -> Option<Box<dyn TwiMLElement>> {
+            self.children.get_mut(index).map(|slot| std::mem::replace(slot, child))
         }
 
-        /// Set statusCallback attribute
-        pub fn status_callback(mut self, url: impl Into<String>) -> Self {
-            self.factory.attributes.push(("statusCallback".to_string(), url.into()));
-            self
+        /// Insert a child element at an index, shifting later children
+        ///
+        /// # Panics
+        ///
+        /// Panics if `index` is greater than the number of children.
+        pub fn insert_child_at(&mut self, index: usize, child: Box<dyn TwiMLElement>) {
+            self.children.insert(index, child);
         }
 
-        /// Set statusCallbackMethod attribute
-        pub fn status_callback_method(mut self, method: impl Into<String>) -> Self {
-            self.factory.attributes.push(("statusCallbackMethod".to_string(), method.into()));
-            self
+        /// Keep only the child elements for which the predicate returns true
+        pub fn retain_children(&mut self, mut keep: impl FnMut(&dyn TwiMLElement) -> bool) {
+            self.children.retain(|child| keep(child.as_ref()));
         }
 
-        /// Set speechContexts attribute
-        pub fn speech_contexts(mut self, contexts: impl Into<String>) -> Self {
-            self.factory.attributes.push(("speechContexts".to_string(), contexts.into()));
-            self
+        /// Convert the element and its descendants to an XMLElement, failing if an
+        /// element has both text and child elements
+        pub fn try_to_xml(&self) -> Result<XMLElement, TwiMLError> {
+            self.build_xml(false)
         }
 
-        /// Set speechDetectorSensitivity attribute
-        pub fn speech_detector_sensitivity(mut self, sensitivity: impl Into<String>) -> Self {
-            self.factory.attributes.push(("speechDetectorSensitivity".to_string(), sensitivity.into()));
-            self
+        /// Convert the element and its descendants to an XMLElement with attributes in
+        /// canonical order, as [`canonicalize`](ElementFactory::canonicalize) leaves them
+        pub fn try_to_canonical_xml(&self) -> Result<XMLElement, TwiMLError> {
+            self.build_xml(true)
         }
 
-        /// Add a Prompt child element
-        pub fn prompt(mut self, prompt: Prompt) -> Self {
-            self.factory.children.push(Box::new(prompt));
-            self
-        }
-    }
+        fn build_xml(&self, canonical: bool) -> Result<XMLElement, TwiMLError> {
+            let serialization = |error: xml_builder::XMLError| {
+                TwiMLError::Serialization(format!("<{}>: {}", self.element, error))
+            };
+            let mut elem = XMLElement::new(&self.element);
 
-    impl TwiMLElement for Gather {
-        fn to_xml(&self) -> XMLElement {
-            self.factory.to_xml()
-        }
-    }
+            // Add attributes
+            let attributes = if canonical {
+                self.canonical_attributes()
+            } else {
+                self.attributes.iter().map(|(key, value)| (key.as_str(), value.as_str())).collect()
+            };
+            for (key, value) in attributes {
+                elem.add_attribute(key, &sanitize(value, SanitizePolicy::Strip));
+            }
 
-    /// Prompt TwiML Element for real-time enhanced speech recognition
-    #[derive(Debug)]
-    pub struct Prompt {
-        factory: ElementFactory,
-    }
+            // Add children
+            for child in &self.children {
+                elem.add_child(child.factory().build_xml(canonical)?).map_err(serialization)?;
+            }
 
-    impl Prompt {
-        /// Create a new Prompt element
-        pub fn new(text: impl Into<String>) -> Self {
-            Self {
-                factory: ElementFactory::new("Prompt", Some(text)),
+            // Add text
+            if let Some(text) = self.xml_text() {
+                elem.add_text(text).map_err(serialization)?;
             }
+
+            Ok(elem)
         }
 
-        /// Set attempt attribute
-        pub fn attempt(mut self, attempt: usize) -> Self {
-            self.factory.attributes.push(("attempt".to_string(), attempt.to_string()));
-            self
+        /// Count this element and its descendants and estimate their serialized size,
+        /// returning false if the fast path can't render them
+        pub(crate) fn measure(&self, elements: &mut usize, bytes: &mut usize) -> bool {
+            *elements += 1;
+            if *elements > FAST_PATH_MAX_ELEMENTS || (self.text.is_some() && !self.children.is_empty()) {
+                return false;
+            }
+            // Tags, plus some room for escaping
+            *bytes += 2 * self.element.len() + 5 + self.text.as_ref().map_or(0, |text| text.len() + 16);
+            *bytes += self.attributes.iter().map(|(key, value)| key.len() + value.len() + 4).sum::<usize>();
+            self.children.iter().all(|child| child.factory().measure(elements, bytes))
         }
 
-        /// Set for attribute
-        pub fn for_attr(mut self, for_value: impl Into<String>) -> Self {
-            self.factory.attributes.push(("for".to_string(), for_value.into()));
-            self
+        /// Write this element and its descendants without building an `XMLElement` tree
+        pub(crate) fn write_small(&self, out: &mut String) {
+            out.push('<');
+            out.push_str(&self.element);
+            for (key, value) in &self.attributes {
+                out.push(' ');
+                out.push_str(key);
+                out.push_str("=\"");
+                push_escaped(out, &sanitize(value, SanitizePolicy::Strip), true);
+                out.push('"');
+            }
+            match &self.text {
+                Some(text) => {
+                    out.push('>');
+                    let text = sanitize(text, SanitizePolicy::Strip);
+                    match self.text_format {
+                        TextFormat::Escaped => push_escaped(out, &text, false),
+                        TextFormat::Cdata => out.push_str(&cdata(&text)),
+                        TextFormat::Markup => out.push_str(&text),
+                    }
+                }
+                None if self.children.is_empty() => {
+                    out.push_str(" />");
+                    return;
+                }
+                None => {
+                    out.push('>');
+                    for child in &self.children {
+                        child.factory().write_small(out);
+                    }
+                }
+            }
+            out.push_str("</");
+            out.push_str(&self.element);
+            out.push('>');
         }
 
-        /// Set voice attribute
-        pub fn voice(mut self, voice: impl Into<String>) -> Self {
-            self.factory.attributes.push(("voice".to_string(), voice.into()));
-            self
+        /// Get the attributes sorted by name, keeping the last value of an attribute set twice
+        fn canonical_attributes(&self) -> Vec<(&str, &str)> {
+            let attributes: BTreeMap<&str, &str> =
+                self.attributes.iter().map(|(key, value)| (key.as_str(), value.as_str())).collect();
+            attributes.into_iter().collect()
         }
 
-        /// Set language attribute
-        pub fn language(mut self, language: impl Into<String>) -> Self {
-            self.factory.attributes.push(("language".to_string(), language.into()));
-            self
+        /// Hash the canonical form of this element and its descendants
+        ///
+        /// Attribute order and runs of whitespace in text don't affect the hash, and the
+        /// hash is FNV-1a over a fixed encoding, so it is stable across builds, platforms
+        /// and Rust versions and can be stored.
+        pub fn content_hash(&self) -> u64 {
+            let mut bytes = Vec::new();
+            self.write_canonical(&mut bytes);
+            fnv1a([bytes.as_slice()])
+        }
+
+        /// Append an unambiguous encoding of the canonical form, each string prefixed by its length
+        fn write_canonical(&self, bytes: &mut Vec<u8>) {
+            let mut write = |value: &str| {
+                bytes.extend((value.len() as u64).to_le_bytes());
+                bytes.extend(value.as_bytes());
+            };
+            write(&self.element);
+            let attributes = self.canonical_attributes();
+            write(&attributes.len().to_string());
+            for (key, value) in attributes {
+                write(key);
+                write(value);
+            }
+            let text = self.text.as_deref().map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "));
+            write(text.as_deref().unwrap_or_default());
+            write(&self.children.len().to_string());
+            for child in &self.children {
+                child.factory().write_canonical(bytes);
+            }
+        }
+
+        /// Encode this element and its descendants exactly as built, each string prefixed by
+        /// its length, so two trees have the same key only if they serialize identically
+        pub(crate) fn structure_key(&self) -> Vec<u8> {
+            let mut bytes = Vec::new();
+            self.write_structure(&mut bytes);
+            bytes
+        }
+
+        fn write_structure(&self, bytes: &mut Vec<u8>) {
+            let mut write = |value: &str| {
+                bytes.extend((value.len() as u64).to_le_bytes());
+                bytes.extend(value.as_bytes());
+            };
+            write(&self.element);
+            write(&self.attributes.len().to_string());
+            for (key, value) in &self.attributes {
+                write(key);
+                write(value);
+            }
+            match &self.text {
+                Some(text) => write(&format!("{:?}{}", self.text_format, text)),
+                None => write(""),
+            }
+            write(&self.children.len().to_string());
+            for child in &self.children {
+                child.factory().write_structure(bytes);
+            }
+        }
+
+        /// Put the attributes of this element and its descendants in canonical order
+        ///
+        /// Attributes are sorted by name and an attribute set more than once keeps its last
+        /// value, so documents that differ only in the order setters were called become
+        /// identical. Useful before hashing, caching or comparing documents.
+        pub fn canonicalize(&mut self) {
+            self.attributes = self
+                .canonical_attributes()
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect();
+            for child in &mut self.children {
+                child.factory_mut().canonicalize();
+            }
+        }
+
+        /// Copy this element and its descendants into plain ElementFactory nodes
+        fn copy_tree(&self) -> ElementFactory {
+            Self {
+                element: self.element.clone(),
+                text: self.text.clone(),
+                attributes: self.attributes.clone(),
+                children: self
+                    .children
+                    .iter()
+                    .map(|child| Box::new(child.factory().copy_tree()) as Box<dyn TwiMLElement>)
+                    .collect(),
+                text_format: self.text_format,
+            }
+        }
+
+        /// Copy this element and its descendants, masking values that identify callers or
+        /// carry credentials or payment data
+        fn redacted_tree(&self, in_pay: bool) -> ElementFactory {
+            let in_pay = in_pay || self.element == "Pay";
+            let attributes = self
+                .attributes
+                .iter()
+                .map(|(key, value)| {
+                    let masked = SENSITIVE_ATTRIBUTES.contains(&key.as_str())
+                        || PERSONAL_ATTRIBUTES.contains(&key.as_str())
+                        || (in_pay && self.element == "Parameter" && key == "value");
+                    let value = if masked { REDACTED.to_string() } else { mask_phone_numbers(value).into_owned() };
+                    (key.clone(), value)
+                })
+                .collect();
+            let text = match (&self.text, self.element.as_str()) {
+                (Some(_), "Number" | "Client" | "Identity") => Some(Cow::Borrowed(REDACTED)),
+                (Some(text), "Sip") => Some(Cow::Owned(mask_sip_user(text))),
+                (Some(text), _) => Some(Cow::Owned(mask_phone_numbers(text).into_owned())),
+                (None, _) => None,
+            };
+            Self {
+                element: self.element.clone(),
+                text,
+                attributes,
+                children: self
+                    .children
+                    .iter()
+                    .map(|child| Box::new(child.factory().redacted_tree(in_pay)) as Box<dyn TwiMLElement>)
+                    .collect(),
+                text_format: self.text_format,
+            }
+        }
+
+        /// Render the text content for XML output, sanitized and then escaped or wrapped in CDATA
+        pub(crate) fn xml_text(&self) -> Option<String> {
+            self.text.as_deref().map(|text| {
+                let text = sanitize(text, SanitizePolicy::Strip);
+                match self.text_format {
+                    TextFormat::Escaped => escape_text(&text),
+                    TextFormat::Cdata => cdata(&text),
+                    TextFormat::Markup => text.into_owned(),
+                }
+            })
+        }
+
+        /// Sanitize the text and attribute values of this element and its descendants,
+        /// returning how many values changed
+        pub fn sanitize(&mut self, policy: SanitizePolicy) -> usize {
+            let mut changed = 0;
+            if let Some(text) = &mut self.text
+                && let Cow::Owned(clean) = sanitize(text, policy)
+            {
+                *text = Cow::Owned(clean);
+                changed += 1;
+            }
+            for (_, value) in &mut self.attributes {
+                if let Cow::Owned(clean) = sanitize(value, policy) {
+                    *value = clean;
+                    changed += 1;
+                }
+            }
+            for child in &mut self.children {
+                changed += child.factory_mut().sanitize(policy);
+            }
+            changed
+        }
+
+        /// Normalize the text of this element and its descendants, counting only
+        /// `Say`, `Message` and `Body` text
+        pub(crate) fn normalize_text(&mut self, normalization: &TextNormalization) -> usize {
+            let mut changed = 0;
+            if matches!(self.element.as_str(), "Say" | "Message" | "Body")
+                && let Some(text) = &mut self.text
+                && let Cow::Owned(clean) = normalization.apply(text)
+            {
+                *text = Cow::Owned(clean);
+                changed += 1;
+            }
+            for child in &mut self.children {
+                changed += child.factory_mut().normalize_text(normalization);
+            }
+            changed
+        }
+    }
+
+    impl TwiMLElement for ElementFactory {
+        fn factory(&self) -> &ElementFactory {
+            self
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            self
         }
-    }
 
-    impl TwiMLElement for Prompt {
         fn to_xml(&self) -> XMLElement {
-            self.factory.to_xml()
+            self.try_to_xml().unwrap_or_else(|error| panic!("{}", error))
+        }
+    }
+
+    /// Number of times a Say or Play element repeats
+    ///
+    /// Twilio interprets `loop="0"` as "repeat until the call ends", which is rarely what a
+    /// caller passing a computed count of zero means. `Times` therefore cannot be zero and
+    /// infinite playback has to be requested with `Forever`.
+    #[cfg(feature = "voice")]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+    pub enum Loop {
+        /// Play a fixed number of times
+        Times(NonZeroU32),
+        /// Repeat until the call ends or the caller interrupts (`0`)
+        Forever,
+    }
+
+    #[cfg(feature = "voice")]
+    impl fmt::Display for Loop {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Loop::Times(times) => write!(f, "{}", times),
+                Loop::Forever => f.write_str("0"),
+            }
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl From<NonZeroU32> for Loop {
+        fn from(times: NonZeroU32) -> Self {
+            Loop::Times(times)
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl From<u32> for Loop {
+        /// Convert a loop count, treating 0 as [`Loop::Forever`] as the `loop` attribute does
+        fn from(times: u32) -> Self {
+            NonZeroU32::new(times).map_or(Loop::Forever, Loop::Times)
+        }
+    }
+
+    /// URL passed to an element's URL-valued attributes or text
+    ///
+    /// Accepts strings as-is and, with the `url` feature, parsed `url::Url` values, so
+    /// applications that already validate their URLs don't have to stringify them first.
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct UrlValue(String);
+
+    impl UrlValue {
+        /// Get the URL as a string
+        pub fn as_str(&self) -> &str {
+            &self.0
+        }
+    }
+
+    impl fmt::Display for UrlValue {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(&self.0)
+        }
+    }
+
+    impl From<&str> for UrlValue {
+        fn from(url: &str) -> Self {
+            Self(url.to_string())
+        }
+    }
+
+    impl From<&String> for UrlValue {
+        fn from(url: &String) -> Self {
+            Self(url.clone())
+        }
+    }
+
+    impl From<String> for UrlValue {
+        fn from(url: String) -> Self {
+            Self(url)
+        }
+    }
+
+    impl From<Cow<'_, str>> for UrlValue {
+        fn from(url: Cow<'_, str>) -> Self {
+            Self(url.into_owned())
+        }
+    }
+
+    #[cfg(feature = "url")]
+    impl From<url::Url> for UrlValue {
+        fn from(url: url::Url) -> Self {
+            Self(url.into())
+        }
+    }
+
+    #[cfg(feature = "url")]
+    impl From<&url::Url> for UrlValue {
+        fn from(url: &url::Url) -> Self {
+            Self(url.as_str().to_string())
+        }
+    }
+
+    impl From<UrlValue> for String {
+        fn from(url: UrlValue) -> Self {
+            url.0
+        }
+    }
+
+    /// Whole seconds for timeout and length attributes, from an integer or a [`Duration`]
+    ///
+    /// Durations round to the nearest second, except that a non-zero duration shorter than
+    /// half a second becomes one second rather than zero, which several attributes treat
+    /// as "no limit".
+    ///
+    /// Integer literals convert through the `usize` form, which is deprecated in favour of
+    /// [`Seconds::new`] but kept so existing calls such as `.timeout(5)` still compile;
+    /// a trait impl can't carry `#[deprecated]`.
+    #[cfg(feature = "voice")]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+    pub struct Seconds(u64);
+
+    #[cfg(feature = "voice")]
+    impl Seconds {
+        /// Create from a number of whole seconds
+        pub const fn new(seconds: u32) -> Self {
+            Self(seconds as u64)
+        }
+
+        /// Get the number of seconds
+        pub fn get(self) -> u64 {
+            self.0
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl fmt::Display for Seconds {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl From<usize> for Seconds {
+        fn from(seconds: usize) -> Self {
+            Self(seconds as u64)
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl From<Duration> for Seconds {
+        fn from(duration: Duration) -> Self {
+            let rounded = duration.as_secs() + u64::from(duration.subsec_millis() >= 500);
+            Self(if rounded == 0 && !duration.is_zero() { 1 } else { rounded })
+        }
+    }
+
+    /// Gather `speechTimeout`: `auto` or a number of seconds
+    #[cfg(feature = "voice")]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct SpeechTimeout(String);
+
+    #[cfg(feature = "voice")]
+    impl SpeechTimeout {
+        /// Stop listening once Twilio detects a pause in speech
+        pub fn auto() -> Self {
+            Self("auto".to_string())
+        }
+
+        /// Stop listening after a fixed number of seconds of silence; preferred over the
+        /// deprecated conversion from `usize`
+        pub fn seconds(seconds: u32) -> Self {
+            Self(seconds.to_string())
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl From<&str> for SpeechTimeout {
+        fn from(timeout: &str) -> Self {
+            Self(timeout.to_string())
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl From<String> for SpeechTimeout {
+        fn from(timeout: String) -> Self {
+            Self(timeout)
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl From<usize> for SpeechTimeout {
+        fn from(seconds: usize) -> Self {
+            Self(seconds.to_string())
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl From<Duration> for SpeechTimeout {
+        fn from(duration: Duration) -> Self {
+            Self(Seconds::from(duration).to_string())
+        }
+    }
+
+    /// DTMF tones for `sendDigits` and Play `digits`: keypad keys and `w` pauses
+    ///
+    /// Each `w` waits half a second, which is usually needed before an extension while
+    /// the far end's menu starts.
+    #[cfg(feature = "voice")]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+    pub struct Digits(String);
+
+    #[cfg(feature = "voice")]
+    impl Digits {
+        /// Create an empty digit string
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Parse a digit string made of `0`-`9`, `*`, `#` and `w` for `element`, which names
+        /// the element in errors: `Play` for its `digits` attribute, otherwise `sendDigits`
+        pub fn parse(element: &str, digits: &str) -> Result<Self, ValidationError> {
+            let attribute = if element == "Play" { "digits" } else { "sendDigits" };
+            match digits.chars().find(|&c| !Digits::is_valid(c)) {
+                Some(c) => Err(ValidationError {
+                    element: element.to_string(),
+                    attribute: Some(attribute.to_string()),
+                    child: None,
+                    reason: format!("`{}` is not a keypad key or `w` pause", c),
+                }),
+                None => Ok(Self(digits.to_string())),
+            }
+        }
+
+        /// Whether a character may appear in a digit string
+        pub fn is_valid(c: char) -> bool {
+            matches!(c, '0'..='9' | '*' | '#' | 'w')
+        }
+
+        /// Append a keypad key, failing if `key` is not `0`-`9`, `*` or `#`
+        ///
+        /// The digits aren't attached to an element yet, so errors name `Digits`.
+        pub fn digit(mut self, key: char) -> Result<Self, ValidationError> {
+            if !Digits::is_valid(key) || key == 'w' {
+                return Err(ValidationError {
+                    element: "Digits".to_string(),
+                    attribute: None,
+                    child: None,
+                    reason: format!("`{}` is not a keypad key", key),
+                });
+            }
+            self.0.push(key);
+            Ok(self)
+        }
+
+        /// Append a pause of `half_seconds` half-second waits
+        pub fn pause(mut self, half_seconds: usize) -> Self {
+            self.0.extend(std::iter::repeat_n('w', half_seconds));
+            self
+        }
+
+        /// Get the digit string
+        pub fn as_str(&self) -> &str {
+            &self.0
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl fmt::Display for Digits {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(&self.0)
+        }
+    }
+
+    /// Parse a digit string not yet attached to an element, so errors name `Digits`
+    #[cfg(feature = "voice")]
+    impl FromStr for Digits {
+        type Err = ValidationError;
+
+        fn from_str(digits: &str) -> Result<Self, Self::Err> {
+            Digits::parse("Digits", digits).map_err(|error| ValidationError { attribute: None, ..error })
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl From<Digits> for String {
+        fn from(digits: Digits) -> Self {
+            digits.0
+        }
+    }
+
+    /// SSML tags Twilio accepts inside Say
+    #[cfg(feature = "voice")]
+    pub const SSML_TAGS: &[&str] = &[
+        "amazon:domain",
+        "amazon:effect",
+        "break",
+        "emphasis",
+        "lang",
+        "p",
+        "phoneme",
+        "prosody",
+        "s",
+        "say-as",
+        "sub",
+        "w",
+    ];
+
+    /// Whether `name` matches the XML `Name` production, so it can be written as a tag or
+    /// attribute name without changing the document's structure
+    #[cfg(feature = "voice")]
+    fn is_xml_name(name: &str) -> bool {
+        let mut chars = name.chars();
+        chars.next().is_some_and(|c| c.is_alphabetic() || matches!(c, '_' | ':'))
+            && chars.all(|c| c.is_alphanumeric() || matches!(c, '_' | ':' | '-' | '.'))
+    }
+
+    /// Text run or SSML tag of a Say built with [`Say::from_parts`]
+    #[cfg(feature = "voice")]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum SayPart {
+        /// Plain text, escaped when written
+        Text(String),
+        /// `<break>` pause with a `time` such as `500ms` or `2s`
+        Break(String),
+        /// `<emphasis>` around text
+        Emphasis(String),
+        /// `<say-as>` around text, with an `interpret-as` such as `digits` or `date`
+        SayAs {
+            /// How to read the text
+            interpret_as: String,
+            /// Text to read
+            text: String,
+        },
+        /// Any other SSML tag, such as `prosody` or `phoneme`, around optional text
+        Tag {
+            /// Tag name
+            name: String,
+            /// Attributes in the order they are written
+            attributes: Vec<(String, String)>,
+            /// Text inside the tag, or `None` for an empty tag
+            text: Option<String>,
+        },
+    }
+
+    #[cfg(feature = "voice")]
+    impl From<&str> for SayPart {
+        fn from(text: &str) -> Self {
+            SayPart::Text(text.to_string())
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl From<String> for SayPart {
+        fn from(text: String) -> Self {
+            SayPart::Text(text)
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl From<&String> for SayPart {
+        fn from(text: &String) -> Self {
+            SayPart::Text(text.clone())
+        }
+    }
+
+    /// Say TwiML Element for text-to-speech
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let say = Say::new("Hello, world").voice("Polly.Joanna").language("en-US").loop_times(2);
+    /// assert_eq!(
+    ///     say.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Say voice="Polly.Joanna" language="en-US" loop="2">Hello, world</Say>"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
+    #[derive(Debug)]
+    pub struct Say {
+        factory: ElementFactory,
+    }
+
+    #[cfg(feature = "voice")]
+    impl Say {
+        /// Create a new Say element
+        pub fn new(text: impl Into<String>) -> Self {
+            Self {
+                factory: ElementFactory::new("Say", Some(text)),
+            }
+        }
+
+        /// Create a Say that borrows static text, such as a fixed greeting, instead of
+        /// copying it into every document
+        ///
+        /// Owned text passed to [`new`](Self::new) as a `String` is moved, not copied.
+        pub fn from_static(text: &'static str) -> Self {
+            Self {
+                factory: ElementFactory::with_static_text("Say", text),
+            }
+        }
+
+        /// Create a new Say element from text runs and SSML tags, in order, failing on a
+        /// tag that isn't in [`SSML_TAGS`] or an attribute name that isn't an XML name
+        ///
+        /// ```
+        /// use twiml::*;
+        ///
+        /// let say = Say::from_parts(["Hello ", "Marie", "."]).unwrap();
+        /// assert!(say.to_xml_string().ends_with("<Say>Hello Marie.</Say>"));
+        ///
+        /// let say = Say::from_parts([
+        ///     SayPart::from("Your code is "),
+        ///     SayPart::SayAs { interpret_as: "digits".into(), text: "1234".into() },
+        ///     SayPart::Break("500ms".into()),
+        ///     SayPart::Emphasis("Goodbye".into()),
+        /// ])
+        /// .unwrap();
+        /// assert!(say.to_xml_string().ends_with(concat!(
+        ///     r#"<Say>Your code is <say-as interpret-as="digits">1234</say-as>"#,
+        ///     r#"<break time="500ms" /><emphasis>Goodbye</emphasis></Say>"#,
+        /// )));
+        /// ```
+        pub fn from_parts(parts: impl IntoIterator<Item = impl Into<SayPart>>) -> Result<Self, ValidationError> {
+            parts.into_iter().try_fold(Say::new(""), |say, part| say.push_part(part.into()))
+        }
+
+        /// Append a text run or SSML tag, failing on a tag that isn't in [`SSML_TAGS`] or an
+        /// attribute name that isn't an XML name
+        pub fn push_part(mut self, part: SayPart) -> Result<Self, ValidationError> {
+            let (name, attributes, text) = match part {
+                SayPart::Text(text) => return Ok(self.push_text(text)),
+                SayPart::Break(time) => ("break".to_string(), vec![("time".to_string(), time)], None),
+                SayPart::Emphasis(text) => ("emphasis".to_string(), Vec::new(), Some(text)),
+                SayPart::SayAs { interpret_as, text } => {
+                    ("say-as".to_string(), vec![("interpret-as".to_string(), interpret_as)], Some(text))
+                }
+                SayPart::Tag { name, attributes, text } => (name, attributes, text),
+            };
+            let invalid = |reason: String| ValidationError {
+                element: "Say".to_string(),
+                attribute: None,
+                child: None,
+                reason,
+            };
+            if !SSML_TAGS.contains(&name.as_str()) {
+                return Err(invalid(format!("`{}` is not an SSML tag Twilio supports", name)));
+            }
+            if let Some((key, _)) = attributes.iter().find(|(key, _)| !is_xml_name(key)) {
+                return Err(invalid(format!("`{}` is not a valid attribute name on <{}>", key, name)));
+            }
+            let markup = self.markup_mut();
+            markup.push('<');
+            markup.push_str(&name);
+            for (key, value) in &attributes {
+                markup.push(' ');
+                markup.push_str(key);
+                markup.push_str("=\"");
+                push_escaped(markup, value, true);
+                markup.push('"');
+            }
+            match text {
+                Some(text) => {
+                    markup.push('>');
+                    push_escaped(markup, &text, false);
+                    markup.push_str("</");
+                    markup.push_str(&name);
+                    markup.push('>');
+                }
+                None => markup.push_str(" />"),
+            }
+            Ok(self)
+        }
+
+        /// Switch the text to escaped markup, escaping any text already set
+        fn markup_mut(&mut self) -> &mut String {
+            let factory = &mut self.factory;
+            if factory.text_format != TextFormat::Markup {
+                let text = factory.text.take().unwrap_or_default();
+                factory.text = Some(Cow::Owned(escape_text(&text)));
+                factory.text_format = TextFormat::Markup;
+            }
+            factory.text.get_or_insert_default().to_mut()
+        }
+
+        /// Split into consecutive Says of at most [`MAX_SAY_CHARACTERS`] characters each
+        ///
+        /// Text is broken at sentence boundaries where possible, and every part keeps the
+        /// original attributes. A Say with SSML parts, or one that loops, is returned
+        /// unchanged: a `loop` copied to every part would repeat each part on its own.
+        pub fn split(self) -> Vec<Say> {
+            self.split_with_limit(MAX_SAY_CHARACTERS)
+        }
+
+        /// Split into consecutive Says of at most `limit` characters each
+        pub fn split_with_limit(self, limit: usize) -> Vec<Say> {
+            let fits = self.factory.text.as_ref().is_none_or(|text| text.chars().count() <= limit);
+            let looping = self.factory.attribute("loop").is_some_and(|loops| loops != "1");
+            let markup = self.factory.text_format == TextFormat::Markup;
+            if fits || looping || markup || !self.factory.children.is_empty() {
+                return vec![self];
+            }
+            let text = self.factory.text.as_deref().unwrap_or_default();
+            split_sentences(text, limit)
+                .into_iter()
+                .map(|chunk| {
+                    let mut say = Say::new(chunk);
+                    say.factory.attributes = self.factory.attributes.clone();
+                    say.factory.text_format = self.factory.text_format;
+                    say
+                })
+                .collect()
+        }
+
+        /// Append a text segment to the spoken text
+        pub fn push_text(mut self, text: impl AsRef<str>) -> Self {
+            if self.factory.text_format == TextFormat::Markup {
+                push_escaped(self.markup_mut(), text.as_ref(), false);
+            } else {
+                self.factory.text.get_or_insert_default().to_mut().push_str(text.as_ref());
+            }
+            self
+        }
+
+        /// Set voice attribute
+        pub fn voice(mut self, voice: impl Into<String>) -> Self {
+            self.factory.set_attribute("voice", voice);
+            self
+        }
+
+        /// Set language attribute
+        pub fn language(mut self, language: impl Into<String>) -> Self {
+            self.factory.set_attribute("language", language);
+            self
+        }
+        
+        /// Set loop attribute
+        pub fn loop_times(mut self, loops: impl Into<Loop>) -> Self {
+            self.factory.set_attribute("loop", loops.into().to_string());
+            self
+        }
+        
+        /// Set pitch attribute
+        pub fn pitch(mut self, pitch: impl Into<String>) -> Self {
+            self.factory.set_attribute("pitch", pitch.into());
+            self
+        }
+        
+        /// Set rate attribute
+        pub fn rate(mut self, rate: impl Into<String>) -> Self {
+            self.factory.set_attribute("rate", rate.into());
+            self
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl From<&str> for Say {
+        fn from(text: &str) -> Self {
+            Say::new(text)
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl From<String> for Say {
+        fn from(text: String) -> Self {
+            Say::new(text)
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Say {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+
+    /// Input modes a Gather listens for
+    #[cfg(feature = "voice")]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+    pub enum GatherInput {
+        /// Keypad digits only (`dtmf`)
+        Dtmf,
+        /// Speech only (`speech`)
+        Speech,
+        /// Keypad digits or speech (`dtmf speech`)
+        DtmfSpeech,
+    }
+
+    #[cfg(feature = "voice")]
+    impl GatherInput {
+        /// Get the attribute value Twilio expects
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                GatherInput::Dtmf => "dtmf",
+                GatherInput::Speech => "speech",
+                GatherInput::DtmfSpeech => "dtmf speech",
+            }
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl fmt::Display for GatherInput {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.as_str())
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl FromStr for GatherInput {
+        type Err = ValidationError;
+
+        /// Parse a space-separated list of modes in either order
+        fn from_str(input: &str) -> Result<Self, Self::Err> {
+            let mut modes: Vec<&str> = input.split_whitespace().collect();
+            modes.sort_unstable();
+            modes.dedup();
+            match modes.as_slice() {
+                ["dtmf"] => Ok(GatherInput::Dtmf),
+                ["speech"] => Ok(GatherInput::Speech),
+                ["dtmf", "speech"] => Ok(GatherInput::DtmfSpeech),
+                _ => Err(ValidationError {
+                    element: "Gather".to_string(),
+                    attribute: Some("input".to_string()),
+                    child: None,
+                    reason: format!("`{}` is not a space-separated list of dtmf and speech", input),
+                }),
+            }
+        }
+    }
+
+    /// Gather TwiML Element for collecting user input
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let gather = Gather::new()
+    ///     .num_digits(1)
+    ///     .action("/menu")
+    ///     .timeout(5)
+    ///     .say("Press 1 for sales");
+    /// assert_eq!(
+    ///     gather.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Gather numDigits="1" action="/menu" timeout="5"><Say>Press 1 for sales</Say></Gather>"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
+    #[derive(Debug)]
+    pub struct Gather {
+        factory: ElementFactory,
+    }
+
+    #[cfg(feature = "voice")]
+    impl Gather {
+        /// Create a new Gather element
+        pub fn new() -> Self {
+            Self {
+                factory: ElementFactory::new("Gather", None::<String>),
+            }
+        }
+
+        /// Set action attribute
+        pub fn action(mut self, action: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("action", action.into().0);
+            self
+        }
+
+        /// Set method attribute
+        pub fn method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("method", method);
+            self
+        }
+
+        /// Set number of digits to collect
+        pub fn num_digits(mut self, num: u32) -> Self {
+            self.factory.set_attribute("numDigits", num.to_string());
+            self
+        }
+
+        /// Set number of digits to collect from a string
+        #[deprecated(note = "use `num_digits` with a `u32`")]
+        pub fn num_digits_str(mut self, num: impl Into<String>) -> Self {
+            self.factory.set_attribute("numDigits", num.into());
+            self
+        }
+        
+        /// Set timeout attribute in seconds
+        pub fn timeout(mut self, timeout: impl Into<Seconds>) -> Self {
+            self.factory.set_attribute("timeout", timeout.into().to_string());
+            self
+        }
+        
+        /// Set input attribute
+        pub fn input(mut self, input: GatherInput) -> Self {
+            self.factory.set_attribute("input", input.to_string());
+            self
+        }
+        
+        /// Set language attribute
+        pub fn language(mut self, language: impl Into<String>) -> Self {
+            self.factory.set_attribute("language", language);
+            self
+        }
+        
+        /// Set finishOnKey attribute
+        pub fn finish_on_key(mut self, key: impl Into<String>) -> Self {
+            self.factory.set_attribute("finishOnKey", key.into());
+            self
+        }
+        
+        /// Set hints attribute for speech recognition
+        pub fn hints(mut self, hints: impl Into<String>) -> Self {
+            self.factory.set_attribute("hints", hints.into());
+            self
+        }
+
+        /// Set hints attribute from a list of phrases, trimming each and skipping empty ones
+        ///
+        /// Twilio accepts at most [`MAX_HINT_PHRASES`](crate::MAX_HINT_PHRASES) phrases of
+        /// [`MAX_HINT_CHARACTERS`](crate::MAX_HINT_CHARACTERS) characters; validation reports
+        /// lists exceeding those limits.
+        pub fn hints_list(self, phrases: impl IntoIterator<Item = impl Into<String>>) -> Self {
+            let phrases: Vec<String> = phrases
+                .into_iter()
+                .map(|phrase| phrase.into().trim().to_string())
+                .filter(|phrase| !phrase.is_empty())
+                .collect();
+            self.hints(phrases.join(","))
+        }
+
+        /// Append the verbs of a fragment, which should only be Say, Play and Pause; validation rejects others
+        pub fn fragment(mut self, fragment: Fragment) -> Self {
+            self.factory.children.extend(fragment.children);
+            self
+        }
+
+        /// Add a prebuilt shared sub-tree, such as a menu prompt, without copying it
+        pub fn shared(mut self, shared: &Shared) -> Self {
+            self.factory.children.push(Box::new(shared.clone()));
+            self
+        }
+
+        /// Add a Say child element from text or a configured Say
+        pub fn say(mut self, say: impl Into<Say>) -> Self {
+            self.factory.children.push(Box::new(say.into()));
+            self
+        }
+
+        /// Add Say child elements, splitting text longer than [`MAX_SAY_CHARACTERS`]
+        pub fn say_split(mut self, say: impl Into<Say>) -> Self {
+            for say in say.into().split() {
+                self.factory.children.push(Box::new(say));
+            }
+            self
+        }
+        
+        /// Add a Play child element from text or a configured Play
+        pub fn play(mut self, play: impl Into<Play>) -> Self {
+            self.factory.children.push(Box::new(play.into()));
+            self
+        }
+        
+        /// Add a Pause child element
+        pub fn pause(mut self, pause: Pause) -> Self {
+            self.factory.children.push(Box::new(pause));
+            self
+        }
+
+        /// Set actionOnEmptyResult attribute
+        pub fn action_on_empty_result(mut self, enable: bool) -> Self {
+            self.factory.set_attribute("actionOnEmptyResult", enable.to_string());
+            self
+        }
+
+        /// Set enhanced attribute for enhanced speech recognition
+        pub fn enhanced(mut self, enable: bool) -> Self {
+            self.factory.set_attribute("enhanced", enable.to_string());
+            self
+        }
+
+        /// Set speechModel attribute
+        pub fn speech_model(mut self, model: impl Into<String>) -> Self {
+            self.factory.set_attribute("speechModel", model.into());
+            self
+        }
+
+        /// Set speechTimeout attribute (`auto` or a number of seconds)
+        pub fn speech_timeout(mut self, timeout: impl Into<SpeechTimeout>) -> Self {
+            self.factory.set_attribute("speechTimeout", timeout.into().0);
+            self
+        }
+
+        /// Set partialResultsCallback attribute
+        pub fn partial_results_callback(mut self, url: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("partialResultsCallback", url.into().0);
+            self
+        }
+
+        /// Set partialResultsCallbackMethod attribute
+        pub fn partial_results_callback_method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("partialResultsCallbackMethod", method.into());
+            self
+        }
+
+        /// Set profanityFilter attribute
+        pub fn profanity_filter(mut self, enable: bool) -> Self {
+            self.factory.set_attribute("profanityFilter", enable.to_string());
+            self
+        }
+
+        /// Set speechResult attribute
+        pub fn speech_result(mut self, result_format: impl Into<String>) -> Self {
+            self.factory.set_attribute("speechResult", result_format.into());
+            self
+        }
+
+        /// Set interdigitTimeout attribute (seconds)
+        pub fn interdigit_timeout(mut self, timeout: impl Into<Seconds>) -> Self {
+            self.factory.set_attribute("interdigitTimeout", timeout.into().to_string());
+            self
+        }
+
+        /// Set for attribute to specify the input mode: digits or speech (used with enhanced)
+        pub fn for_attr(mut self, for_value: impl Into<String>) -> Self {
+            self.factory.set_attribute("for", for_value.into());
+            self
+        }
+
+        /// Set statusCallback attribute
+        pub fn status_callback(mut self, url: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("statusCallback", url.into().0);
+            self
+        }
+
+        /// Set statusCallbackMethod attribute
+        pub fn status_callback_method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("statusCallbackMethod", method.into());
+            self
+        }
+
+        /// Set speechContexts attribute
+        pub fn speech_contexts(mut self, contexts: impl Into<String>) -> Self {
+            self.factory.set_attribute("speechContexts", contexts.into());
+            self
+        }
+
+        /// Set speechDetectorSensitivity attribute
+        pub fn speech_detector_sensitivity(mut self, sensitivity: impl Into<String>) -> Self {
+            self.factory.set_attribute("speechDetectorSensitivity", sensitivity.into());
+            self
+        }
+
+        /// Add a Prompt child element
+        #[cfg(feature = "pay")]
+        #[deprecated(note = "Twilio only accepts Prompt inside Pay; use `say`, `play` or `pause`")]
+        pub fn prompt(mut self, prompt: Prompt) -> Self {
+            self.factory.children.push(Box::new(prompt));
+            self
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl Default for Gather {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Gather {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+
+    /// Prompt TwiML Element for real-time enhanced speech recognition
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let prompt = Prompt::new_empty()
+    ///     .for_attr(PromptFor::PaymentCardNumber)
+    ///     .attempt(1)
+    ///     .say("Please enter your card number");
+    /// assert_eq!(
+    ///     prompt.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Prompt for="payment-card-number" attempt="1"><Say>Please enter your card number</Say></Prompt>"#
+    /// );
+    /// ```
+    #[cfg(feature = "pay")]
+    #[derive(Debug)]
+    pub struct Prompt {
+        factory: ElementFactory,
+    }
+
+    /// Piece of payment information a Pay [`Prompt`] asks for
+    #[cfg(feature = "pay")]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum PromptFor {
+        /// Card number (`payment-card-number`)
+        PaymentCardNumber,
+        /// Card expiration date (`expiration-date`)
+        ExpirationDate,
+        /// Card security code (`security-code`)
+        SecurityCode,
+        /// Billing postal code (`postal-code`)
+        PostalCode,
+        /// Played while the payment is processed (`payment-processing`)
+        PaymentProcessing,
+        /// Bank account number (`bank-account-number`)
+        BankAccountNumber,
+        /// Bank routing number (`bank-routing-number`)
+        BankRoutingNumber,
+    }
+
+    #[cfg(feature = "pay")]
+    impl PromptFor {
+        /// Get the attribute value Twilio expects
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                PromptFor::PaymentCardNumber => "payment-card-number",
+                PromptFor::ExpirationDate => "expiration-date",
+                PromptFor::SecurityCode => "security-code",
+                PromptFor::PostalCode => "postal-code",
+                PromptFor::PaymentProcessing => "payment-processing",
+                PromptFor::BankAccountNumber => "bank-account-number",
+                PromptFor::BankRoutingNumber => "bank-routing-number",
+            }
+        }
+    }
+
+    #[cfg(feature = "pay")]
+    impl fmt::Display for PromptFor {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.as_str())
+        }
+    }
+
+    #[cfg(feature = "pay")]
+    impl FromStr for PromptFor {
+        type Err = ValidationError;
+
+        fn from_str(value: &str) -> Result<Self, Self::Err> {
+            [
+                PromptFor::PaymentCardNumber,
+                PromptFor::ExpirationDate,
+                PromptFor::SecurityCode,
+                PromptFor::PostalCode,
+                PromptFor::PaymentProcessing,
+                PromptFor::BankAccountNumber,
+                PromptFor::BankRoutingNumber,
+            ]
+            .into_iter()
+            .find(|field| field.as_str() == value.trim())
+            .ok_or_else(|| ValidationError {
+                element: "Prompt".to_string(),
+                attribute: Some("for".to_string()),
+                child: None,
+                reason: format!("`{}` is not a piece of payment information Pay asks for", value),
+            })
+        }
+    }
+
+    #[cfg(feature = "pay")]
+    impl Prompt {
+        /// Create a new Prompt element
+        pub fn new(text: impl Into<String>) -> Self {
+            Self {
+                factory: ElementFactory::new("Prompt", Some(text)),
+            }
+        }
+
+        /// Create a Prompt without text, whose content comes from Say, Play and Pause children
+        /// as in Twilio's own documents
+        pub fn new_empty() -> Self {
+            Self {
+                factory: ElementFactory::new("Prompt", None::<String>),
+            }
+        }
+
+        /// Add a Say child element from text or a configured Say
+        pub fn say(mut self, say: impl Into<Say>) -> Self {
+            self.factory.children.push(Box::new(say.into()));
+            self
+        }
+
+        /// Add a Play child element from a URL or a configured Play
+        pub fn play(mut self, play: impl Into<Play>) -> Self {
+            self.factory.children.push(Box::new(play.into()));
+            self
+        }
+
+        /// Add a Pause child element
+        pub fn pause(mut self, pause: Pause) -> Self {
+            self.factory.children.push(Box::new(pause));
+            self
+        }
+
+        /// Set attempt attribute, from 1 to [`MAX_PAY_ATTEMPTS`](crate::MAX_PAY_ATTEMPTS)
+        pub fn attempt(mut self, attempt: u32) -> Self {
+            self.factory.set_attribute("attempt", attempt.to_string());
+            self
+        }
+
+        /// Set attempt attribute to play the prompt on several attempts
+        pub fn attempts(mut self, attempts: impl IntoIterator<Item = u32>) -> Self {
+            let attempts: Vec<String> = attempts.into_iter().map(|attempt| attempt.to_string()).collect();
+            self.factory.set_attribute("attempt", attempts.join(" "));
+            self
+        }
+
+        /// Set for attribute, the piece of payment information the prompt asks for
+        pub fn for_attr(mut self, field: PromptFor) -> Self {
+            self.factory.set_attribute("for", field.to_string());
+            self
+        }
+
+        /// Set for attribute from a string
+        #[deprecated(note = "use `for_attr` with a `PromptFor`")]
+        pub fn for_attr_str(mut self, field: impl Into<String>) -> Self {
+            self.factory.set_attribute("for", field.into());
+            self
+        }
+
+        /// Set voice attribute
+        pub fn voice(mut self, voice: impl Into<String>) -> Self {
+            self.factory.set_attribute("voice", voice.into());
+            self
+        }
+
+        /// Set language attribute
+        pub fn language(mut self, language: impl Into<String>) -> Self {
+            self.factory.set_attribute("language", language.into());
+            self
+        }
+    }
+
+    #[cfg(feature = "pay")]
+    impl TwiMLElement for Prompt {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+
+    /// Redirect TwiML Element
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let redirect = Redirect::new("/next").method("POST");
+    /// assert_eq!(
+    ///     redirect.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Redirect method="POST">/next</Redirect>"#
+    /// );
+    /// ```
+    #[derive(Debug)]
+    pub struct Redirect {
+        factory: ElementFactory,
+    }
+
+    impl Redirect {
+        /// Create a new Redirect element
+        pub fn new(url: impl Into<UrlValue>) -> Self {
+            Self {
+                factory: ElementFactory::new("Redirect", Some(url.into().0)),
+            }
+        }
+        
+        /// Set method attribute
+        pub fn method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("method", method);
+            self
+        }
+    }
+
+    impl From<&str> for Redirect {
+        fn from(url: &str) -> Self {
+            Redirect::new(url)
+        }
+    }
+
+    impl From<String> for Redirect {
+        fn from(url: String) -> Self {
+            Redirect::new(url)
+        }
+    }
+
+    #[cfg(feature = "url")]
+    impl From<url::Url> for Redirect {
+        fn from(url: url::Url) -> Self {
+            Redirect::new(url)
+        }
+    }
+
+    impl TwiMLElement for Redirect {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+    
+    /// Play TwiML Element to play audio files
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let play = Play::new("https://example.com/hold.mp3").loop_times(3);
+    /// assert_eq!(
+    ///     play.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Play loop="3">https://example.com/hold.mp3</Play>"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
+    #[derive(Debug)]
+    pub struct Play {
+        factory: ElementFactory,
+    }
+    
+    #[cfg(feature = "voice")]
+    impl Play {
+        /// Create a new Play element
+        pub fn new(url: impl Into<UrlValue>) -> Self {
+            Self {
+                factory: ElementFactory::new("Play", Some(url.into().0)),
+            }
+        }
+
+        /// Create a Play that borrows a static URL instead of copying it
+        pub fn from_static(url: &'static str) -> Self {
+            Self {
+                factory: ElementFactory::with_static_text("Play", url),
+            }
+        }
+        
+        /// Set loop attribute
+        pub fn loop_times(mut self, loops: impl Into<Loop>) -> Self {
+            self.factory.set_attribute("loop", loops.into().to_string());
+            self
+        }
+        
+        /// Set digits attribute, e.g. from a [`Digits`] builder
+        pub fn digits(mut self, digits: impl Into<String>) -> Self {
+            self.factory.set_attribute("digits", digits.into());
+            self
+        }
+    }
+    
+    #[cfg(feature = "voice")]
+    impl From<&str> for Play {
+        fn from(url: &str) -> Self {
+            Play::new(url)
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl From<String> for Play {
+        fn from(url: String) -> Self {
+            Play::new(url)
+        }
+    }
+
+    #[cfg(all(feature = "voice", feature = "url"))]
+    impl From<url::Url> for Play {
+        fn from(url: url::Url) -> Self {
+            Play::new(url)
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Play {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+    
+    /// Pause TwiML Element for silent pause
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let pause = Pause::new().length(2);
+    /// assert_eq!(
+    ///     pause.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Pause length="2" />"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
+    #[derive(Debug)]
+    pub struct Pause {
+        factory: ElementFactory,
+    }
+    
+    #[cfg(feature = "voice")]
+    impl Pause {
+        /// Create a new Pause element
+        pub fn new() -> Self {
+            Self {
+                factory: ElementFactory::new("Pause", None::<String>),
+            }
+        }
+        
+        /// Set length attribute (seconds)
+        pub fn length(mut self, seconds: impl Into<Seconds>) -> Self {
+            self.factory.set_attribute("length", seconds.into().to_string());
+            self
+        }
+    }
+    
+    #[cfg(feature = "voice")]
+    impl Default for Pause {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Pause {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+    
+    /// Hangup TwiML Element to end a call
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let hangup = Hangup::new();
+    /// assert_eq!(
+    ///     hangup.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Hangup />"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
+    #[derive(Debug)]
+    pub struct Hangup {
+        factory: ElementFactory,
+    }
+    
+    #[cfg(feature = "voice")]
+    impl Hangup {
+        /// Create a new Hangup element
+        pub fn new() -> Self {
+            Self {
+                factory: ElementFactory::new("Hangup", None::<String>),
+            }
+        }
+    }
+    
+    #[cfg(feature = "voice")]
+    impl Default for Hangup {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Hangup {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+    
+    /// Busy signal or rejection played by [`Reject`]
+    #[cfg(feature = "voice")]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub enum RejectReason {
+        /// Play a "not in service" message (`rejected`)
+        #[default]
+        Rejected,
+        /// Play a busy signal (`busy`)
+        Busy,
+    }
+
+    #[cfg(feature = "voice")]
+    impl RejectReason {
+        /// Get the attribute value Twilio expects
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                RejectReason::Rejected => "rejected",
+                RejectReason::Busy => "busy",
+            }
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl fmt::Display for RejectReason {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.as_str())
+        }
+    }
+
+    /// Reject TwiML Element to decline an incoming call without answering, so it isn't billed
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let reject = Reject::new().reason(RejectReason::Busy);
+    /// assert_eq!(
+    ///     reject.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Reject reason="busy" />"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
+    #[derive(Debug)]
+    pub struct Reject {
+        factory: ElementFactory,
+    }
+
+    #[cfg(feature = "voice")]
+    impl Reject {
+        /// Create a new Reject element
+        pub fn new() -> Self {
+            Self {
+                factory: ElementFactory::new("Reject", None::<String>),
+            }
+        }
+
+        /// Set reason attribute
+        pub fn reason(mut self, reason: RejectReason) -> Self {
+            self.factory.set_attribute("reason", reason.to_string());
+            self
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl Default for Reject {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Reject {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+
+    /// Record TwiML Element to record caller's voice
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let record = Record::new()
+    ///     .action("/recorded")
+    ///     .max_length(60)
+    ///     .finish_on_key("#")
+    ///     .play_beep(true);
+    /// assert_eq!(
+    ///     record.to_xml_string(),
+    ///     r##"<?xml version="1.1" encoding="UTF-8"?><Record action="/recorded" maxLength="60" finishOnKey="#" playBeep="true" />"##
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
+    #[derive(Debug)]
+    pub struct Record {
+        factory: ElementFactory,
+    }
+    
+    #[cfg(feature = "voice")]
+    impl Record {
+        /// Create a new Record element
+        pub fn new() -> Self {
+            Self {
+                factory: ElementFactory::new("Record", None::<String>),
+            }
+        }
+        
+        /// Set action attribute
+        pub fn action(mut self, action: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("action", action.into().0);
+            self
+        }
+        
+        /// Set method attribute
+        pub fn method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("method", method);
+            self
+        }
+        
+        /// Set timeout attribute in seconds
+        pub fn timeout(mut self, timeout: impl Into<Seconds>) -> Self {
+            self.factory.set_attribute("timeout", timeout.into().to_string());
+            self
+        }
+        
+        /// Set finishOnKey attribute
+        pub fn finish_on_key(mut self, key: impl Into<String>) -> Self {
+            self.factory.set_attribute("finishOnKey", key.into());
+            self
+        }
+        
+        /// Set maxLength attribute (seconds)
+        pub fn max_length(mut self, seconds: impl Into<Seconds>) -> Self {
+            self.factory.set_attribute("maxLength", seconds.into().to_string());
+            self
+        }
+        
+        /// Set playBeep attribute
+        pub fn play_beep(mut self, play_beep: bool) -> Self {
+            self.factory.set_attribute("playBeep", play_beep.to_string());
+            self
+        }
+        
+        /// Set recordingStatusCallback attribute
+        pub fn recording_status_callback(mut self, url: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("recordingStatusCallback", url.into().0);
+            self
+        }
+        
+        /// Set recordingStatusCallbackMethod attribute
+        pub fn recording_status_callback_method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("recordingStatusCallbackMethod", method.into());
+            self
+        }
+
+        /// Set storageUrl attribute, where Twilio uploads the recording instead of keeping it
+        ///
+        /// Used with external storage, such as an S3 bucket the account owns; the account's
+        /// external storage settings must allow it.
+        pub fn storage_url(mut self, url: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("storageUrl", url.into().0);
+            self
+        }
+
+        /// Set storageUrlMethod attribute, the HTTP method used to upload to storageUrl
+        pub fn storage_url_method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("storageUrlMethod", method.into());
+            self
+        }
+        
+        /// Set transcribe attribute
+        pub fn transcribe(mut self, transcribe: bool) -> Self {
+            self.factory.set_attribute("transcribe", transcribe.to_string());
+            self
+        }
+        
+        /// Set transcribeCallback attribute
+        pub fn transcribe_callback(mut self, url: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("transcribeCallback", url.into().0);
+            self
+        }
+    }
+    
+    #[cfg(feature = "voice")]
+    impl Default for Record {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Record {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+    
+    /// Dial TwiML Element to connect call to another phone
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let dial = Dial::new_empty()
+    ///     .caller_id("+15550001111")
+    ///     .timeout(20)
+    ///     .number(Number::new("+15551234567"));
+    /// assert_eq!(
+    ///     dial.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Dial callerId="+15550001111" timeout="20"><Number>+15551234567</Number></Dial>"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
+    #[derive(Debug)]
+    pub struct Dial {
+        factory: ElementFactory,
+    }
+    
+    #[cfg(feature = "voice")]
+    impl Dial {
+        /// Create a new Dial element with optional number to dial
+        #[deprecated(note = "use `Dial::new_with_number`, or `Dial::new_empty` to dial nouns")]
+        pub fn new(number: Option<impl Into<String>>) -> Self {
+            Self {
+                factory: ElementFactory::new("Dial", number.map(|n| n.into())),
+            }
+        }
+        
+        /// Create a Dial without a number, which dials the nouns added with
+        /// [`number`](Self::number), [`client`](Self::client), [`conference`](Self::conference)
+        /// and [`sip`](Self::sip)
+        pub fn new_empty() -> Self {
+            Self {
+                factory: ElementFactory::new("Dial", None::<String>),
+            }
+        }
+
+        /// Create a Dial that calls a single phone number given as its text
+        ///
+        /// A Dial with a number can't also have nouns such as [`Number`] or [`Conference`];
+        /// validation rejects the combination.
+        pub fn new_with_number(number: impl Into<String>) -> Self {
+            Self {
+                factory: ElementFactory::new("Dial", Some(number)),
+            }
+        }
+        
+        /// Set action attribute
+        pub fn action(mut self, action: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("action", action.into().0);
+            self
+        }
+        
+        /// Set method attribute
+        pub fn method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("method", method.into());
+            self
+        }
+        
+        /// Set timeout attribute in seconds
+        pub fn timeout(mut self, timeout: impl Into<Seconds>) -> Self {
+            self.factory.set_attribute("timeout", timeout.into().to_string());
+            self
+        }
+
+        /// Set timeLimit attribute, the longest the call may last in seconds
+        pub fn time_limit(mut self, seconds: impl Into<Seconds>) -> Self {
+            self.factory.set_attribute("timeLimit", seconds.into().to_string());
+            self
+        }
+        
+        /// Set callerId attribute
+        pub fn caller_id(mut self, caller_id: impl Into<String>) -> Self {
+            self.factory.set_attribute("callerId", caller_id);
+            self
+        }
+        
+        /// Set record attribute
+        pub fn record(mut self, record: impl Into<String>) -> Self {
+            self.factory.set_attribute("record", record);
+            self
+        }
+        
+        /// Set Number child element
+        pub fn number(mut self, number: Number) -> Self {
+            self.factory.children.push(Box::new(number));
+            self
+        }
+        
+        /// Set Client child element
+        pub fn client(mut self, client: Client) -> Self {
+            self.factory.children.push(Box::new(client));
+            self
+        }
+        
+        /// Set Conference child element
+        pub fn conference(mut self, conference: Conference) -> Self {
+            self.factory.children.push(Box::new(conference));
+            self
+        }
+        
+        /// Set Sip child element
+        pub fn sip(mut self, sip: Sip) -> Self {
+            self.factory.children.push(Box::new(sip));
+            self
+        }
+
+        /// Set Application child element
+        pub fn application(mut self, application: Application) -> Self {
+            self.factory.children.push(Box::new(application));
+            self
+        }
+    }
+    
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Dial {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+    
+    /// Set of status callback events, serialized as a space-separated list
+    #[cfg(feature = "voice")]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct EventSet(u16);
+
+    #[cfg(feature = "voice")]
+    impl EventSet {
+        /// Call leg is being dialed
+        pub const INITIATED: EventSet = EventSet(1 << 0);
+        /// Call leg is ringing
+        pub const RINGING: EventSet = EventSet(1 << 1);
+        /// Call leg was answered
+        pub const ANSWERED: EventSet = EventSet(1 << 2);
+        /// Call leg ended
+        pub const COMPLETED: EventSet = EventSet(1 << 3);
+        /// Conference started
+        pub const START: EventSet = EventSet(1 << 4);
+        /// Conference ended
+        pub const END: EventSet = EventSet(1 << 5);
+        /// Participant joined the conference
+        pub const JOIN: EventSet = EventSet(1 << 6);
+        /// Participant left the conference
+        pub const LEAVE: EventSet = EventSet(1 << 7);
+        /// Participant was muted or unmuted
+        pub const MUTE: EventSet = EventSet(1 << 8);
+        /// Participant was held or unheld
+        pub const HOLD: EventSet = EventSet(1 << 9);
+        /// Participant settings were modified
+        pub const MODIFY: EventSet = EventSet(1 << 10);
+        /// Participant started or stopped speaking
+        pub const SPEAKER: EventSet = EventSet(1 << 11);
+        /// Announcement to the conference or a participant finished
+        pub const ANNOUNCEMENT: EventSet = EventSet(1 << 12);
+        /// Recording started
+        pub const IN_PROGRESS: EventSet = EventSet(1 << 13);
+        /// Recording was never started or produced no audio
+        pub const ABSENT: EventSet = EventSet(1 << 14);
+
+        const NAMES: [(EventSet, &'static str); 15] = [
+            (EventSet::INITIATED, "initiated"),
+            (EventSet::RINGING, "ringing"),
+            (EventSet::ANSWERED, "answered"),
+            (EventSet::COMPLETED, "completed"),
+            (EventSet::START, "start"),
+            (EventSet::END, "end"),
+            (EventSet::JOIN, "join"),
+            (EventSet::LEAVE, "leave"),
+            (EventSet::MUTE, "mute"),
+            (EventSet::HOLD, "hold"),
+            (EventSet::MODIFY, "modify"),
+            (EventSet::SPEAKER, "speaker"),
+            (EventSet::ANNOUNCEMENT, "announcement"),
+            (EventSet::IN_PROGRESS, "in-progress"),
+            (EventSet::ABSENT, "absent"),
+        ];
+
+        /// Create an empty event set
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Add events to the set
+        pub fn with(self, events: EventSet) -> Self {
+            EventSet(self.0 | events.0)
+        }
+
+        /// Whether every event in `events` is in the set
+        pub fn contains(&self, events: EventSet) -> bool {
+            self.0 & events.0 == events.0
+        }
+
+        /// Whether the set has no events
+        pub fn is_empty(&self) -> bool {
+            self.0 == 0
+        }
+
+        /// Iterate over the Twilio names of the events in the set
+        pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+            Self::NAMES.iter().filter(|(event, _)| self.contains(*event)).map(|(_, name)| *name)
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl BitOr for EventSet {
+        type Output = EventSet;
+
+        fn bitor(self, events: EventSet) -> EventSet {
+            self.with(events)
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl fmt::Display for EventSet {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(&self.names().collect::<Vec<_>>().join(" "))
+        }
+    }
+
+    /// Number TwiML Element noun for Dial
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let number = Number::new("+15551234567").send_digits("ww1234");
+    /// assert_eq!(
+    ///     number.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Number sendDigits="ww1234">+15551234567</Number>"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
+    #[derive(Debug)]
+    pub struct Number {
+        factory: ElementFactory,
+    }
+    
+    #[cfg(feature = "voice")]
+    impl Number {
+        /// Create a new Number element
+        pub fn new(number: impl Into<String>) -> Self {
+            Self {
+                factory: ElementFactory::new("Number", Some(number)),
+            }
+        }
+        
+        /// Set sendDigits attribute, e.g. from a [`Digits`] builder
+        pub fn send_digits(mut self, digits: impl Into<String>) -> Self {
+            self.factory.set_attribute("sendDigits", digits.into());
+            self
+        }
+        
+        /// Set url attribute
+        pub fn url(mut self, url: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("url", url.into().0);
+            self
+        }
+
+        /// Set statusCallback attribute
+        pub fn status_callback(mut self, url: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("statusCallback", url.into().0);
+            self
+        }
+
+        /// Set statusCallbackMethod attribute
+        pub fn status_callback_method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("statusCallbackMethod", method.into());
+            self
+        }
+
+        /// Set statusCallbackEvent attribute
+        pub fn status_callback_event(mut self, events: EventSet) -> Self {
+            self.factory.set_attribute("statusCallbackEvent", events.to_string());
+            self
+        }
+    }
+    
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Number {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+    
+    /// Client TwiML Element noun for Dial
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let client = Client::new("alice").status_callback("/client-events");
+    /// assert_eq!(
+    ///     client.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Client statusCallback="/client-events">alice</Client>"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
+    #[derive(Debug)]
+    pub struct Client {
+        factory: ElementFactory,
+    }
+    
+    #[cfg(feature = "voice")]
+    impl Client {
+        /// Create a new Client element
+        pub fn new(client_id: impl Into<String>) -> Self {
+            Self {
+                factory: ElementFactory::new("Client", Some(client_id)),
+            }
+        }
+        
+        /// Set url attribute
+        pub fn url(mut self, url: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("url", url.into().0);
+            self
+        }
+        
+        /// Set method attribute
+        pub fn method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("method", method.into());
+            self
+        }
+        
+        /// Set statusCallback attribute
+        pub fn status_callback(mut self, url: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("statusCallback", url.into().0);
+            self
+        }
+        
+        /// Set statusCallbackMethod attribute
+        pub fn status_callback_method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("statusCallbackMethod", method.into());
+            self
+        }
+
+        /// Set statusCallbackEvent attribute
+        pub fn status_callback_event(mut self, events: EventSet) -> Self {
+            self.factory.set_attribute("statusCallbackEvent", events.to_string());
+            self
+        }
+
+        /// Add a Parameter child passed to the callee's Voice SDK application
+        ///
+        /// The client identity moves into an `<Identity>` child, since XML can't hold
+        /// both text and Parameter children.
+        pub fn parameter(mut self, parameter: Parameter) -> Self {
+            if let Some(identity) = self.factory.text.take() {
+                let mut child = ElementFactory::new("Identity", None::<String>);
+                child.text = Some(identity);
+                self.factory.children.insert(0, Box::new(child));
+            }
+            self.factory.children.push(Box::new(parameter));
+            self
+        }
+    }
+    
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Client {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+    
+    /// Application TwiML noun for Dial, connecting the call to another TwiML App
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let application = Application::new("AP123").parameter(Parameter::new("ticket", "42"));
+    /// assert_eq!(
+    ///     application.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Application><ApplicationSid>AP123</ApplicationSid><Parameter name="ticket" value="42" /></Application>"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
+    #[derive(Debug)]
+    pub struct Application {
+        factory: ElementFactory,
+    }
+
+    #[cfg(feature = "voice")]
+    impl Application {
+        /// Create a new Application element dialing the TwiML App with this SID
+        pub fn new(application_sid: impl Into<String>) -> Self {
+            let mut factory = ElementFactory::new("Application", None::<String>);
+            factory.children.push(Box::new(ElementFactory::new("ApplicationSid", Some(application_sid))));
+            Self { factory }
+        }
+
+        /// Set url attribute
+        pub fn url(mut self, url: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("url", url.into().0);
+            self
+        }
+
+        /// Set method attribute
+        pub fn method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("method", method.into());
+            self
+        }
+
+        /// Set statusCallback attribute
+        pub fn status_callback(mut self, url: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("statusCallback", url.into().0);
+            self
+        }
+
+        /// Set statusCallbackMethod attribute
+        pub fn status_callback_method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("statusCallbackMethod", method.into());
+            self
+        }
+
+        /// Set statusCallbackEvent attribute
+        pub fn status_callback_event(mut self, events: EventSet) -> Self {
+            self.factory.set_attribute("statusCallbackEvent", events.to_string());
+            self
+        }
+
+        /// Set customerId attribute, identifying the caller to the application
+        pub fn customer_id(mut self, customer_id: impl Into<String>) -> Self {
+            self.factory.set_attribute("customerId", customer_id.into());
+            self
+        }
+
+        /// Set copyParentTo attribute, passing the parent call's To number to the application
+        pub fn copy_parent_to(mut self, copy: bool) -> Self {
+            self.factory.set_attribute("copyParentTo", copy.to_string());
+            self
+        }
+
+        /// Add a Parameter child passed to the application's webhook
+        pub fn parameter(mut self, parameter: Parameter) -> Self {
+            self.factory.children.push(Box::new(parameter));
+            self
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Application {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+
+    /// Beep behavior when participants join or leave a Conference
+    #[cfg(feature = "voice")]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+    pub enum Beep {
+        /// Beep when participants join and leave (`true`)
+        Always,
+        /// Never beep (`false`)
+        Never,
+        /// Beep only when participants join (`onEnter`)
+        OnEnter,
+        /// Beep only when participants leave (`onExit`)
+        OnExit,
+    }
+
+    #[cfg(feature = "voice")]
+    impl Beep {
+        /// Get the attribute value Twilio expects
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                Beep::Always => "true",
+                Beep::Never => "false",
+                Beep::OnEnter => "onEnter",
+                Beep::OnExit => "onExit",
+            }
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl fmt::Display for Beep {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.as_str())
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl From<bool> for Beep {
+        fn from(beep: bool) -> Self {
+            if beep { Beep::Always } else { Beep::Never }
+        }
+    }
+
+    /// Conference TwiML Element noun for Dial
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let conference = Conference::new("Standup")
+    ///     .start_conference_on_enter(true)
+    ///     .beep(false)
+    ///     .max_participants(10);
+    /// assert_eq!(
+    ///     conference.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Conference startConferenceOnEnter="true" beep="false" maxParticipants="10">Standup</Conference>"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
+    #[derive(Debug)]
+    pub struct Conference {
+        factory: ElementFactory,
+    }
+    
+    #[cfg(feature = "voice")]
+    impl Conference {
+        /// Create a new Conference element
+        pub fn new(room_name: impl Into<String>) -> Self {
+            Self {
+                factory: ElementFactory::new("Conference", Some(room_name)),
+            }
+        }
+        
+        /// Set muted attribute
+        pub fn muted(mut self, muted: bool) -> Self {
+            self.factory.set_attribute("muted", muted.to_string());
+            self
+        }
+        
+        /// Set startConferenceOnEnter attribute
+        pub fn start_conference_on_enter(mut self, start: bool) -> Self {
+            self.factory.set_attribute("startConferenceOnEnter", start.to_string());
+            self
+        }
+        
+        /// Set endConferenceOnExit attribute
+        pub fn end_conference_on_exit(mut self, end: bool) -> Self {
+            self.factory.set_attribute("endConferenceOnExit", end.to_string());
+            self
+        }
+        
+        /// Set maxParticipants attribute, from 2 to [`MAX_CONFERENCE_PARTICIPANTS`](crate::MAX_CONFERENCE_PARTICIPANTS)
+        pub fn max_participants(mut self, max: u32) -> Self {
+            self.factory.set_attribute("maxParticipants", max.to_string());
+            self
+        }
+        
+        /// Set beep attribute from a [`Beep`] value or a bool
+        pub fn beep(mut self, beep: impl Into<Beep>) -> Self {
+            self.factory.set_attribute("beep", beep.into().to_string());
+            self
+        }
+        
+        /// Set record attribute
+        pub fn record(mut self, record: impl Into<String>) -> Self {
+            self.factory.set_attribute("record", record.into());
+            self
+        }
+
+        /// Set statusCallback attribute
+        pub fn status_callback(mut self, url: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("statusCallback", url.into().0);
+            self
+        }
+
+        /// Set statusCallbackMethod attribute
+        pub fn status_callback_method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("statusCallbackMethod", method.into());
+            self
+        }
+
+        /// Set statusCallbackEvent attribute
+        pub fn status_callback_event(mut self, events: EventSet) -> Self {
+            self.factory.set_attribute("statusCallbackEvent", events.to_string());
+            self
+        }
+
+        /// Set waitUrl attribute for hold music played before the conference starts
+        pub fn wait_url(mut self, url: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("waitUrl", url.into().0);
+            self
+        }
+
+        /// Set waitMethod attribute
+        pub fn wait_method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("waitMethod", method.into());
+            self
+        }
+
+        /// Set region attribute
+        pub fn region(mut self, region: impl Into<String>) -> Self {
+            self.factory.set_attribute("region", region.into());
+            self
+        }
+
+        /// Set coach attribute to the CallSid of the participant to coach
+        pub fn coach(mut self, call_sid: impl Into<String>) -> Self {
+            self.factory.set_attribute("coach", call_sid.into());
+            self
+        }
+
+        /// Set trim attribute, `trim-silence` or `do-not-trim`
+        pub fn trim(mut self, trim: impl Into<String>) -> Self {
+            self.factory.set_attribute("trim", trim.into());
+            self
+        }
+
+        /// Set recordingStatusCallback attribute
+        pub fn recording_status_callback(mut self, url: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("recordingStatusCallback", url.into().0);
+            self
+        }
+
+        /// Set recordingStatusCallbackMethod attribute
+        pub fn recording_status_callback_method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("recordingStatusCallbackMethod", method.into());
+            self
+        }
+
+        /// Set recordingStatusCallbackEvent attribute from IN_PROGRESS, COMPLETED and ABSENT
+        pub fn recording_status_callback_event(mut self, events: EventSet) -> Self {
+            self.factory.set_attribute("recordingStatusCallbackEvent", events.to_string());
+            self
+        }
+
+        /// Set eventCallbackUrl attribute
+        pub fn event_callback_url(mut self, url: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("eventCallbackUrl", url.into().0);
+            self
+        }
+
+        /// Set jitterBufferSize attribute: `large`, `medium`, `small` or `off`
+        pub fn jitter_buffer_size(mut self, size: impl Into<String>) -> Self {
+            self.factory.set_attribute("jitterBufferSize", size.into());
+            self
+        }
+
+        /// Set participantLabel attribute
+        pub fn participant_label(mut self, label: impl Into<String>) -> Self {
+            self.factory.set_attribute("participantLabel", label.into());
+            self
+        }
+    }
+    
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Conference {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+    
+    /// Sip TwiML Element noun for Dial
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let sip = Sip::new("sip:alice@example.com").header("X-Account", "42").username("alice");
+    /// assert_eq!(
+    ///     sip.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Sip username="alice">sip:alice@example.com?X-Account=42</Sip>"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
+    #[derive(Debug)]
+    pub struct Sip {
+        factory: ElementFactory,
+    }
+    
+    #[cfg(feature = "voice")]
+    impl Sip {
+        /// Create a new Sip element
+        pub fn new(sip_url: impl Into<String>) -> Self {
+            Self {
+                factory: ElementFactory::new("Sip", Some(sip_url)),
+            }
+        }
+        
+        /// Add a custom header, sent to the SIP endpoint as a parameter of the SIP URI
+        ///
+        /// Twilio only passes on headers whose names start with `X-`. The value is
+        /// percent-encoded.
+        pub fn header(mut self, name: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+            let uri = self.factory.text.get_or_insert_default().to_mut();
+            uri.push(if uri.contains('?') { '&' } else { '?' });
+            uri.push_str(&percent_encode(name.as_ref()));
+            uri.push('=');
+            uri.push_str(&percent_encode(value.as_ref()));
+            self
+        }
+
+        /// Set username attribute
+        pub fn username(mut self, username: impl Into<String>) -> Self {
+            self.factory.set_attribute("username", username.into());
+            self
+        }
+        
+        /// Set password attribute
+        pub fn password(mut self, password: impl Into<String>) -> Self {
+            self.factory.set_attribute("password", password.into());
+            self
+        }
+
+        /// Set statusCallback attribute
+        pub fn status_callback(mut self, url: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("statusCallback", url.into().0);
+            self
+        }
+
+        /// Set statusCallbackMethod attribute
+        pub fn status_callback_method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("statusCallbackMethod", method.into());
+            self
+        }
+
+        /// Set statusCallbackEvent attribute
+        pub fn status_callback_event(mut self, events: EventSet) -> Self {
+            self.factory.set_attribute("statusCallbackEvent", events.to_string());
+            self
+        }
+    }
+    
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Sip {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+    
+    /// SMS TwiML Element to send text message during a call
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let sms = Sms::new("Your code is 1234").to("+15551234567");
+    /// assert_eq!(
+    ///     sms.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Sms to="+15551234567">Your code is 1234</Sms>"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
+    #[derive(Debug)]
+    pub struct Sms {
+        factory: ElementFactory,
+    }
+    
+    #[cfg(feature = "voice")]
+    impl Sms {
+        /// Create a new Sms element
+        pub fn new(message: impl Into<String>) -> Self {
+            Self {
+                factory: ElementFactory::new("Sms", Some(message)),
+            }
+        }
+        
+        /// Set to attribute
+        pub fn to(mut self, to: impl Into<String>) -> Self {
+            self.factory.set_attribute("to", to.into());
+            self
+        }
+        
+        /// Set from attribute
+        pub fn from(mut self, from: impl Into<String>) -> Self {
+            self.factory.set_attribute("from", from.into());
+            self
+        }
+        
+        /// Set action attribute
+        pub fn action(mut self, action: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("action", action.into().0);
+            self
+        }
+        
+        /// Set method attribute
+        pub fn method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("method", method.into());
+            self
+        }
+    }
+    
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Sms {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+    
+    /// Body TwiML Element used within Message
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let body = Body::new("See you at 5");
+    /// assert_eq!(
+    ///     body.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Body>See you at 5</Body>"#
+    /// );
+    /// ```
+    #[cfg(feature = "messaging")]
+    #[derive(Debug)]
+    pub struct Body {
+        factory: ElementFactory,
+    }
+    
+    #[cfg(feature = "messaging")]
+    impl Body {
+        /// Create a new Body element
+        pub fn new(text: impl Into<String>) -> Self {
+            Self {
+                factory: ElementFactory::new("Body", Some(text)),
+            }
+        }
+
+        /// Create a Body that borrows static text instead of copying it
+        pub fn from_static(text: &'static str) -> Self {
+            Self {
+                factory: ElementFactory::with_static_text("Body", text),
+            }
+        }
+    }
+    
+    #[cfg(feature = "messaging")]
+    impl From<&str> for Body {
+        fn from(text: &str) -> Self {
+            Body::new(text)
+        }
+    }
+
+    #[cfg(feature = "messaging")]
+    impl From<String> for Body {
+        fn from(text: String) -> Self {
+            Body::new(text)
+        }
+    }
+
+    #[cfg(feature = "messaging")]
+    impl TwiMLElement for Body {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+    
+    /// Media TwiML Element used within Message to attach an MMS media file
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let media = Media::new("https://example.com/map.png");
+    /// assert_eq!(
+    ///     media.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Media>https://example.com/map.png</Media>"#
+    /// );
+    /// ```
+    #[cfg(feature = "messaging")]
+    #[derive(Debug)]
+    pub struct Media {
+        factory: ElementFactory,
+    }
+
+    #[cfg(feature = "messaging")]
+    impl Media {
+        /// Create a new Media element
+        pub fn new(url: impl Into<UrlValue>) -> Self {
+            Self {
+                factory: ElementFactory::new("Media", Some(url.into().0)),
+            }
+        }
+    }
+
+    #[cfg(feature = "messaging")]
+    impl TwiMLElement for Media {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+
+    /// Message TwiML Element for sending messages
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let message = Message::new_empty()
+    ///     .to("+15551234567")
+    ///     .body(Body::new("Your order shipped"))
+    ///     .media_url("https://example.com/label.png");
+    /// assert_eq!(
+    ///     message.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Message to="+15551234567"><Body>Your order shipped</Body><Media>https://example.com/label.png</Media></Message>"#
+    /// );
+    /// ```
+    #[cfg(feature = "messaging")]
+    #[derive(Debug)]
+    pub struct Message {
+        factory: ElementFactory,
+    }
+    
+    #[cfg(feature = "messaging")]
+    impl Message {
+        /// Create a new Message element with optional text
+        #[deprecated(note = "use `Message::new_with_text`, or `Message::new_empty` with a Body")]
+        pub fn new(text: Option<impl Into<String>>) -> Self {
+            Self {
+                factory: ElementFactory::new("Message", text.map(|t| t.into())),
+            }
+        }
+        
+        /// Create a Message without text, whose content comes from [`Body`] and [`Media`] children
+        pub fn new_empty() -> Self {
+            Self {
+                factory: ElementFactory::new("Message", None::<String>),
+            }
+        }
+        
+        /// Create a new Message element with direct text content
+        pub fn new_with_text(text: impl Into<String>) -> Self {
+            Self {
+                factory: ElementFactory::new("Message", Some(text.into())),
+            }
+        }
+        
+        /// Set to attribute
+        pub fn to(mut self, to: impl Into<String>) -> Self {
+            self.factory.set_attribute("to", to.into());
+            self
+        }
+        
+        /// Set from attribute
+        pub fn from(mut self, from: impl Into<String>) -> Self {
+            self.factory.set_attribute("from", from.into());
+            self
+        }
+        
+        /// Set action attribute
+        pub fn action(mut self, action: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("action", action.into().0);
+            self
+        }
+        
+        /// Set method attribute
+        pub fn method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("method", method.into());
+            self
+        }
+        
+        /// Set statusCallback attribute
+        pub fn status_callback(mut self, url: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("statusCallback", url.into().0);
+            self
+        }
+        
+        /// Add a Body child element from text or a configured Body
+        pub fn body(mut self, body: impl Into<Body>) -> Self {
+            self.factory.children.push(Box::new(body.into()));
+            self
+        }
+
+        /// Add a Media child element
+        pub fn media(mut self, media: Media) -> Self {
+            self.factory.children.push(Box::new(media));
+            self
+        }
+
+        /// Add a Media child element from a URL
+        pub fn media_url(self, url: impl Into<UrlValue>) -> Self {
+            self.media(Media::new(url))
+        }
+
+        /// Add a Media child element for each URL
+        pub fn media_urls(self, urls: impl IntoIterator<Item = impl Into<UrlValue>>) -> Self {
+            urls.into_iter().fold(self, |message, url| message.media_url(url))
+        }
+
+        /// Split a long body into consecutive Messages of at most `max_segments` SMS
+        /// segments each, such as [`MAX_SMS_SEGMENTS`](crate::MAX_SMS_SEGMENTS)
+        ///
+        /// Text is broken at word boundaries where possible. Every part keeps the original
+        /// attributes, and media stays on the first part. A Message that fits, or with
+        /// more than one Body, is returned unchanged.
+        pub fn split_segments(mut self, max_segments: usize) -> Vec<Message> {
+            let bodies: Vec<usize> = (0..self.factory.children.len())
+                .filter(|&index| self.factory.children[index].factory().name() == "Body")
+                .collect();
+            let text = match (bodies.as_slice(), &self.factory.text) {
+                ([], Some(text)) => text.to_string(),
+                (&[index], None) => self.factory.children[index].factory().text().unwrap_or_default().to_string(),
+                _ => return vec![self],
+            };
+            let parts = crate::sms::split_body(&text, max_segments);
+            if parts.len() < 2 {
+                return vec![self];
+            }
+
+            let with_text = |mut message: Message, part: &str| {
+                match bodies.first() {
+                    Some(&index) if index < message.factory.children.len() => {
+                        message.factory.children[index] = Box::new(Body::new(part));
+                    }
+                    Some(_) => message.factory.children.push(Box::new(Body::new(part))),
+                    None => message.factory.text = Some(Cow::Owned(part.to_string())),
+                }
+                message
+            };
+            let rest: Vec<Message> = parts[1..]
+                .iter()
+                .map(|part| {
+                    let mut message = Message::new_empty();
+                    message.factory.attributes = self.factory.attributes.clone();
+                    with_text(message, part)
+                })
+                .collect();
+            let first = parts[0].to_string();
+            self = with_text(self, &first);
+            std::iter::once(self).chain(rest).collect()
+        }
+    }
+    
+    #[cfg(feature = "messaging")]
+    impl TwiMLElement for Message {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+    
+    /// Enqueue TwiML Element to add call to a queue
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let enqueue = Enqueue::new("support").wait_url("/hold-music");
+    /// assert_eq!(
+    ///     enqueue.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Enqueue waitUrl="/hold-music">support</Enqueue>"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
+    #[derive(Debug)]
+    pub struct Enqueue {
+        factory: ElementFactory,
+    }
+    
+    #[cfg(feature = "voice")]
+    impl Enqueue {
+        /// Create a new Enqueue element
+        pub fn new(queue_name: impl Into<String>) -> Self {
+            Self {
+                factory: ElementFactory::new("Enqueue", Some(queue_name)),
+            }
+        }
+
+        /// Create an Enqueue without a queue name, which routes through TaskRouter with
+        /// [`workflow_sid`](Self::workflow_sid) and a [`Task`]
+        pub fn new_empty() -> Self {
+            Self {
+                factory: ElementFactory::new("Enqueue", None::<String>),
+            }
+        }
+        
+        /// Set action attribute
+        pub fn action(mut self, action: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("action", action.into().0);
+            self
+        }
+        
+        /// Set method attribute
+        pub fn method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("method", method.into());
+            self
+        }
+        
+        /// Set waitUrl attribute, the document played while the caller waits, such as
+        /// [`queue_wait_announcement`](crate::presets::queue_wait_announcement)
+        pub fn wait_url(mut self, url: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("waitUrl", url.into().0);
+            self
+        }
+        
+        /// Set waitUrlMethod attribute
+        pub fn wait_url_method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("waitUrlMethod", method.into());
+            self
+        }
+
+        /// Set workflowSid attribute to route the call through TaskRouter
+        pub fn workflow_sid(mut self, sid: impl Into<String>) -> Self {
+            self.factory.set_attribute("workflowSid", sid.into());
+            self
+        }
+
+        /// Add a Task noun; the Enqueue must be created with `new_empty`
+        pub fn task(mut self, task: Task) -> Self {
+            self.factory.children.push(Box::new(task));
+            self
+        }
+    }
+    
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Enqueue {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+    
+    /// Task TwiML noun for Enqueue carrying TaskRouter task attributes as JSON
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let task = Task::new(r#"{"language":"es"}"#).priority(5);
+    /// assert_eq!(
+    ///     task.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Task priority="5">{"language":"es"}</Task>"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
+    #[derive(Debug)]
+    pub struct Task {
+        factory: ElementFactory,
+    }
+
+    #[cfg(feature = "voice")]
+    impl Task {
+        /// Create a new Task element from a JSON attributes payload, without checking it
+        pub fn new(attributes: impl Into<String>) -> Self {
+            Self {
+                factory: ElementFactory::new("Task", Some(attributes)),
+            }
+        }
+
+        /// Create a Task from a JSON attributes payload, checking that it is a JSON object
+        pub fn from_json(attributes: impl Into<String>) -> Result<Self, ValidationError> {
+            let attributes = attributes.into();
+            let error = |reason: String| ValidationError {
+                element: "Task".to_string(),
+                attribute: None,
+                child: None,
+                reason,
+            };
+            match serde_json::from_str::<serde_json::Value>(&attributes) {
+                Ok(serde_json::Value::Object(_)) => Ok(Self::new(attributes)),
+                Ok(_) => Err(error("task attributes must be a JSON object".to_string())),
+                Err(err) => Err(error(format!("invalid JSON: {}", err))),
+            }
+        }
+
+        /// Create a Task by serializing a value to its JSON attributes payload
+        ///
+        /// The value must serialize to a JSON object, such as a struct or a map.
+        pub fn from_attributes<T: Serialize + ?Sized>(attributes: &T) -> Result<Self, ValidationError> {
+            match serde_json::to_value(attributes) {
+                Ok(value @ serde_json::Value::Object(_)) => Ok(Self::new(value.to_string())),
+                Ok(_) => Err(ValidationError {
+                    element: "Task".to_string(),
+                    attribute: None,
+                    child: None,
+                    reason: "task attributes must serialize to a JSON object".to_string(),
+                }),
+                Err(err) => Err(ValidationError {
+                    element: "Task".to_string(),
+                    attribute: None,
+                    child: None,
+                    reason: format!("cannot serialize task attributes: {}", err),
+                }),
+            }
+        }
+
+        /// Set priority attribute, at most [`MAX_TASK_PRIORITY`](crate::MAX_TASK_PRIORITY)
+        pub fn priority(mut self, priority: u32) -> Self {
+            self.factory.set_attribute("priority", priority.to_string());
+            self
+        }
+
+        /// Set timeout attribute in seconds, at most [`MAX_TASK_TIMEOUT`](crate::MAX_TASK_TIMEOUT)
+        pub fn timeout(mut self, timeout: impl Into<Seconds>) -> Self {
+            self.factory.set_attribute("timeout", timeout.into().to_string());
+            self
+        }
+
+        /// Emit the JSON inside a CDATA section instead of escaping it
+        pub fn cdata(mut self, enable: bool) -> Self {
+            self.factory.text_format = if enable { TextFormat::Cdata } else { TextFormat::Escaped };
+            self
         }
     }
 
-    /// Redirect TwiML Element
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Task {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+
+    /// Leave TwiML Element to exit a queue
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let leave = Leave::new();
+    /// assert_eq!(
+    ///     leave.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Leave />"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
     #[derive(Debug)]
-    pub struct Redirect {
+    pub struct Leave {
         factory: ElementFactory,
     }
-
-    impl Redirect {
-        /// Create a new Redirect element
-        pub fn new(url: impl Into<String>) -> Self {
+    
+    #[cfg(feature = "voice")]
+    impl Leave {
+        /// Create a new Leave element
+        pub fn new() -> Self {
             Self {
-                factory: ElementFactory::new("Redirect", Some(url)),
+                factory: ElementFactory::new("Leave", None::<String>),
             }
         }
-        
-        /// Set method attribute
-        pub fn method(mut self, method: impl Into<String>) -> Self {
-            self.factory.attributes.push(("method".to_string(), method.into()));
-            self
+    }
+    
+    #[cfg(feature = "voice")]
+    impl Default for Leave {
+        fn default() -> Self {
+            Self::new()
         }
     }
 
-    impl TwiMLElement for Redirect {
-        fn to_xml(&self) -> XMLElement {
-            self.factory.to_xml()
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Leave {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
         }
     }
-    
-    /// Play TwiML Element to play audio files
+
+    /// Type of token a Pay verb generates for the captured payment method
+    #[cfg(feature = "pay")]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+    pub enum TokenType {
+        /// Token valid for a single charge (`one-time`)
+        OneTime,
+        /// Token that can be charged repeatedly (`reusable`)
+        Reusable,
+        /// Processor payment-method token (`payment-method`)
+        PaymentMethod,
+    }
+
+    #[cfg(feature = "pay")]
+    impl TokenType {
+        /// Get the attribute value Twilio expects
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                TokenType::OneTime => "one-time",
+                TokenType::Reusable => "reusable",
+                TokenType::PaymentMethod => "payment-method",
+            }
+        }
+    }
+
+    #[cfg(feature = "pay")]
+    impl fmt::Display for TokenType {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.as_str())
+        }
+    }
+
+    /// Active ISO 4217 currency codes, excluding precious metals and testing codes
+    #[cfg(feature = "pay")]
+    pub(crate) const ISO_4217_CODES: &[&str] = &[
+        "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT",
+        "BGN", "BHD", "BIF", "BMD", "BND", "BOB", "BOV", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD",
+        "CAD", "CDF", "CHE", "CHF", "CHW", "CLF", "CLP", "CNY", "COP", "COU", "CRC", "CUC", "CUP",
+        "CVE", "CZK", "DJF", "DKK", "DOP", "DZD", "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP",
+        "GEL", "GHS", "GIP", "GMD", "GNF", "GTQ", "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS",
+        "INR", "IQD", "IRR", "ISK", "JMD", "JOD", "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW",
+        "KWD", "KYD", "KZT", "LAK", "LBP", "LKR", "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD",
+        "MMK", "MNT", "MOP", "MRU", "MUR", "MVR", "MWK", "MXN", "MXV", "MYR", "MZN", "NAD", "NGN",
+        "NIO", "NOK", "NPR", "NZD", "OMR", "PAB", "PEN", "PGK", "PHP", "PKR", "PLN", "PYG", "QAR",
+        "RON", "RSD", "RUB", "RWF", "SAR", "SBD", "SCR", "SDG", "SEK", "SGD", "SHP", "SLE", "SLL",
+        "SOS", "SRD", "SSP", "STN", "SVC", "SYP", "SZL", "THB", "TJS", "TMT", "TND", "TOP", "TRY",
+        "TTD", "TWD", "TZS", "UAH", "UGX", "USD", "USN", "UYI", "UYU", "UYW", "UZS", "VED", "VES",
+        "VND", "VUV", "WST", "XAF", "XCD", "XOF", "XPF", "YER", "ZAR", "ZMW", "ZWG", "ZWL",
+    ];
+
+    /// ISO 4217 codes of the currencies Twilio's Pay connectors charge in
+    #[cfg(feature = "pay")]
+    pub(crate) const PAY_CURRENCIES: &[&str] = &[
+        "AUD", "BRL", "CAD", "CHF", "CZK", "DKK", "EUR", "GBP", "HKD", "HUF", "INR", "JPY", "MXN",
+        "NOK", "NZD", "PLN", "SEK", "SGD", "USD", "ZAR",
+    ];
+
+    /// Currency code for Pay, normalized to the lowercase form Twilio expects
+    ///
+    /// Only ISO 4217 codes of currencies Twilio Pay supports are accepted.
+    #[cfg(feature = "pay")]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct Currency(String);
+
+    #[cfg(feature = "pay")]
+    impl Currency {
+        /// Parse a currency code, ignoring surrounding whitespace and case
+        pub fn parse(code: &str) -> Result<Self, ValidationError> {
+            let code = code.trim().to_ascii_uppercase();
+            let error = |reason: String| ValidationError {
+                element: "Pay".to_string(),
+                attribute: Some("currency".to_string()),
+                child: None,
+                reason,
+            };
+            if ISO_4217_CODES.binary_search(&code.as_str()).is_err() {
+                return Err(error(format!("`{}` is not an ISO 4217 currency code", code)));
+            }
+            if PAY_CURRENCIES.binary_search(&code.as_str()).is_err() {
+                return Err(error(format!("`{}` is not a currency Twilio Pay supports", code)));
+            }
+            Ok(Self(code.to_ascii_lowercase()))
+        }
+
+        /// US dollars, Twilio's default currency
+        pub fn usd() -> Self {
+            Self("usd".to_string())
+        }
+
+        /// Get the lowercase currency code
+        pub fn as_str(&self) -> &str {
+            &self.0
+        }
+    }
+
+    #[cfg(feature = "pay")]
+    impl fmt::Display for Currency {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(&self.0)
+        }
+    }
+
+    #[cfg(feature = "pay")]
+    impl FromStr for Currency {
+        type Err = ValidationError;
+
+        fn from_str(code: &str) -> Result<Self, Self::Err> {
+            Currency::parse(code)
+        }
+    }
+
+    /// Name of a Pay Connector configured in the Twilio console
+    #[cfg(feature = "pay")]
+    #[derive(Clone, PartialEq, Eq, Hash)]
+    pub struct PaymentConnector(String);
+
+    #[cfg(feature = "pay")]
+    impl Debug for PaymentConnector {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_tuple("PaymentConnector").field(&REDACTED).finish()
+        }
+    }
+
+    #[cfg(feature = "pay")]
+    impl PaymentConnector {
+        /// Create a connector reference from its unique name
+        pub fn new(name: impl Into<String>) -> Self {
+            Self(name.into())
+        }
+
+        /// Get the connector name
+        pub fn as_str(&self) -> &str {
+            &self.0
+        }
+    }
+
+    #[cfg(feature = "pay")]
+    impl Default for PaymentConnector {
+        /// The connector Twilio uses when none is specified
+        fn default() -> Self {
+            Self::new("Default")
+        }
+    }
+
+    #[cfg(feature = "pay")]
+    impl From<&str> for PaymentConnector {
+        fn from(name: &str) -> Self {
+            Self::new(name)
+        }
+    }
+
+    #[cfg(feature = "pay")]
+    impl From<String> for PaymentConnector {
+        fn from(name: String) -> Self {
+            Self::new(name)
+        }
+    }
+
+    /// Pay TwiML Element to capture payment details over the phone
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let pay = Pay::new()
+    ///     .charge_amount("10.00")
+    ///     .payment_connector("Stripe_Connector")
+    ///     .max_attempts(2);
+    /// assert_eq!(
+    ///     pay.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Pay chargeAmount="10.00" paymentConnector="Stripe_Connector" maxAttempts="2" />"#
+    /// );
+    /// ```
+    #[cfg(feature = "pay")]
     #[derive(Debug)]
-    pub struct Play {
+    pub struct Pay {
         factory: ElementFactory,
     }
-    
-    impl Play {
-        /// Create a new Play element
-        pub fn new(url: impl Into<String>) -> Self {
+
+    #[cfg(feature = "pay")]
+    impl Pay {
+        /// Create a new Pay element
+        pub fn new() -> Self {
             Self {
-                factory: ElementFactory::new("Play", Some(url)),
+                factory: ElementFactory::new("Pay", None::<String>),
             }
         }
-        
-        /// Set loop attribute
-        pub fn loop_times(mut self, loops: usize) -> Self {
-            self.factory.attributes.push(("loop".to_string(), loops.to_string()));
+
+        /// Set input attribute
+        pub fn input(mut self, input: impl Into<String>) -> Self {
+            self.factory.set_attribute("input", input.into());
             self
         }
-        
-        /// Set digits attribute
-        pub fn digits(mut self, digits: impl Into<String>) -> Self {
-            self.factory.attributes.push(("digits".to_string(), digits.into()));
+
+        /// Set action attribute
+        pub fn action(mut self, action: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("action", action.into().0);
+            self
+        }
+
+        /// Set bankAccountType attribute
+        pub fn bank_account_type(mut self, account_type: impl Into<String>) -> Self {
+            self.factory.set_attribute("bankAccountType", account_type.into());
+            self
+        }
+
+        /// Set chargeAmount attribute; omit or use 0 to tokenize without charging
+        pub fn charge_amount(mut self, amount: impl Into<String>) -> Self {
+            self.factory.set_attribute("chargeAmount", amount.into());
+            self
+        }
+
+        /// Set currency attribute
+        pub fn currency(mut self, currency: Currency) -> Self {
+            self.factory.set_attribute("currency", currency.0);
+            self
+        }
+
+        /// Set description attribute
+        pub fn description(mut self, description: impl Into<String>) -> Self {
+            self.factory.set_attribute("description", description.into());
+            self
+        }
+
+        /// Set language attribute
+        pub fn language(mut self, language: impl Into<String>) -> Self {
+            self.factory.set_attribute("language", language.into());
+            self
+        }
+
+        /// Set maxAttempts attribute, from 1 to [`MAX_PAY_ATTEMPTS`](crate::MAX_PAY_ATTEMPTS)
+        pub fn max_attempts(mut self, attempts: u32) -> Self {
+            self.factory.set_attribute("maxAttempts", attempts.to_string());
+            self
+        }
+
+        /// Set minPostalCodeLength attribute
+        pub fn min_postal_code_length(mut self, length: u32) -> Self {
+            self.factory.set_attribute("minPostalCodeLength", length.to_string());
+            self
+        }
+
+        /// Set paymentConnector attribute
+        pub fn payment_connector(mut self, connector: impl Into<PaymentConnector>) -> Self {
+            self.factory.set_attribute("paymentConnector", connector.into().0);
+            self
+        }
+
+        /// Set paymentMethod attribute (ach-debit or credit-card)
+        pub fn payment_method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("paymentMethod", method.into());
+            self
+        }
+
+        /// Set postalCode attribute
+        pub fn postal_code(mut self, postal_code: bool) -> Self {
+            self.factory.set_attribute("postalCode", postal_code.to_string());
+            self
+        }
+
+        /// Set securityCode attribute
+        pub fn security_code(mut self, security_code: bool) -> Self {
+            self.factory.set_attribute("securityCode", security_code.to_string());
+            self
+        }
+
+        /// Set statusCallback attribute
+        pub fn status_callback(mut self, url: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("statusCallback", url.into().0);
+            self
+        }
+
+        /// Set statusCallbackMethod attribute
+        pub fn status_callback_method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("statusCallbackMethod", method.into());
+            self
+        }
+
+        /// Set timeout attribute in seconds
+        pub fn timeout(mut self, timeout: impl Into<Seconds>) -> Self {
+            self.factory.set_attribute("timeout", timeout.into().to_string());
+            self
+        }
+
+        /// Set tokenType attribute
+        pub fn token_type(mut self, token_type: TokenType) -> Self {
+            self.factory.set_attribute("tokenType", token_type.to_string());
+            self
+        }
+
+        /// Set validCardTypes attribute (space-separated card brands)
+        pub fn valid_card_types(mut self, card_types: impl Into<String>) -> Self {
+            self.factory.set_attribute("validCardTypes", card_types.into());
+            self
+        }
+
+        /// Add a Prompt child element
+        pub fn prompt(mut self, prompt: Prompt) -> Self {
+            self.factory.children.push(Box::new(prompt));
+            self
+        }
+
+        /// Add a Parameter child passed to the payment connector
+        pub fn parameter(mut self, parameter: Parameter) -> Self {
+            self.factory.children.push(Box::new(parameter));
             self
         }
     }
-    
-    impl TwiMLElement for Play {
-        fn to_xml(&self) -> XMLElement {
-            self.factory.to_xml()
+
+    #[cfg(feature = "pay")]
+    impl Default for Pay {
+        fn default() -> Self {
+            Self::new()
         }
     }
-    
-    /// Pause TwiML Element for silent pause
+
+    #[cfg(feature = "pay")]
+    impl TwiMLElement for Pay {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+
+    /// Denoise SignalWire extension element to enable noise reduction on the call
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let denoise = Denoise::new();
+    /// assert_eq!(
+    ///     denoise.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Denoise />"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
     #[derive(Debug)]
-    pub struct Pause {
+    pub struct Denoise {
         factory: ElementFactory,
     }
-    
-    impl Pause {
-        /// Create a new Pause element
+
+    #[cfg(feature = "voice")]
+    impl Denoise {
+        /// Create a new Denoise element
         pub fn new() -> Self {
             Self {
-                factory: ElementFactory::new("Pause", None::<String>),
+                factory: ElementFactory::new("Denoise", None::<String>),
             }
         }
-        
-        /// Set length attribute (seconds)
-        pub fn length(mut self, seconds: usize) -> Self {
-            self.factory.attributes.push(("length".to_string(), seconds.to_string()));
-            self
+    }
+
+    #[cfg(feature = "voice")]
+    impl Default for Denoise {
+        fn default() -> Self {
+            Self::new()
         }
     }
-    
-    impl TwiMLElement for Pause {
-        fn to_xml(&self) -> XMLElement {
-            self.factory.to_xml()
+
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Denoise {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
         }
     }
-    
-    /// Hangup TwiML Element to end a call
+
+    /// Receive TwiML verb accepting an incoming fax
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let receive = Receive::new().action("/fax-received").store_media(true);
+    /// assert_eq!(
+    ///     receive.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Receive action="/fax-received" storeMedia="true" />"#
+    /// );
+    /// ```
+    #[cfg(feature = "fax")]
     #[derive(Debug)]
-    pub struct Hangup {
+    pub struct Receive {
         factory: ElementFactory,
     }
-    
-    impl Hangup {
-        /// Create a new Hangup element
+
+    #[cfg(feature = "fax")]
+    impl Receive {
+        /// Create a new Receive element
         pub fn new() -> Self {
             Self {
-                factory: ElementFactory::new("Hangup", None::<String>),
+                factory: ElementFactory::new("Receive", None::<String>),
             }
         }
+
+        /// Set action attribute, requested once the fax has been received
+        pub fn action(mut self, action: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("action", action.into().0);
+            self
+        }
+
+        /// Set method attribute
+        pub fn method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("method", method.into());
+            self
+        }
+
+        /// Set mediaType attribute, `application/pdf` or `image/tiff`
+        pub fn media_type(mut self, media_type: impl Into<String>) -> Self {
+            self.factory.set_attribute("mediaType", media_type.into());
+            self
+        }
+
+        /// Set pageSize attribute, `letter`, `legal` or `a4`
+        pub fn page_size(mut self, page_size: impl Into<String>) -> Self {
+            self.factory.set_attribute("pageSize", page_size.into());
+            self
+        }
+
+        /// Set storeMedia attribute
+        pub fn store_media(mut self, store_media: bool) -> Self {
+            self.factory.set_attribute("storeMedia", store_media.to_string());
+            self
+        }
     }
-    
-    impl TwiMLElement for Hangup {
-        fn to_xml(&self) -> XMLElement {
-            self.factory.to_xml()
+
+    #[cfg(feature = "fax")]
+    impl Default for Receive {
+        fn default() -> Self {
+            Self::new()
         }
     }
-    
-    /// Record TwiML Element to record caller's voice
+
+    #[cfg(feature = "fax")]
+    impl TwiMLElement for Receive {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+
+    /// Connect TwiML verb connecting the call to a bot, stream or room
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let connect = Connect::new()
+    ///     .action("/bot-done")
+    ///     .virtual_agent(VirtualAgent::new("support-bot"));
+    /// assert_eq!(
+    ///     connect.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Connect action="/bot-done"><VirtualAgent connectorName="support-bot" /></Connect>"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
     #[derive(Debug)]
-    pub struct Record {
+    pub struct Connect {
         factory: ElementFactory,
     }
-    
-    impl Record {
-        /// Create a new Record element
+
+    #[cfg(feature = "voice")]
+    impl Connect {
+        /// Create a new Connect element
         pub fn new() -> Self {
             Self {
-                factory: ElementFactory::new("Record", None::<String>),
+                factory: ElementFactory::new("Connect", None::<String>),
             }
         }
-        
+
         /// Set action attribute
-        pub fn action(mut self, action: impl Into<String>) -> Self {
-            self.factory.attributes.push(("action".to_string(), action.into()));
+        pub fn action(mut self, action: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("action", action.into().0);
             self
         }
-        
+
         /// Set method attribute
         pub fn method(mut self, method: impl Into<String>) -> Self {
-            self.factory.attributes.push(("method".to_string(), method.into()));
-            self
-        }
-        
-        /// Set timeout attribute
-        pub fn timeout(mut self, timeout: usize) -> Self {
-            self.factory.attributes.push(("timeout".to_string(), timeout.to_string()));
-            self
-        }
-        
-        /// Set finishOnKey attribute
-        pub fn finish_on_key(mut self, key: impl Into<String>) -> Self {
-            self.factory.attributes.push(("finishOnKey".to_string(), key.into()));
+            self.factory.set_attribute("method", method.into());
             self
         }
-        
-        /// Set maxLength attribute
-        pub fn max_length(mut self, seconds: usize) -> Self {
-            self.factory.attributes.push(("maxLength".to_string(), seconds.to_string()));
+
+        /// Add a ConversationRelay noun
+        pub fn conversation_relay(mut self, relay: ConversationRelay) -> Self {
+            self.factory.children.push(Box::new(relay));
             self
         }
-        
-        /// Set playBeep attribute
-        pub fn play_beep(mut self, play_beep: bool) -> Self {
-            self.factory.attributes.push(("playBeep".to_string(), play_beep.to_string()));
+
+        /// Add a VirtualAgent noun
+        pub fn virtual_agent(mut self, agent: VirtualAgent) -> Self {
+            self.factory.children.push(Box::new(agent));
             self
         }
-        
-        /// Set recordingStatusCallback attribute
-        pub fn recording_status_callback(mut self, url: impl Into<String>) -> Self {
-            self.factory.attributes.push(("recordingStatusCallback".to_string(), url.into()));
+
+        /// Add a Stream noun
+        pub fn stream(mut self, stream: Stream) -> Self {
+            self.factory.children.push(Box::new(stream));
             self
         }
-        
-        /// Set recordingStatusCallbackMethod attribute
-        pub fn recording_status_callback_method(mut self, method: impl Into<String>) -> Self {
-            self.factory.attributes.push(("recordingStatusCallbackMethod".to_string(), method.into()));
+
+        /// Add a Room noun
+        pub fn room(mut self, room: Room) -> Self {
+            self.factory.children.push(Box::new(room));
             self
         }
-        
-        /// Set transcribe attribute
-        pub fn transcribe(mut self, transcribe: bool) -> Self {
-            self.factory.attributes.push(("transcribe".to_string(), transcribe.to_string()));
+
+        /// Add a Conversation noun
+        pub fn conversation(mut self, conversation: Conversation) -> Self {
+            self.factory.children.push(Box::new(conversation));
             self
         }
-        
-        /// Set transcribeCallback attribute
-        pub fn transcribe_callback(mut self, url: impl Into<String>) -> Self {
-            self.factory.attributes.push(("transcribeCallback".to_string(), url.into()));
+
+        /// Add a noun this crate doesn't model yet, such as one built with [`ElementFactory`]
+        ///
+        /// Connect takes exactly one noun; validation rejects a Connect with none or several.
+        pub fn append_noun(mut self, noun: impl TwiMLElement) -> Self {
+            self.factory.children.push(Box::new(noun));
             self
         }
     }
-    
-    impl TwiMLElement for Record {
-        fn to_xml(&self) -> XMLElement {
-            self.factory.to_xml()
+
+    #[cfg(feature = "voice")]
+    impl Default for Connect {
+        fn default() -> Self {
+            Self::new()
         }
     }
-    
-    /// Dial TwiML Element to connect call to another phone
+
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Connect {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+
+    /// ConversationRelay TwiML noun for Connect, relaying the call to a voice bot over WebSocket
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let relay = ConversationRelay::new("wss://bot.example.com/relay")
+    ///     .welcome_greeting("Hi!")
+    ///     .language("en-US");
+    /// assert_eq!(
+    ///     relay.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><ConversationRelay url="wss://bot.example.com/relay" welcomeGreeting="Hi!" language="en-US" />"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
     #[derive(Debug)]
-    pub struct Dial {
+    pub struct ConversationRelay {
         factory: ElementFactory,
     }
-    
-    impl Dial {
-        /// Create a new Dial element with optional number to dial
-        pub fn new(number: Option<impl Into<String>>) -> Self {
-            Self {
-                factory: ElementFactory::new("Dial", number.map(|n| n.into())),
-            }
+
+    #[cfg(feature = "voice")]
+    impl ConversationRelay {
+        /// Create a new ConversationRelay element for a `wss://` URL
+        pub fn new(url: impl Into<UrlValue>) -> Self {
+            let mut factory = ElementFactory::new("ConversationRelay", None::<String>);
+            factory.attributes.push(("url".to_string(), url.into().0));
+            Self { factory }
         }
-        
-        /// Create a new empty Dial element without any number
-        pub fn new_empty() -> Self {
-            Self {
-                factory: ElementFactory::new("Dial", None::<String>),
-            }
+
+        /// Set welcomeGreeting attribute
+        pub fn welcome_greeting(mut self, greeting: impl Into<String>) -> Self {
+            self.factory.set_attribute("welcomeGreeting", greeting.into());
+            self
         }
-        
-        /// Set action attribute
-        pub fn action(mut self, action: impl Into<String>) -> Self {
-            self.factory.attributes.push(("action".to_string(), action.into()));
+
+        /// Set welcomeGreetingInterruptible attribute: `any`, `speech`, `dtmf` or `none`
+        pub fn welcome_greeting_interruptible(mut self, mode: impl Into<String>) -> Self {
+            self.factory.set_attribute("welcomeGreetingInterruptible", mode.into());
             self
         }
-        
-        /// Set method attribute
-        pub fn method(mut self, method: impl Into<String>) -> Self {
-            self.factory.attributes.push(("method".to_string(), method.into()));
+
+        /// Set language attribute, the default for both speech and transcription
+        pub fn language(mut self, language: impl Into<String>) -> Self {
+            self.factory.set_attribute("language", language.into());
             self
         }
-        
-        /// Set timeout attribute
-        pub fn timeout(mut self, timeout: usize) -> Self {
-            self.factory.attributes.push(("timeout".to_string(), timeout.to_string()));
+
+        /// Set ttsLanguage attribute
+        pub fn tts_language(mut self, language: impl Into<String>) -> Self {
+            self.factory.set_attribute("ttsLanguage", language.into());
             self
         }
-        
-        /// Set callerId attribute
-        pub fn caller_id(mut self, caller_id: impl Into<String>) -> Self {
-            self.factory.attributes.push(("callerId".to_string(), caller_id.into()));
+
+        /// Set ttsProvider attribute
+        pub fn tts_provider(mut self, provider: impl Into<String>) -> Self {
+            self.factory.set_attribute("ttsProvider", provider.into());
             self
         }
-        
-        /// Set record attribute
-        pub fn record(mut self, record: impl Into<String>) -> Self {
-            self.factory.attributes.push(("record".to_string(), record.into()));
+
+        /// Set voice attribute
+        pub fn voice(mut self, voice: impl Into<String>) -> Self {
+            self.factory.set_attribute("voice", voice.into());
             self
         }
-        
-        /// Set Number child element
-        pub fn number(mut self, number: Number) -> Self {
-            self.factory.children.push(Box::new(number));
+
+        /// Set transcriptionLanguage attribute
+        pub fn transcription_language(mut self, language: impl Into<String>) -> Self {
+            self.factory.set_attribute("transcriptionLanguage", language.into());
             self
         }
-        
-        /// Set Client child element
-        pub fn client(mut self, client: Client) -> Self {
-            self.factory.children.push(Box::new(client));
+
+        /// Set transcriptionProvider attribute
+        pub fn transcription_provider(mut self, provider: impl Into<String>) -> Self {
+            self.factory.set_attribute("transcriptionProvider", provider.into());
             self
         }
-        
-        /// Set Conference child element
-        pub fn conference(mut self, conference: Conference) -> Self {
-            self.factory.children.push(Box::new(conference));
+
+        /// Set speechModel attribute
+        pub fn speech_model(mut self, model: impl Into<String>) -> Self {
+            self.factory.set_attribute("speechModel", model.into());
             self
         }
-        
-        /// Set Sip child element
-        pub fn sip(mut self, sip: Sip) -> Self {
-            self.factory.children.push(Box::new(sip));
+
+        /// Set interruptible attribute: `any`, `speech`, `dtmf` or `none`
+        pub fn interruptible(mut self, mode: impl Into<String>) -> Self {
+            self.factory.set_attribute("interruptible", mode.into());
+            self
+        }
+
+        /// Set dtmfDetection attribute
+        pub fn dtmf_detection(mut self, enable: bool) -> Self {
+            self.factory.set_attribute("dtmfDetection", enable.to_string());
+            self
+        }
+
+        /// Set hints attribute
+        pub fn hints(mut self, hints: impl Into<String>) -> Self {
+            self.factory.set_attribute("hints", hints.into());
+            self
+        }
+
+        /// Add a Language child configuring voice and transcription for one language
+        pub fn language_config(mut self, language: Language) -> Self {
+            self.factory.children.push(Box::new(language));
+            self
+        }
+
+        /// Add a Parameter child passed to the WebSocket server in the setup message
+        pub fn parameter(mut self, parameter: Parameter) -> Self {
+            self.factory.children.push(Box::new(parameter));
             self
         }
     }
-    
-    impl TwiMLElement for Dial {
-        fn to_xml(&self) -> XMLElement {
-            self.factory.to_xml()
+
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for ConversationRelay {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
         }
     }
-    
-    /// Number TwiML Element noun for Dial
+
+    /// Language TwiML noun for ConversationRelay configuring one language
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let language = Language::new("fr-FR").tts_provider("ElevenLabs").voice("Jeanne");
+    /// assert_eq!(
+    ///     language.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Language code="fr-FR" ttsProvider="ElevenLabs" voice="Jeanne" />"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
     #[derive(Debug)]
-    pub struct Number {
+    pub struct Language {
         factory: ElementFactory,
     }
-    
-    impl Number {
-        /// Create a new Number element
-        pub fn new(number: impl Into<String>) -> Self {
-            Self {
-                factory: ElementFactory::new("Number", Some(number)),
-            }
+
+    #[cfg(feature = "voice")]
+    impl Language {
+        /// Create a new Language element for a language code such as `fr-FR`
+        pub fn new(code: impl Into<String>) -> Self {
+            let mut factory = ElementFactory::new("Language", None::<String>);
+            factory.attributes.push(("code".to_string(), code.into()));
+            Self { factory }
         }
-        
-        /// Set sendDigits attribute
-        pub fn send_digits(mut self, digits: impl Into<String>) -> Self {
-            self.factory.attributes.push(("sendDigits".to_string(), digits.into()));
+
+        /// Set ttsProvider attribute
+        pub fn tts_provider(mut self, provider: impl Into<String>) -> Self {
+            self.factory.set_attribute("ttsProvider", provider.into());
             self
         }
-        
-        /// Set url attribute
-        pub fn url(mut self, url: impl Into<String>) -> Self {
-            self.factory.attributes.push(("url".to_string(), url.into()));
+
+        /// Set voice attribute
+        pub fn voice(mut self, voice: impl Into<String>) -> Self {
+            self.factory.set_attribute("voice", voice.into());
+            self
+        }
+
+        /// Set transcriptionProvider attribute
+        pub fn transcription_provider(mut self, provider: impl Into<String>) -> Self {
+            self.factory.set_attribute("transcriptionProvider", provider.into());
+            self
+        }
+
+        /// Set speechModel attribute
+        pub fn speech_model(mut self, model: impl Into<String>) -> Self {
+            self.factory.set_attribute("speechModel", model.into());
             self
         }
     }
-    
-    impl TwiMLElement for Number {
-        fn to_xml(&self) -> XMLElement {
-            self.factory.to_xml()
+
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Language {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
         }
     }
-    
-    /// Client TwiML Element noun for Dial
+
+    /// VirtualAgent TwiML noun for Connect, handing the call to a Dialogflow CX agent
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let agent = VirtualAgent::new("support-bot")
+    ///     .language("es-ES")
+    ///     .config(Config::new("voiceName", "es-ES-Standard-A"));
+    /// assert_eq!(
+    ///     agent.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><VirtualAgent connectorName="support-bot" language="es-ES"><Config name="voiceName" value="es-ES-Standard-A" /></VirtualAgent>"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
     #[derive(Debug)]
-    pub struct Client {
+    pub struct VirtualAgent {
         factory: ElementFactory,
     }
-    
-    impl Client {
-        /// Create a new Client element
-        pub fn new(client_id: impl Into<String>) -> Self {
-            Self {
-                factory: ElementFactory::new("Client", Some(client_id)),
-            }
+
+    #[cfg(feature = "voice")]
+    impl VirtualAgent {
+        /// Create a new VirtualAgent element for a connector configured in the console
+        pub fn new(connector_name: impl Into<String>) -> Self {
+            let mut factory = ElementFactory::new("VirtualAgent", None::<String>);
+            factory.attributes.push(("connectorName".to_string(), connector_name.into()));
+            Self { factory }
         }
-        
-        /// Set url attribute
-        pub fn url(mut self, url: impl Into<String>) -> Self {
-            self.factory.attributes.push(("url".to_string(), url.into()));
+
+        /// Set language attribute
+        pub fn language(mut self, language: impl Into<String>) -> Self {
+            self.factory.set_attribute("language", language.into());
             self
         }
-        
-        /// Set method attribute
-        pub fn method(mut self, method: impl Into<String>) -> Self {
-            self.factory.attributes.push(("method".to_string(), method.into()));
+
+        /// Set sentimentAnalysis attribute
+        pub fn sentiment_analysis(mut self, enable: bool) -> Self {
+            self.factory.set_attribute("sentimentAnalysis", enable.to_string());
             self
         }
-        
+
         /// Set statusCallback attribute
-        pub fn status_callback(mut self, url: impl Into<String>) -> Self {
-            self.factory.attributes.push(("statusCallback".to_string(), url.into()));
+        pub fn status_callback(mut self, url: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("statusCallback", url.into().0);
             self
         }
-        
-        /// Set statusCallbackMethod attribute
-        pub fn status_callback_method(mut self, method: impl Into<String>) -> Self {
-            self.factory.attributes.push(("statusCallbackMethod".to_string(), method.into()));
+
+        /// Add a Config child overriding a connector setting for this call
+        pub fn config(mut self, config: Config) -> Self {
+            self.factory.children.push(Box::new(config));
+            self
+        }
+
+        /// Add a Parameter child sent to the agent as a session parameter
+        pub fn parameter(mut self, parameter: Parameter) -> Self {
+            self.factory.children.push(Box::new(parameter));
             self
         }
     }
-    
-    impl TwiMLElement for Client {
-        fn to_xml(&self) -> XMLElement {
-            self.factory.to_xml()
+
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for VirtualAgent {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
         }
     }
-    
-    /// Conference TwiML Element noun for Dial
+
+    /// Config TwiML noun for VirtualAgent setting a connector option such as `voiceName`
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let config = Config::new("voiceName", "en-US-Standard-C");
+    /// assert_eq!(
+    ///     config.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Config name="voiceName" value="en-US-Standard-C" />"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
     #[derive(Debug)]
-    pub struct Conference {
+    pub struct Config {
         factory: ElementFactory,
     }
-    
-    impl Conference {
-        /// Create a new Conference element
-        pub fn new(room_name: impl Into<String>) -> Self {
-            Self {
-                factory: ElementFactory::new("Conference", Some(room_name)),
-            }
+
+    #[cfg(feature = "voice")]
+    impl Config {
+        /// Create a new Config element
+        pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+            let mut factory = ElementFactory::new("Config", None::<String>);
+            factory.attributes.push(("name".to_string(), name.into()));
+            factory.attributes.push(("value".to_string(), value.into()));
+            Self { factory }
         }
-        
-        /// Set muted attribute
-        pub fn muted(mut self, muted: bool) -> Self {
-            self.factory.attributes.push(("muted".to_string(), muted.to_string()));
-            self
+    }
+
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Config {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
         }
-        
-        /// Set startConferenceOnEnter attribute
-        pub fn start_conference_on_enter(mut self, start: bool) -> Self {
-            self.factory.attributes.push(("startConferenceOnEnter".to_string(), start.to_string()));
-            self
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
         }
-        
-        /// Set endConferenceOnExit attribute
-        pub fn end_conference_on_exit(mut self, end: bool) -> Self {
-            self.factory.attributes.push(("endConferenceOnExit".to_string(), end.to_string()));
+    }
+
+    /// Parameter TwiML noun passing a custom name/value pair
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let parameter = Parameter::new("customer", "42");
+    /// assert_eq!(
+    ///     parameter.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Parameter name="customer" value="42" />"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
+    #[derive(Debug)]
+    pub struct Parameter {
+        factory: ElementFactory,
+    }
+
+    #[cfg(feature = "voice")]
+    impl Parameter {
+        /// Create a new Parameter element
+        pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+            let mut factory = ElementFactory::new("Parameter", None::<String>);
+            factory.attributes.push(("name".to_string(), name.into()));
+            factory.attributes.push(("value".to_string(), value.into()));
+            Self { factory }
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Parameter {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
+        }
+    }
+
+    /// Stream TwiML noun for Connect, streaming the call audio both ways over a WebSocket
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let stream = Stream::new("wss://media.example.com/audio").name("agent-assist");
+    /// assert_eq!(
+    ///     stream.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Stream url="wss://media.example.com/audio" name="agent-assist" />"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
+    #[derive(Debug)]
+    pub struct Stream {
+        factory: ElementFactory,
+    }
+
+    #[cfg(feature = "voice")]
+    impl Stream {
+        /// Create a new Stream element for a `wss://` URL
+        pub fn new(url: impl Into<UrlValue>) -> Self {
+            let mut factory = ElementFactory::new("Stream", None::<String>);
+            factory.attributes.push(("url".to_string(), url.into().0));
+            Self { factory }
+        }
+
+        /// Set name attribute
+        pub fn name(mut self, name: impl Into<String>) -> Self {
+            self.factory.set_attribute("name", name.into());
             self
         }
-        
-        /// Set maxParticipants attribute
-        pub fn max_participants(mut self, max: usize) -> Self {
-            self.factory.attributes.push(("maxParticipants".to_string(), max.to_string()));
+
+        /// Set statusCallback attribute
+        pub fn status_callback(mut self, url: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("statusCallback", url.into().0);
             self
         }
-        
-        /// Set beep attribute
-        pub fn beep(mut self, beep: bool) -> Self {
-            self.factory.attributes.push(("beep".to_string(), beep.to_string()));
+
+        /// Set statusCallbackMethod attribute
+        pub fn status_callback_method(mut self, method: impl Into<String>) -> Self {
+            self.factory.set_attribute("statusCallbackMethod", method.into());
             self
         }
-        
-        /// Set record attribute
-        pub fn record(mut self, record: impl Into<String>) -> Self {
-            self.factory.attributes.push(("record".to_string(), record.into()));
+
+        /// Add a Parameter child sent in the stream's `start` message
+        pub fn parameter(mut self, parameter: Parameter) -> Self {
+            self.factory.children.push(Box::new(parameter));
             self
         }
     }
-    
-    impl TwiMLElement for Conference {
-        fn to_xml(&self) -> XMLElement {
-            self.factory.to_xml()
+
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Stream {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
         }
     }
-    
-    /// Sip TwiML Element noun for Dial
+
+    /// Room TwiML noun for Connect, joining the call to a Programmable Video room
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let room = Room::new("DailyStandup").participant_identity("alice");
+    /// assert_eq!(
+    ///     room.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Room participantIdentity="alice">DailyStandup</Room>"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
     #[derive(Debug)]
-    pub struct Sip {
+    pub struct Room {
         factory: ElementFactory,
     }
-    
-    impl Sip {
-        /// Create a new Sip element
-        pub fn new(sip_url: impl Into<String>) -> Self {
+
+    #[cfg(feature = "voice")]
+    impl Room {
+        /// Create a new Room element for a room name or SID, at most
+        /// [`MAX_ROOM_NAME_CHARACTERS`](crate::MAX_ROOM_NAME_CHARACTERS) long
+        pub fn new(room: impl Into<String>) -> Self {
             Self {
-                factory: ElementFactory::new("Sip", Some(sip_url)),
+                factory: ElementFactory::new("Room", Some(room)),
             }
         }
-        
-        /// Set username attribute
-        pub fn username(mut self, username: impl Into<String>) -> Self {
-            self.factory.attributes.push(("username".to_string(), username.into()));
-            self
-        }
-        
-        /// Set password attribute
-        pub fn password(mut self, password: impl Into<String>) -> Self {
-            self.factory.attributes.push(("password".to_string(), password.into()));
+
+        /// Set participantIdentity attribute, the identity the caller joins the room with
+        pub fn participant_identity(mut self, identity: impl Into<String>) -> Self {
+            self.factory.set_attribute("participantIdentity", identity.into());
             self
         }
     }
-    
-    impl TwiMLElement for Sip {
-        fn to_xml(&self) -> XMLElement {
-            self.factory.to_xml()
+
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Room {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
         }
     }
-    
-    /// SMS TwiML Element to send text message during a call
+
+    /// Conversation TwiML noun for Connect, adding the call to a Flex conversation
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let conversation = Conversation::new("IS0123").inbound_timeout(60);
+    /// assert_eq!(
+    ///     conversation.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Conversation serviceInstanceSid="IS0123" inboundTimeout="60" />"#
+    /// );
+    /// ```
+    #[cfg(feature = "voice")]
     #[derive(Debug)]
-    pub struct Sms {
+    pub struct Conversation {
         factory: ElementFactory,
     }
-    
-    impl Sms {
-        /// Create a new Sms element
-        pub fn new(message: impl Into<String>) -> Self {
-            Self {
-                factory: ElementFactory::new("Sms", Some(message)),
-            }
+
+    #[cfg(feature = "voice")]
+    impl Conversation {
+        /// Create a new Conversation element for a Conversations service instance
+        pub fn new(service_instance_sid: impl Into<String>) -> Self {
+            let mut factory = ElementFactory::new("Conversation", None::<String>);
+            factory.attributes.push(("serviceInstanceSid".to_string(), service_instance_sid.into()));
+            Self { factory }
         }
-        
-        /// Set to attribute
-        pub fn to(mut self, to: impl Into<String>) -> Self {
-            self.factory.attributes.push(("to".to_string(), to.into()));
+
+        /// Set inboundAutocreation attribute
+        pub fn inbound_autocreation(mut self, enable: bool) -> Self {
+            self.factory.set_attribute("inboundAutocreation", enable.to_string());
             self
         }
-        
-        /// Set from attribute
-        pub fn from(mut self, from: impl Into<String>) -> Self {
-            self.factory.attributes.push(("from".to_string(), from.into()));
+
+        /// Set routingAssignmentTimeout attribute in seconds
+        pub fn routing_assignment_timeout(mut self, timeout: impl Into<Seconds>) -> Self {
+            self.factory.set_attribute("routingAssignmentTimeout", timeout.into().to_string());
             self
         }
-        
-        /// Set action attribute
-        pub fn action(mut self, action: impl Into<String>) -> Self {
-            self.factory.attributes.push(("action".to_string(), action.into()));
+
+        /// Set inboundTimeout attribute in seconds
+        pub fn inbound_timeout(mut self, timeout: impl Into<Seconds>) -> Self {
+            self.factory.set_attribute("inboundTimeout", timeout.into().to_string());
             self
         }
-        
+
+        /// Set url attribute, requested when the conversation ends
+        pub fn url(mut self, url: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("url", url.into().0);
+            self
+        }
+
         /// Set method attribute
         pub fn method(mut self, method: impl Into<String>) -> Self {
-            self.factory.attributes.push(("method".to_string(), method.into()));
+            self.factory.set_attribute("method", method.into());
             self
         }
-    }
-    
-    impl TwiMLElement for Sms {
-        fn to_xml(&self) -> XMLElement {
-            self.factory.to_xml()
+
+        /// Set record attribute
+        pub fn record(mut self, record: impl Into<String>) -> Self {
+            self.factory.set_attribute("record", record.into());
+            self
         }
-    }
-    
-    /// Body TwiML Element used within Message
-    #[derive(Debug)]
-    pub struct Body {
-        factory: ElementFactory,
-    }
-    
-    impl Body {
-        /// Create a new Body element
-        pub fn new(text: impl Into<String>) -> Self {
-            Self {
-                factory: ElementFactory::new("Body", Some(text)),
-            }
+
+        /// Set statusCallback attribute
+        pub fn status_callback(mut self, url: impl Into<UrlValue>) -> Self {
+            self.factory.set_attribute("statusCallback", url.into().0);
+            self
+        }
+
+        /// Set statusCallbackEvent attribute
+        pub fn status_callback_event(mut self, events: EventSet) -> Self {
+            self.factory.set_attribute("statusCallbackEvent", events.to_string());
+            self
         }
     }
-    
-    impl TwiMLElement for Body {
-        fn to_xml(&self) -> XMLElement {
-            self.factory.to_xml()
+
+    #[cfg(feature = "voice")]
+    impl TwiMLElement for Conversation {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
         }
     }
-    
-    /// Message TwiML Element for sending messages
+
+    /// Response TwiML Element - the root element
+    ///
+    /// ```
+    /// use twiml::*;
+    ///
+    /// let response = Response::new().redirect("/menu");
+    /// assert_eq!(
+    ///     response.to_xml_string(),
+    ///     r#"<?xml version="1.1" encoding="UTF-8"?><Response><Redirect>/menu</Redirect></Response>"#
+    /// );
+    /// ```
     #[derive(Debug)]
-    pub struct Message {
+    pub struct Response {
         factory: ElementFactory,
     }
-    
-    impl Message {
-        /// Create a new Message element with optional text
-        pub fn new(text: Option<impl Into<String>>) -> Self {
+
+    impl Response {
+        /// Create a new Response element
+        pub fn new() -> Self {
             Self {
-                factory: ElementFactory::new("Message", text.map(|t| t.into())),
+                factory: ElementFactory::new("Response", None::<String>),
             }
         }
-        
-        /// Create a new Message element with no text content
-        pub fn new_empty() -> Self {
-            Self {
-                factory: ElementFactory::new("Message", None::<String>),
-            }
+
+        /// Create a document that speaks `say` and nothing else
+        #[cfg(feature = "voice")]
+        pub fn say_only(say: impl Into<Say>) -> Self {
+            Self::new().say(say)
         }
-        
-        /// Create a new Message element with direct text content
-        pub fn new_with_text(text: impl Into<String>) -> Self {
-            Self {
-                factory: ElementFactory::new("Message", Some(text.into())),
+
+        /// Create a document that replies with a single message
+        #[cfg(feature = "messaging")]
+        pub fn message_only(body: impl Into<String>) -> Self {
+            Self::new().message(Message::new_with_text(body))
+        }
+
+        /// Create a document that rejects the incoming call
+        #[cfg(feature = "voice")]
+        pub fn reject(reason: RejectReason) -> Self {
+            let mut response = Self::new();
+            response.factory.children.push(Box::new(Reject::new().reason(reason)));
+            response
+        }
+
+        /// Create a document that ends the call immediately
+        #[cfg(feature = "voice")]
+        pub fn hangup_only() -> Self {
+            Self::new().hangup()
+        }
+
+        /// Add a Say child element from text or a configured Say
+        #[cfg(feature = "voice")]
+        pub fn say(mut self, say: impl Into<Say>) -> Self {
+            self.factory.children.push(Box::new(say.into()));
+            self
+        }
+
+        /// Add Say child elements, splitting text longer than [`MAX_SAY_CHARACTERS`]
+        #[cfg(feature = "voice")]
+        pub fn say_split(mut self, say: impl Into<Say>) -> Self {
+            for say in say.into().split() {
+                self.factory.children.push(Box::new(say));
             }
+            self
         }
-        
-        /// Set to attribute
-        pub fn to(mut self, to: impl Into<String>) -> Self {
-            self.factory.attributes.push(("to".to_string(), to.into()));
+
+        /// Add a Gather child element
+        #[cfg(feature = "voice")]
+        pub fn gather(mut self, gather: Gather) -> Self {
+            self.factory.children.push(Box::new(gather));
             self
         }
-        
-        /// Set from attribute
-        pub fn from(mut self, from: impl Into<String>) -> Self {
-            self.factory.attributes.push(("from".to_string(), from.into()));
+
+        /// Add a Redirect child element from a URL or a configured Redirect
+        pub fn redirect(mut self, redirect: impl Into<Redirect>) -> Self {
+            self.factory.children.push(Box::new(redirect.into()));
             self
         }
         
-        /// Set action attribute
-        pub fn action(mut self, action: impl Into<String>) -> Self {
-            self.factory.attributes.push(("action".to_string(), action.into()));
+        /// Add a Play child element from text or a configured Play
+        #[cfg(feature = "voice")]
+        pub fn play(mut self, play: impl Into<Play>) -> Self {
+            self.factory.children.push(Box::new(play.into()));
             self
         }
         
-        /// Set method attribute
-        pub fn method(mut self, method: impl Into<String>) -> Self {
-            self.factory.attributes.push(("method".to_string(), method.into()));
+        /// Add a Pause child element
+        #[cfg(feature = "voice")]
+        pub fn pause(mut self, pause: Pause) -> Self {
+            self.factory.children.push(Box::new(pause));
             self
         }
         
-        /// Set statusCallback attribute
-        pub fn status_callback(mut self, url: impl Into<String>) -> Self {
-            self.factory.attributes.push(("statusCallback".to_string(), url.into()));
+        /// Add a Hangup child element
+        #[cfg(feature = "voice")]
+        pub fn hangup(mut self) -> Self {
+            self.factory.children.push(Box::new(Hangup::new()));
             self
         }
         
-        /// Add a Body child element
-        pub fn body(mut self, body: Body) -> Self {
-            self.factory.children.push(Box::new(body));
+        /// Add a Record child element
+        #[cfg(feature = "voice")]
+        pub fn record(mut self, record: Record) -> Self {
+            self.factory.children.push(Box::new(record));
             self
         }
-    }
-    
-    impl TwiMLElement for Message {
-        fn to_xml(&self) -> XMLElement {
-            self.factory.to_xml()
+        
+        /// Add a Dial child element
+        #[cfg(feature = "voice")]
+        pub fn dial(mut self, dial: Dial) -> Self {
+            self.factory.children.push(Box::new(dial));
+            self
         }
-    }
-    
-    /// Enqueue TwiML Element to add call to a queue
-    #[derive(Debug)]
-    pub struct Enqueue {
-        factory: ElementFactory,
-    }
-    
-    impl Enqueue {
-        /// Create a new Enqueue element
-        pub fn new(queue_name: impl Into<String>) -> Self {
-            Self {
-                factory: ElementFactory::new("Enqueue", Some(queue_name)),
-            }
+        
+        /// Add an Sms child element
+        #[cfg(feature = "voice")]
+        pub fn sms(mut self, sms: Sms) -> Self {
+            self.factory.children.push(Box::new(sms));
+            self
         }
         
-        /// Set action attribute
-        pub fn action(mut self, action: impl Into<String>) -> Self {
-            self.factory.attributes.push(("action".to_string(), action.into()));
+        /// Add a Message child element
+        #[cfg(feature = "messaging")]
+        pub fn message(mut self, message: Message) -> Self {
+            self.factory.children.push(Box::new(message));
             self
         }
         
-        /// Set method attribute
-        pub fn method(mut self, method: impl Into<String>) -> Self {
-            self.factory.attributes.push(("method".to_string(), method.into()));
+        /// Add an Enqueue child element
+        #[cfg(feature = "voice")]
+        pub fn enqueue(mut self, enqueue: Enqueue) -> Self {
+            self.factory.children.push(Box::new(enqueue));
             self
         }
         
-        /// Set waitUrl attribute
-        pub fn wait_url(mut self, url: impl Into<String>) -> Self {
-            self.factory.attributes.push(("waitUrl".to_string(), url.into()));
+        /// Add a Leave child element
+        #[cfg(feature = "voice")]
+        pub fn leave(mut self) -> Self {
+            self.factory.children.push(Box::new(Leave::new()));
             self
         }
         
-        /// Set waitUrlMethod attribute
-        pub fn wait_url_method(mut self, method: impl Into<String>) -> Self {
-            self.factory.attributes.push(("waitUrlMethod".to_string(), method.into()));
+        /// Add a Pay child element
+        #[cfg(feature = "pay")]
+        pub fn pay(mut self, pay: Pay) -> Self {
+            self.factory.children.push(Box::new(pay));
             self
         }
-    }
-    
-    impl TwiMLElement for Enqueue {
-        fn to_xml(&self) -> XMLElement {
-            self.factory.to_xml()
+
+        /// Add a Receive child element to accept a fax
+        #[cfg(feature = "fax")]
+        pub fn receive(mut self, receive: Receive) -> Self {
+            self.factory.children.push(Box::new(receive));
+            self
+        }
+
+        /// Add a Connect child element
+        #[cfg(feature = "voice")]
+        pub fn connect(mut self, connect: Connect) -> Self {
+            self.factory.children.push(Box::new(connect));
+            self
+        }
+
+        /// Append the verbs of a fragment
+        pub fn fragment(mut self, fragment: Fragment) -> Self {
+            self.factory.children.extend(fragment.children);
+            self
+        }
+
+        /// Add a prebuilt shared sub-tree without copying it
+        pub fn shared(mut self, shared: &Shared) -> Self {
+            self.factory.children.push(Box::new(shared.clone()));
+            self
+        }
+
+        /// Add a Denoise child element (SignalWire extension, see [`crate::Dialect::SignalWire`])
+        #[cfg(feature = "voice")]
+        pub fn denoise(mut self) -> Self {
+            self.factory.children.push(Box::new(Denoise::new()));
+            self
+        }
+
+        /// Get the child element at an index
+        pub fn child(&self, index: usize) -> Option<&dyn TwiMLElement> {
+            self.factory.child(index)
+        }
+
+        /// Remove and return the child element at an index
+        pub fn remove_child(&mut self, index: usize) -> Option<Box<dyn TwiMLElement>> {
+            self.factory.remove_child(index)
+        }
+
+        /// Replace the child element at an index, returning the previous child
+        pub fn replace_child(&mut self, index: usize, child: impl TwiMLElement + 'static) -> Option<Box<dyn TwiMLElement>> {
+            self.factory.replace_child(index, Box::new(child))
+        }
+
+        /// Insert a child element at an index, shifting later children
+        ///
+        /// # Panics
+        ///
+        /// Panics if `index` is greater than the number of children.
+        pub fn insert_child_at(&mut self, index: usize, child: impl TwiMLElement + 'static) {
+            self.factory.insert_child_at(index, Box::new(child));
+        }
+
+        /// Keep only the child elements for which the predicate returns true
+        pub fn retain_children(&mut self, keep: impl FnMut(&dyn TwiMLElement) -> bool) {
+            self.factory.retain_children(keep);
+        }
+
+        /// Serialize the document for logs, masking phone numbers, sendDigits, SIP users
+        /// and credentials, Client identities, payment connectors and Pay parameters
+        ///
+        /// The output is for observability only: masked values make it unusable as a reply.
+        pub fn to_redacted_string(&self) -> String {
+            self.factory.redacted_tree(false).to_xml_string()
+        }
+
+        /// Hash the document independently of attribute order and whitespace, for cache keys,
+        /// deduplication and change detection; see [`ElementFactory::content_hash`]
+        pub fn content_hash(&self) -> u64 {
+            self.factory.content_hash()
+        }
+
+        /// Add direct text to the response
+        pub fn text(mut self, text: impl Into<String>) -> Self {
+            self.factory.text = Some(Cow::Owned(text.into()));
+            self
         }
     }
-    
-    /// Leave TwiML Element to exit a queue
-    #[derive(Debug)]
-    pub struct Leave {
-        factory: ElementFactory,
-    }
-    
-    impl Leave {
-        /// Create a new Leave element
-        pub fn new() -> Self {
-            Self {
-                factory: ElementFactory::new("Leave", None::<String>),
-            }
+
+    impl Default for Response {
+        fn default() -> Self {
+            Self::new()
         }
     }
-    
-    impl TwiMLElement for Leave {
-        fn to_xml(&self) -> XMLElement {
-            self.factory.to_xml()
+
+    impl TwiMLElement for Response {
+        fn factory(&self) -> &ElementFactory {
+            &self.factory
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            &mut self.factory
         }
     }
 
-    /// Response TwiML Element - the root element
-    #[derive(Debug)]
-    pub struct Response {
-        factory: ElementFactory,
+    /// Ordered list of verbs built apart from any document and appended later
+    ///
+    /// Lets a piece of a flow, such as a compliance notice or a language menu, be built and
+    /// tested on its own and then added to a [`Response`] or [`Gather`]. Converting a
+    /// fragment into a `Response` gives a complete document to validate or snapshot.
+    #[derive(Debug, Default)]
+    pub struct Fragment {
+        children: Vec<Box<dyn TwiMLElement>>,
     }
 
-    impl Response {
-        /// Create a new Response element
+    impl Fragment {
+        /// Create an empty fragment
         pub fn new() -> Self {
-            Self {
-                factory: ElementFactory::new("Response", None::<String>),
-            }
+            Self::default()
         }
 
-        /// Add a Say child element
-        pub fn say(mut self, say: Say) -> Self {
-            self.factory.children.push(Box::new(say));
+        /// Append any element
+        pub fn verb(mut self, element: impl TwiMLElement) -> Self {
+            self.children.push(Box::new(element));
             self
         }
 
-        /// Add a Gather child element
-        pub fn gather(mut self, gather: Gather) -> Self {
-            self.factory.children.push(Box::new(gather));
-            self
+        /// Append a Say element from text or a configured Say
+        #[cfg(feature = "voice")]
+        pub fn say(self, say: impl Into<Say>) -> Self {
+            self.verb(say.into())
         }
 
-        /// Add a Redirect child element
-        pub fn redirect(mut self, redirect: Redirect) -> Self {
-            self.factory.children.push(Box::new(redirect));
-            self
+        /// Append a Play element from a URL or a configured Play
+        #[cfg(feature = "voice")]
+        pub fn play(self, play: impl Into<Play>) -> Self {
+            self.verb(play.into())
         }
-        
-        /// Add a Play child element
-        pub fn play(mut self, play: Play) -> Self {
-            self.factory.children.push(Box::new(play));
-            self
+
+        /// Append a Pause element
+        #[cfg(feature = "voice")]
+        pub fn pause(self, pause: Pause) -> Self {
+            self.verb(pause)
         }
-        
-        /// Add a Pause child element
-        pub fn pause(mut self, pause: Pause) -> Self {
-            self.factory.children.push(Box::new(pause));
-            self
+
+        /// Append a Gather element
+        #[cfg(feature = "voice")]
+        pub fn gather(self, gather: Gather) -> Self {
+            self.verb(gather)
+        }
+
+        /// Append a Hangup element
+        #[cfg(feature = "voice")]
+        pub fn hangup(self) -> Self {
+            self.verb(Hangup::new())
+        }
+
+        /// Append a Message element
+        #[cfg(feature = "messaging")]
+        pub fn message(self, message: Message) -> Self {
+            self.verb(message)
+        }
+
+        /// Append a Redirect element from a URL or a configured Redirect
+        pub fn redirect(self, redirect: impl Into<Redirect>) -> Self {
+            self.verb(redirect.into())
         }
-        
-        /// Add a Hangup child element
-        pub fn hangup(mut self) -> Self {
-            self.factory.children.push(Box::new(Hangup::new()));
+
+        /// Append the verbs of another fragment
+        pub fn extend(mut self, other: Fragment) -> Self {
+            self.children.extend(other.children);
             self
         }
-        
-        /// Add a Record child element
-        pub fn record(mut self, record: Record) -> Self {
-            self.factory.children.push(Box::new(record));
-            self
+
+        /// Get the verbs in order
+        pub fn children(&self) -> &[Box<dyn TwiMLElement>] {
+            &self.children
         }
-        
-        /// Add a Dial child element
-        pub fn dial(mut self, dial: Dial) -> Self {
-            self.factory.children.push(Box::new(dial));
-            self
+
+        /// Get the number of verbs
+        pub fn len(&self) -> usize {
+            self.children.len()
         }
-        
-        /// Add an Sms child element
-        pub fn sms(mut self, sms: Sms) -> Self {
-            self.factory.children.push(Box::new(sms));
-            self
+
+        /// Whether the fragment has no verbs
+        pub fn is_empty(&self) -> bool {
+            self.children.is_empty()
         }
-        
-        /// Add a Message child element
-        pub fn message(mut self, message: Message) -> Self {
-            self.factory.children.push(Box::new(message));
-            self
+    }
+
+    impl From<Fragment> for Response {
+        fn from(fragment: Fragment) -> Self {
+            Response::new().fragment(fragment)
         }
-        
-        /// Add an Enqueue child element
-        pub fn enqueue(mut self, enqueue: Enqueue) -> Self {
-            self.factory.children.push(Box::new(enqueue));
-            self
+    }
+
+    /// Add `update`, which runs consuming builder methods through a mutable reference
+    macro_rules! update_in_place {
+        ($($(#[$cfg:meta])* $element:ident,)+) => {
+            $(
+                $(#[$cfg])*
+                impl $element {
+                    /// Apply builder methods to an element held by mutable reference, for
+                    /// building in loops and branches without rebinding
+                    ///
+                    /// If `build` panics the element is left empty.
+                    pub fn update(&mut self, build: impl FnOnce(Self) -> Self) -> &mut Self {
+                        let factory = std::mem::replace(&mut self.factory, ElementFactory::new("", None::<String>));
+                        *self = build(Self { factory });
+                        self
+                    }
+                }
+            )+
+        };
+    }
+
+    update_in_place! {
+        Response,
+        Redirect,
+        #[cfg(feature = "voice")] Say,
+        #[cfg(feature = "voice")] Gather,
+        #[cfg(feature = "voice")] Play,
+        #[cfg(feature = "voice")] Pause,
+        #[cfg(feature = "voice")] Hangup,
+        #[cfg(feature = "voice")] Reject,
+        #[cfg(feature = "voice")] Record,
+        #[cfg(feature = "voice")] Dial,
+        #[cfg(feature = "voice")] Number,
+        #[cfg(feature = "voice")] Client,
+        #[cfg(feature = "voice")] Application,
+        #[cfg(feature = "voice")] Conference,
+        #[cfg(feature = "voice")] Sip,
+        #[cfg(feature = "voice")] Sms,
+        #[cfg(feature = "voice")] Enqueue,
+        #[cfg(feature = "voice")] Task,
+        #[cfg(feature = "voice")] Leave,
+        #[cfg(feature = "voice")] Denoise,
+        #[cfg(feature = "voice")] Connect,
+        #[cfg(feature = "voice")] ConversationRelay,
+        #[cfg(feature = "voice")] Language,
+        #[cfg(feature = "voice")] VirtualAgent,
+        #[cfg(feature = "voice")] Config,
+        #[cfg(feature = "voice")] Parameter,
+        #[cfg(feature = "voice")] Stream,
+        #[cfg(feature = "voice")] Room,
+        #[cfg(feature = "voice")] Conversation,
+        #[cfg(feature = "pay")] Prompt,
+        #[cfg(feature = "pay")] Pay,
+        #[cfg(feature = "messaging")] Message,
+        #[cfg(feature = "messaging")] Body,
+        #[cfg(feature = "messaging")] Media,
+        #[cfg(feature = "fax")] Receive,
+    }
+
+    /// Prebuilt sub-tree, such as a legal disclaimer or a standard menu, shared between documents
+    ///
+    /// Cloning a `Shared` or appending it with [`Response::shared`] only bumps a reference
+    /// count, so a fragment built once can be added to every response. Mutating it through
+    /// [`TwiMLElement::factory_mut`], as [`ElementFactory::sanitize`] does, copies the
+    /// sub-tree first so other documents are unaffected.
+    #[derive(Debug, Clone)]
+    pub struct Shared(Arc<dyn TwiMLElement>);
+
+    impl Shared {
+        /// Share an element and its children
+        pub fn new(element: impl TwiMLElement) -> Self {
+            Self(Arc::new(element))
         }
-        
-        /// Add a Leave child element
-        pub fn leave(mut self) -> Self {
-            self.factory.children.push(Box::new(Leave::new()));
-            self
+
+        /// Get the shared element
+        pub fn inner(&self) -> &dyn TwiMLElement {
+            self.0.as_ref()
         }
-        
-        /// Add direct text to the response
-        pub fn text(mut self, text: impl Into<String>) -> Self {
-            self.factory.text = Some(text.into());
-            self
+    }
+
+    impl<T: TwiMLElement> From<Arc<T>> for Shared {
+        fn from(element: Arc<T>) -> Self {
+            Self(element)
         }
     }
 
-    impl TwiMLElement for Response {
+    impl From<Arc<dyn TwiMLElement>> for Shared {
+        fn from(element: Arc<dyn TwiMLElement>) -> Self {
+            Self(element)
+        }
+    }
+
+    impl TwiMLElement for Shared {
+        fn factory(&self) -> &ElementFactory {
+            self.0.factory()
+        }
+
+        fn factory_mut(&mut self) -> &mut ElementFactory {
+            if Arc::get_mut(&mut self.0).is_none() {
+                self.0 = Arc::new(self.0.factory().copy_tree());
+            }
+            Arc::get_mut(&mut self.0).expect("copied sub-tree is not shared").factory_mut()
+        }
+
         fn to_xml(&self) -> XMLElement {
-            self.factory.to_xml()
+            self.0.to_xml()
         }
     }
 }
@@ -1098,7 +5075,7 @@ mod elements {
 pub use elements::*;
 
 // Example usage and test
-#[cfg(test)]
+#[cfg(all(test, feature = "messaging", feature = "pay"))]
 mod tests {
     use super::*;
 
@@ -1120,7 +5097,7 @@ mod tests {
             .say(Say::new("Welcome to our service").voice("alice").language("en-US"))
             .gather(
                 Gather::new()
-                    .num_digits("1")
+                    .num_digits(1)
                     .timeout(5)
                     .action("/process-selection")
                     .method("POST")
@@ -1128,7 +5105,7 @@ mod tests {
                     .say(Say::new("Press 1 for sales, press 2 for support"))
             )
             .dial(
-                Dial::new(None::<String>)
+                Dial::new_empty()
                     .timeout(10)
                     .action("/handle-dial")
                     .number(Number::new("+1234567890"))
@@ -1145,7 +5122,7 @@ mod tests {
         let response = Response::new()
             .say(Say::new("You are about to join the conference"))
             .dial(
-                Dial::new(None::<String>)
+                Dial::new_empty()
                     .conference(
                         Conference::new("MyRoom")
                             .muted(false)
@@ -1164,6 +5141,119 @@ mod tests {
         assert!(xml_string.contains("beep=\"true\""));
     }
     
+    #[test]
+    fn test_conference_beep_values() {
+        let xml_string = Response::new()
+            .dial(Dial::new_empty().conference(Conference::new("Room").beep(Beep::OnExit)))
+            .dial(Dial::new_empty().conference(Conference::new("Quiet").beep(false)))
+            .to_xml_string();
+
+        assert!(xml_string.contains("<Conference beep=\"onExit\">Room</Conference>"));
+        assert!(xml_string.contains("<Conference beep=\"false\">Quiet</Conference>"));
+    }
+
+    #[test]
+    fn test_status_callback_event_set() {
+        let events = EventSet::COMPLETED | EventSet::INITIATED | EventSet::RINGING;
+        assert!(events.contains(EventSet::RINGING));
+        assert!(!events.contains(EventSet::ANSWERED));
+
+        let xml_string = Response::new()
+            .dial(
+                Dial::new_empty()
+                    .number(Number::new("+15551234567").status_callback("/status").status_callback_event(events))
+                    .conference(
+                        Conference::new("Room").status_callback_event(EventSet::new().with(EventSet::JOIN).with(EventSet::LEAVE)),
+                    ),
+            )
+            .to_xml_string();
+
+        assert!(xml_string.contains("statusCallbackEvent=\"initiated ringing completed\""));
+        assert!(xml_string.contains("statusCallbackEvent=\"join leave\""));
+    }
+
+    #[test]
+    fn test_currency_normalization() {
+        assert_eq!(Currency::parse("usd ").unwrap(), Currency::usd());
+        assert_eq!("Eur".parse::<Currency>().unwrap().as_str(), "eur");
+        assert!(Currency::parse("US$").is_err());
+        assert!(Currency::parse("XYZ").is_err());
+        let unsupported = Currency::parse("kpw").unwrap_err();
+        assert_eq!(unsupported.reason, "`KPW` is not a currency Twilio Pay supports");
+
+        assert!(ISO_4217_CODES.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(PAY_CURRENCIES.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(PAY_CURRENCIES.iter().all(|code| ISO_4217_CODES.contains(code)));
+
+        let xml_string = Response::new()
+            .pay(Pay::new().charge_amount("5.00").currency(Currency::parse(" GBP").unwrap()))
+            .to_xml_string();
+        assert!(xml_string.contains("currency=\"gbp\""));
+    }
+
+    #[test]
+    fn test_loop_values() {
+        assert_eq!(Loop::from(0), Loop::Forever);
+        assert!(Say::new("Hi").loop_times(0u32).to_xml_string().ends_with(r#"<Say loop="0">Hi</Say>"#));
+        assert_eq!(Loop::from(2), Loop::Times(NonZeroU32::new(2).unwrap()));
+
+        let xml_string = Response::new()
+            .say(Say::new("Hold please").loop_times(Loop::Forever))
+            .play(Play::new("https://example.com/tone.mp3").loop_times(2))
+            .to_xml_string();
+        assert!(xml_string.contains("<Say loop=\"0\">Hold please</Say>"));
+        assert!(xml_string.contains("<Play loop=\"2\">"));
+    }
+
+    #[test]
+    fn test_setters_replace_attributes() {
+        let say = Say::new("Hi").loop_times(2).voice("alice").loop_times(3);
+        assert!(say.to_xml_string().ends_with(r#"<Say loop="3" voice="alice">Hi</Say>"#));
+        let dial = Dial::new_with_number("+15551234567").action("/a").method("GET").action("/b").method("POST");
+        assert!(dial.to_xml_string().contains(r#"<Dial action="/b" method="POST">"#));
+        assert_eq!(render_small(dial.factory()).unwrap(), dial.to_xml_string());
+    }
+
+    #[test]
+    fn test_say_from_parts() {
+        let name = "Alice";
+        let say = Say::from_parts(["Hello, ", name, ". "]).unwrap().push_text("Press 1 for sales.").voice("alice");
+
+        assert_eq!(
+            Response::new().say(say).to_xml_string(),
+            Response::new()
+                .say(Say::new("Hello, Alice. Press 1 for sales.").voice("alice"))
+                .to_xml_string()
+        );
+
+        let say = Say::new("Hi & welcome, ")
+            .push_part(SayPart::Tag {
+                name: "prosody".to_string(),
+                attributes: vec![("rate".to_string(), "slow".to_string())],
+                text: Some("Alice".to_string()),
+            })
+            .and_then(|say| say.push_part(SayPart::Break("1s".to_string())))
+            .unwrap()
+            .push_text("<1 new message>");
+        let expected = concat!(
+            r#"<Say>Hi &amp; welcome, <prosody rate="slow">Alice</prosody><break time="1s" />"#,
+            r#"&lt;1 new message&gt;</Say>"#,
+        );
+        assert!(say.to_xml_string().ends_with(expected));
+        assert_eq!(render_small(say.factory()).unwrap(), say.to_xml_string());
+        assert_eq!(say.factory().plain_text().as_deref(), Some("Hi & welcome, Alice<1 new message>"));
+        assert_eq!(say.split_with_limit(10).len(), 1);
+
+        let injected = SayPart::Tag { name: "break/><Hangup".to_string(), attributes: Vec::new(), text: None };
+        assert!(Say::new("Hi").push_part(injected).unwrap_err().reason.contains("not an SSML tag"));
+        let injected = SayPart::Tag {
+            name: "prosody".to_string(),
+            attributes: vec![("rate=\"x\"><Hangup/><x y".to_string(), "slow".to_string())],
+            text: None,
+        };
+        assert!(Say::new("Hi").push_part(injected).is_err());
+    }
+
     #[test]
     fn test_recording() {
         let response = Response::new()
@@ -1184,6 +5274,559 @@ mod tests {
         assert!(xml_string.contains("playBeep=\"true\""));
     }
     
+    #[test]
+    fn test_redirect_from_url_or_element() {
+        let response = Response::new()
+            .redirect("/next-step")
+            .redirect(Redirect::new("/status").method("GET"));
+
+        let xml_string = response.to_xml_string();
+        assert!(xml_string.contains("<Redirect>/next-step</Redirect>"));
+        assert!(xml_string.contains("<Redirect method=\"GET\">/status</Redirect>"));
+    }
+
+    #[test]
+    fn test_child_mutation() {
+        let mut response = Response::new()
+            .say(Say::new("This call may be recorded"))
+            .record(Record::new())
+            .gather(Gather::new().say(Say::new("Press 1")));
+
+        response.retain_children(|child| child.factory().name() != "Record");
+        assert_eq!(response.child(1).unwrap().factory().name(), "Gather");
+
+        let replaced = response.replace_child(0, Say::new("Welcome")).unwrap();
+        assert_eq!(replaced.factory().text(), Some("This call may be recorded"));
+
+        response.insert_child_at(1, Pause::new().length(1));
+        let removed = response.remove_child(2).unwrap();
+        assert_eq!(removed.factory().name(), "Gather");
+        assert!(response.remove_child(5).is_none());
+
+        // Nested children can be edited through the parent's node
+        let mut response = response.gather(Gather::new().say(Say::new("a")).pause(Pause::new()));
+        let gather = response.factory_mut().child_mut(2).unwrap();
+        gather.factory_mut().remove_child(0);
+
+        assert!(response.to_xml_string().contains(
+            "<Response><Say>Welcome</Say><Pause length=\"1\" /><Gather><Pause /></Gather></Response>"
+        ));
+    }
+
+    #[test]
+    fn test_default_elements() {
+        fn build<T: Default + TwiMLElement>() -> String {
+            T::default().to_xml_string()
+        }
+
+        assert!(build::<Response>().ends_with("<Response />"));
+        assert!(build::<Gather>().ends_with("<Gather />"));
+        assert!(build::<Record>().ends_with("<Record />"));
+        assert!(build::<Pause>().ends_with("<Pause />"));
+        assert!(build::<Pay>().ends_with("<Pay />"));
+    }
+
+    #[test]
+    fn test_terse_construction() {
+        let response = Response::new()
+            .say("Hello")
+            .play("https://example.com/tone.mp3")
+            .gather(Gather::new().say(String::from("Press 1")))
+            .message(Message::new_empty().body("Thanks"));
+
+        let xml_string = response.to_xml_string();
+        assert!(xml_string.contains("<Say>Hello</Say><Play>https://example.com/tone.mp3</Play>"));
+        assert!(xml_string.contains("<Gather><Say>Press 1</Say></Gather>"));
+        assert!(xml_string.contains("<Body>Thanks</Body>"));
+    }
+
+    #[test]
+    fn test_debug_redacts_credentials() {
+        let dial = Dial::new_empty().sip(Sip::new("sip:alice@example.com").username("alice").password("hunter2"));
+        let pay = Pay::new().payment_connector("Stripe_Live");
+
+        let debug = format!("{:?} {:?} {:?}", dial, pay, PaymentConnector::new("Stripe_Live"));
+        assert!(!debug.contains("hunter2"));
+        assert!(!debug.contains("Stripe_Live"));
+        assert!(debug.contains("(\"username\", \"alice\")"));
+        assert!(debug.contains("(\"password\", \"[redacted]\")"));
+        // Serialization is unaffected
+        assert!(dial.to_xml_string().contains("password=\"hunter2\""));
+    }
+
+    #[test]
+    fn test_conference_recording_attributes() {
+        let conference = Conference::new("Room")
+            .record("record-from-start")
+            .trim("trim-silence")
+            .recording_status_callback("/recordings")
+            .recording_status_callback_event(EventSet::IN_PROGRESS | EventSet::COMPLETED | EventSet::ABSENT)
+            .wait_url("/hold")
+            .jitter_buffer_size("small");
+
+        let xml_string = conference.to_xml_string();
+        assert!(xml_string.contains("recordingStatusCallbackEvent=\"completed in-progress absent\""));
+        assert!(xml_string.contains("waitUrl=\"/hold\" jitterBufferSize=\"small\""));
+    }
+
+    #[test]
+    fn test_conversation_relay() {
+        let response = Response::new().connect(
+            Connect::new().action("/relay-done").conversation_relay(
+                ConversationRelay::new("wss://bot.example.com/relay")
+                    .welcome_greeting("Hi!")
+                    .language("en-US")
+                    .language_config(Language::new("fr-FR").tts_provider("ElevenLabs").voice("Jeanne"))
+                    .parameter(Parameter::new("customer", "42")),
+            ),
+        );
+
+        assert!(response.to_xml_string().contains(concat!(
+            r#"<Connect action="/relay-done"><ConversationRelay url="wss://bot.example.com/relay" welcomeGreeting="Hi!" language="en-US">"#,
+            r#"<Language code="fr-FR" ttsProvider="ElevenLabs" voice="Jeanne" />"#,
+            r#"<Parameter name="customer" value="42" /></ConversationRelay></Connect>"#,
+        )));
+    }
+
+    #[test]
+    fn test_virtual_agent_config() {
+        let connect = Connect::new().virtual_agent(
+            VirtualAgent::new("support-bot")
+                .language("es-ES")
+                .config(Config::new("voiceName", "es-ES-Standard-A"))
+                .parameter(Parameter::new("order", "1234")),
+        );
+
+        assert!(connect.to_xml_string().ends_with(concat!(
+            r#"<Connect><VirtualAgent connectorName="support-bot" language="es-ES">"#,
+            r#"<Config name="voiceName" value="es-ES-Standard-A" /><Parameter name="order" value="1234" />"#,
+            r#"</VirtualAgent></Connect>"#,
+        )));
+    }
+
+    #[test]
+    fn test_connect_nouns() {
+        let stream = Connect::new().stream(
+            Stream::new("wss://media.example.com/audio").name("agent-assist").parameter(Parameter::new("caller", "42")),
+        );
+        assert!(stream.to_xml_string().ends_with(concat!(
+            r#"<Connect><Stream url="wss://media.example.com/audio" name="agent-assist">"#,
+            r#"<Parameter name="caller" value="42" /></Stream></Connect>"#,
+        )));
+
+        let room = Response::new().connect(Connect::new().room(Room::new("DailyStandup").participant_identity("alice")));
+        assert!(room.to_xml_string().ends_with(
+            r#"<Connect><Room participantIdentity="alice">DailyStandup</Room></Connect></Response>"#
+        ));
+        assert!(room.validate().is_ok());
+
+        let custom = ElementFactory::new("Assistant", None::<String>);
+        let connect = Connect::new().conversation(Conversation::new("IS123").inbound_timeout(60)).append_noun(custom);
+        let error = Response::new().connect(connect).validate().unwrap_err();
+        assert_eq!(error.reason, "must contain exactly one noun, found 2");
+        assert!(Response::new().connect(Connect::new()).validate().is_err());
+    }
+
+    #[test]
+    fn test_text_is_escaped() {
+        let xml_string = Response::new().say("Fish & chips <cheap>").to_xml_string();
+        assert!(xml_string.contains("<Say>Fish &amp; chips &lt;cheap&gt;</Say>"));
+    }
+
+    #[test]
+    fn test_split_long_say() {
+        let text = "One two three. Four five six seven eight nine! Supercalifragilistic";
+        let says = Say::new(text).voice("Polly.Joanna").split_with_limit(30);
+        let xml: Vec<String> = says.iter().map(|say| render_fragment(&say.to_xml())).collect();
+        assert_eq!(
+            xml,
+            vec![
+                r#"<Say voice="Polly.Joanna">One two three.</Say>"#,
+                r#"<Say voice="Polly.Joanna">Four five six seven eight</Say>"#,
+                r#"<Say voice="Polly.Joanna">nine! Supercalifragilistic</Say>"#,
+            ]
+        );
+
+        let long = "This is a sentence. ".repeat(300);
+        let response = Response::new().say_split(long.as_str());
+        assert_eq!(response.iter().len(), 2);
+        assert!(response.validate().is_ok());
+        assert!(Response::new().say(long.as_str()).validate().is_err());
+        assert_eq!(Say::new("Short").split().len(), 1);
+
+        let looping = Say::new("One two three. Four five six.").loop_times(3u32).split_with_limit(16);
+        assert_eq!(looping.len(), 1);
+        assert_eq!(Say::new("One two three. Four five six.").loop_times(Loop::Forever).split_with_limit(16).len(), 1);
+        assert_eq!(Say::new("One two three. Four five six.").loop_times(1u32).split_with_limit(16).len(), 2);
+    }
+
+    #[test]
+    fn test_digits() {
+        let digits = Digits::new().pause(2).digit('1').and_then(|d| d.digit('2')).and_then(|d| d.digit('#')).unwrap();
+        assert_eq!(digits.as_str(), "ww12#");
+        let dial = Dial::new_empty().number(Number::new("+15551234567").send_digits(digits));
+        assert!(dial.to_xml_string().contains(r#"<Number sendDigits="ww12#">+15551234567</Number>"#));
+
+        assert_eq!("w*9".parse::<Digits>().unwrap().to_string(), "w*9");
+        let error = Digits::parse("Play", "12p3").unwrap_err();
+        assert_eq!((error.element.as_str(), error.attribute.as_deref()), ("Play", Some("digits")));
+        assert!(error.reason.contains("`p`"));
+        assert_eq!(Digits::parse("Number", "1x").unwrap_err().attribute.as_deref(), Some("sendDigits"));
+        assert_eq!(Digits::new().digit('a').unwrap_err().element, "Digits");
+        assert!(Digits::new().digit('w').is_err());
+    }
+
+    #[test]
+    fn test_gather_hints_list() {
+        let gather = Gather::new().hints_list([" billing ", "", "technical support", "sales"]);
+        assert!(gather.to_xml_string().ends_with(r#"<Gather hints="billing,technical support,sales" />"#));
+        assert!(Response::new().gather(gather).validate().is_ok());
+
+        let too_many = Gather::new().hints_list((0..=crate::MAX_HINT_PHRASES).map(|i| i.to_string()));
+        assert!(Response::new().gather(too_many).validate().unwrap_err().reason.contains("501 phrases"));
+        let too_long = Gather::new().hints_list(["a".repeat(crate::MAX_HINT_CHARACTERS + 1)]);
+        let error = Response::new().gather(too_long).validate().unwrap_err();
+        assert_eq!(error.attribute.as_deref(), Some("hints"));
+    }
+
+    #[test]
+    fn test_duration_attributes() {
+        use std::time::Duration;
+
+        let gather = Gather::new()
+            .timeout(Duration::from_millis(2500))
+            .speech_timeout(Duration::from_secs(3))
+            .interdigit_timeout(2);
+        assert!(gather.to_xml_string().ends_with(r#"<Gather timeout="3" speechTimeout="3" interdigitTimeout="2" />"#));
+        assert!(Gather::new().speech_timeout("auto").to_xml_string().contains(r#"speechTimeout="auto""#));
+
+        assert_eq!(Seconds::from(Duration::from_millis(1499)).get(), 1);
+        assert_eq!(Seconds::from(Duration::from_millis(100)).get(), 1);
+        assert_eq!(Seconds::from(Duration::ZERO).get(), 0);
+        let count: u32 = 4;
+        assert!(Gather::new().num_digits(count).timeout(Seconds::new(count)).speech_timeout(SpeechTimeout::seconds(count))
+            .to_xml_string().ends_with(r#"<Gather numDigits="4" timeout="4" speechTimeout="4" />"#));
+        assert!(Pause::new().length(Duration::from_secs(2)).to_xml_string().ends_with(r#"<Pause length="2" />"#));
+        assert!(Record::new().max_length(Duration::from_secs(90)).to_xml_string().contains(r#"maxLength="90""#));
+
+        let dial = Dial::new_with_number("+15551234567").timeout(Duration::from_secs(30)).time_limit(Duration::from_secs(3600));
+        assert!(Response::new().dial(dial).validate().is_ok());
+        let error = Response::new().dial(Dial::new_with_number("+15551234567").timeout(2)).validate().unwrap_err();
+        assert_eq!(error.attribute.as_deref(), Some("timeout"));
+        let error = Response::new()
+            .dial(Dial::new_with_number("+15551234567").time_limit(crate::MAX_DIAL_TIME_LIMIT as usize + 1))
+            .validate()
+            .unwrap_err();
+        assert_eq!(error.attribute.as_deref(), Some("timeLimit"));
+    }
+
+    #[cfg(feature = "url")]
+    #[test]
+    fn test_url_values() {
+        let base = url::Url::parse("https://example.com/voice/").unwrap();
+        let gather = Gather::new().action(base.join("menu?step=1").unwrap());
+        let response = Response::new()
+            .play(base.join("hold.mp3").unwrap())
+            .gather(gather)
+            .redirect(base.join("start").unwrap());
+        let xml_string = response.to_xml_string();
+        assert!(xml_string.contains("<Play>https://example.com/voice/hold.mp3</Play>"));
+        assert!(xml_string.contains(r#"<Gather action="https://example.com/voice/menu?step=1" />"#));
+        assert!(xml_string.ends_with("<Redirect>https://example.com/voice/start</Redirect></Response>"));
+
+        let message = Message::new_empty().media_urls([&base, &base.join("b.png").unwrap()]);
+        assert!(message.to_xml_string().contains("<Media>https://example.com/voice/b.png</Media>"));
+    }
+
+    #[test]
+    fn test_one_verb_documents() {
+        assert!(Response::say_only("Goodbye").to_xml_string().ends_with("<Response><Say>Goodbye</Say></Response>"));
+        assert!(Response::message_only("Thanks!").to_xml_string().ends_with(
+            "<Response><Message>Thanks!</Message></Response>"
+        ));
+        assert!(Response::reject(RejectReason::Busy).to_xml_string().ends_with(
+            r#"<Response><Reject reason="busy" /></Response>"#
+        ));
+        assert!(Response::hangup_only().to_xml_string().ends_with("<Response><Hangup /></Response>"));
+    }
+
+    #[test]
+    fn test_shared_fragments() {
+        let disclaimer = Arc::new(Say::new("This call may be recorded."));
+        let menu = Shared::new(Say::new("Press 1 for sales."));
+        let first = Response::new().shared(&disclaimer.clone().into()).gather(Gather::new().shared(&menu));
+        let second = Response::new().shared(&Shared::from(disclaimer.clone()));
+        assert_eq!(Arc::strong_count(&disclaimer), 3);
+        assert!(first.to_xml_string().ends_with(
+            "<Response><Say>This call may be recorded.</Say><Gather><Say>Press 1 for sales.</Say></Gather></Response>"
+        ));
+
+        assert!(second.to_xml_string().contains("recorded"));
+
+        let prompt = Arc::new(Say::new("Hello\u{0}"));
+        let mut copied = Shared::from(prompt.clone());
+        assert_eq!(copied.factory_mut().sanitize(SanitizePolicy::Strip), 1);
+        assert_eq!(copied.factory().text(), Some("Hello"));
+        assert_eq!(prompt.factory().text(), Some("Hello\u{0}"));
+    }
+
+    #[test]
+    fn test_fragments() {
+        let notice = || Fragment::new().say("This call may be recorded.").pause(Pause::new().length(1));
+        let standalone = Response::from(notice());
+        assert!(standalone.validate().is_ok());
+        assert!(standalone.to_xml_string().ends_with(
+            r#"<Response><Say>This call may be recorded.</Say><Pause length="1" /></Response>"#
+        ));
+
+        let menu = Fragment::new().say("Press 1 for sales.").play("https://example.com/beep.mp3");
+        let response = Response::new()
+            .fragment(notice())
+            .gather(Gather::new().num_digits(1).fragment(menu))
+            .fragment(Fragment::new().redirect("/voice"));
+        assert_eq!(response.iter().count(), 4);
+        assert_eq!(response.find("Gather").unwrap().factory().children().len(), 2);
+        assert!(Fragment::new().extend(notice()).len() == 2 && Fragment::new().is_empty());
+    }
+
+    #[test]
+    fn test_record_storage_url() {
+        let record = Record::new()
+            .storage_url("https://recordings.example.com/upload")
+            .storage_url_method("PUT");
+        assert!(record.to_xml_string().ends_with(
+            r#"<Record storageUrl="https://recordings.example.com/upload" storageUrlMethod="PUT" />"#
+        ));
+    }
+
+    #[test]
+    fn test_canonical_attribute_order() {
+        let first = Response::new().gather(Gather::new().action("/menu").num_digits(1).timeout(5));
+        let second = Response::new().gather(Gather::new().timeout(3).num_digits(1).action("/menu").timeout(5));
+        assert_ne!(first.to_xml_string(), second.to_xml_string());
+        assert_eq!(first.to_canonical_xml_string(), second.to_canonical_xml_string());
+        assert!(first.to_canonical_xml_string().contains(r#"<Gather action="/menu" numDigits="1" timeout="5" />"#));
+
+        let mut second = second;
+        second.factory_mut().canonicalize();
+        assert_eq!(second.to_xml_string(), first.to_canonical_xml_string());
+    }
+
+    #[test]
+    fn test_content_hash() {
+        let first = Response::new().say(Say::new("Hello,  world").voice("alice").language("en-US"));
+        let second = Response::new().say(Say::new(" Hello, world\n").language("en-US").voice("alice"));
+        assert_eq!(first.content_hash(), second.content_hash());
+        assert_ne!(first.content_hash(), Response::new().say("Hello, world").content_hash());
+        assert_ne!(Response::new().say("ab").content_hash(), Response::new().say("a").say("b").content_hash());
+        assert_eq!(Response::new().content_hash(), 0xb498_f9d1_3532_3c0c);
+    }
+
+    #[test]
+    fn test_small_documents_match_xml_builder() {
+        let documents = [
+            Response::new(),
+            Response::new().say(Say::new("Fish & chips <cheap> \"fresh\"").voice("it's")).hangup(),
+            Response::new().gather(Gather::new().num_digits(1).action("/menu?a=1&b=2").say("").pause(Pause::new())),
+            Response::new().enqueue(Enqueue::new_empty().workflow_sid("WW1").task(Task::new(r#"{"a":"]]>"}"#).cdata(true))),
+            Response::new().message(Message::new_empty().body(Body::new("bell \u{7} stripped")).media_url("https://e.com/x")),
+        ];
+        for document in &documents {
+            assert!(render_small(document.factory()).is_some());
+            assert_eq!(document.to_xml_string(), render_document(document.to_xml()));
+        }
+
+        let large = (0..FAST_PATH_MAX_ELEMENTS).fold(Response::new(), |response, _| response.pause(Pause::new()));
+        assert!(render_small(large.factory()).is_none());
+        assert_eq!(large.to_xml_string(), render_document(large.to_xml()));
+    }
+
+    #[test]
+    fn test_update_in_place() {
+        let options = ["sales", "support", "billing"];
+        let mut gather = Gather::new().num_digits(1);
+        for (digit, option) in options.iter().enumerate() {
+            gather.update(|gather| gather.say(format!("Press {} for {}.", digit + 1, option)));
+        }
+        let vip = true;
+        if vip {
+            gather.update(|gather| gather.timeout(10));
+        }
+
+        let mut response = Response::new();
+        response.update(|response| response.gather(gather)).update(|response| response.hangup());
+        assert!(response.to_xml_string().ends_with(concat!(
+            r#"<Response><Gather numDigits="1" timeout="10"><Say>Press 1 for sales.</Say><Say>Press 2 for support.</Say>"#,
+            r#"<Say>Press 3 for billing.</Say></Gather><Hangup /></Response>"#,
+        )));
+
+        let mut application = Application::new("AP123");
+        for (name, value) in [("tier", "gold"), ("region", "eu")] {
+            application.update(|application| application.parameter(Parameter::new(name, value)));
+        }
+        application.update(|application| application.method("GET"));
+        assert_eq!(
+            application.to_xml_string(),
+            Application::new("AP123")
+                .parameter(Parameter::new("tier", "gold"))
+                .parameter(Parameter::new("region", "eu"))
+                .method("GET")
+                .to_xml_string()
+        );
+
+        let mut body = Body::new("draft");
+        body.update(|_| Body::new("Your order shipped"));
+        assert_eq!(body.to_xml_string(), Body::new("Your order shipped").to_xml_string());
+    }
+
+    #[test]
+    fn test_static_text() {
+        const GREETING: &str = "Thanks for calling Example Corp.";
+        let say = Say::from_static(GREETING).voice("alice");
+        assert!(std::ptr::eq(say.factory().text().unwrap(), GREETING));
+        assert_eq!(say.to_xml_string(), Say::new(GREETING).voice("alice").to_xml_string());
+
+        let play = Play::from_static("https://example.com/hold.mp3").loop_times(2);
+        let body = Body::from_static("Reply STOP to opt out");
+        let response = Response::new().play(play).message(Message::new_empty().body(body));
+        assert!(response.to_xml_string().ends_with(concat!(
+            r#"<Response><Play loop="2">https://example.com/hold.mp3</Play>"#,
+            "<Message><Body>Reply STOP to opt out</Body></Message></Response>",
+        )));
+    }
+
+    #[test]
+    fn test_split_message_segments() {
+        let text = "word ".repeat(100);
+        let parts = Message::new_empty().to("+15551234567").body(text.as_str()).media_url("https://example.com/a.png").split_segments(1);
+        assert_eq!(parts.len(), 4);
+        let xml: Vec<String> = parts.iter().map(|message| render_fragment(&message.to_xml())).collect();
+        assert!(xml[0].starts_with(r#"<Message to="+15551234567"><Body>word word"#));
+        assert!(xml[0].ends_with("</Body><Media>https://example.com/a.png</Media></Message>"));
+        assert!(xml[3].starts_with(r#"<Message to="+15551234567"><Body>word"#) && !xml[3].contains("Media"));
+
+        let texts = Message::new_with_text(text.as_str()).split_segments(2);
+        assert_eq!(texts.len(), 2);
+        assert!(texts.iter().all(|message| message.factory().children().is_empty()));
+        assert_eq!(Message::new_with_text("Hi").split_segments(1).len(), 1);
+    }
+
+    #[test]
+    fn test_dial_noun_parameters() {
+        let client = Client::new("alice").url("/screen").parameter(Parameter::new("ticket", "42"));
+        let application = Application::new("AP123").customer_id("c-1").parameter(Parameter::new("ticket", "42"));
+        let response = Response::new()
+            .dial(Dial::new_empty().client(client))
+            .dial(Dial::new_empty().application(application));
+        assert!(response.to_xml_string().ends_with(concat!(
+            r#"<Client url="/screen"><Identity>alice</Identity><Parameter name="ticket" value="42" /></Client></Dial>"#,
+            r#"<Dial><Application customerId="c-1"><ApplicationSid>AP123</ApplicationSid>"#,
+            r#"<Parameter name="ticket" value="42" /></Application></Dial></Response>"#,
+        )));
+        assert!(response.validate().is_ok());
+        assert_eq!(response.describe(), "Dial Client alice, then Dial Application AP123");
+    }
+
+    #[test]
+    fn test_redacted_string() {
+        let response = Response::new()
+            .say("Calling +1 (555) 123-4567 now, ticket +42")
+            .dial(
+                Dial::new_empty()
+                    .caller_id("+15550001111")
+                    .number(Number::new("+15551234567").send_digits("ww1234"))
+                    .sip(Sip::new("sip:jane.doe@example.com").username("agent").password("secret"))
+                    .client(Client::new("john.roe")),
+            )
+            .pay(Pay::new().payment_connector("Stripe").parameter(Parameter::new("customer", "cus_123")));
+        let redacted = response.to_redacted_string();
+        for secret in ["555", "ww1234", "agent", "jane.doe", "john.roe", "secret", "Stripe", "cus_123"] {
+            assert!(!redacted.contains(secret), "{} leaked in {}", secret, redacted);
+        }
+        assert!(redacted.contains("<Say>Calling [redacted number] now, ticket +42</Say>"));
+        assert!(redacted.contains(r#"<Parameter name="customer" value="[redacted]" />"#));
+        assert!(redacted.contains(">sip:[redacted]@example.com</Sip>"));
+        assert!(response.to_xml_string().contains("+15551234567"));
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_tracing_events() {
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        #[derive(Default)]
+        struct Fields(Vec<String>);
+
+        impl Visit for Fields {
+            fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+                self.0.push(format!("{}={:?}", field.name(), value));
+            }
+        }
+
+        struct Recorder(Arc<Mutex<Vec<String>>>);
+
+        impl Subscriber for Recorder {
+            fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                self.0.lock().unwrap().push(span.metadata().name().to_string());
+                Id::from_u64(1)
+            }
+            fn record(&self, _span: &Id, _values: &Record<'_>) {}
+            fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+            fn event(&self, event: &Event<'_>) {
+                let mut fields = Fields::default();
+                event.record(&mut fields);
+                self.0.lock().unwrap().push(fields.0.join(" "));
+            }
+            fn enter(&self, _span: &Id) {}
+            fn exit(&self, _span: &Id) {}
+        }
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        tracing::subscriber::with_default(Recorder(events.clone()), || {
+            let response = Response::new().say("Hi").gather(Gather::new().num_digits(0).say("Press 1"));
+            response.to_xml_string();
+            let _ = response.validate();
+        });
+
+        let events = events.lock().unwrap();
+        assert_eq!(events[0], "twiml.serialize");
+        assert!(events[1].starts_with("message=serialized TwiML bytes="));
+        assert!(events[1].ends_with("verbs=2 elements=4"));
+        assert!(events[2].contains("element=Gather attribute=\"numDigits\""));
+    }
+
+    #[cfg(feature = "fax")]
+    #[test]
+    fn test_receive_fax() {
+        let response = Response::new().receive(Receive::new().action("/fax/received").media_type("application/pdf").store_media(false));
+        assert!(response.to_xml_string().ends_with(
+            r#"<Response><Receive action="/fax/received" mediaType="application/pdf" storeMedia="false" /></Response>"#
+        ));
+    }
+
+    #[test]
+    fn test_enqueue_task() {
+        let json = r#"{"language":"es","note":"a < b ]]> c"}"#;
+        let escaped = Response::new().enqueue(Enqueue::new_empty().workflow_sid("WW123").task(Task::new(json).priority(5)));
+        assert!(escaped.to_xml_string().contains(
+            r#"<Enqueue workflowSid="WW123"><Task priority="5">{"language":"es","note":"a &lt; b ]]&gt; c"}</Task></Enqueue>"#
+        ));
+
+        let wrapped = Task::from_json(json).unwrap().cdata(true);
+        assert!(wrapped.to_xml_string().ends_with(
+            r#"<Task><![CDATA[{"language":"es","note":"a < b ]]]]><![CDATA[> c"}]]></Task>"#
+        ));
+
+        assert!(Task::from_json("{\"language\":").unwrap_err().reason.starts_with("invalid JSON"));
+        assert!(Task::from_json("[1, 2]").is_err());
+    }
+
     #[test]
     fn test_text_in_response() {
         let response = Response::new()
@@ -1210,12 +5853,14 @@ mod tests {
     }
 }
 
+#[cfg(feature = "pay")]
 #[test]
+#[allow(deprecated)]
 fn test_enhanced_gather() {
     let response = Response::new()
         .gather(
             Gather::new()
-                .input("speech")
+                .input(GatherInput::Speech)
                 .language("en-US")
                 .enhanced(true)
                 .speech_model("phone_call")
@@ -1231,7 +5876,7 @@ fn test_enhanced_gather() {
                 .prompt(
                     Prompt::new("Please tell us the reason for your call")
                         .attempt(1)
-                        .for_attr("speech")
+                        .for_attr_str("speech")
                         .voice("alice")
                         .language("en-US")
                 )
@@ -1251,14 +5896,28 @@ fn test_enhanced_gather() {
     assert!(xml_string.contains("Please tell us the reason for your call"));
 }
 
+#[cfg(feature = "voice")]
+#[test]
+fn test_gather_input_parsing() {
+    assert_eq!("dtmf".parse::<GatherInput>().unwrap(), GatherInput::Dtmf);
+    assert_eq!("speech  dtmf".parse::<GatherInput>().unwrap(), GatherInput::DtmfSpeech);
+    assert!("dtmf,speech".parse::<GatherInput>().is_err());
+
+    let xml_string = Response::new()
+        .gather(Gather::new().input(GatherInput::DtmfSpeech))
+        .to_xml_string();
+    assert!(xml_string.contains("input=\"dtmf speech\""));
+}
+
+#[cfg(feature = "voice")]
 #[test]
 fn test_dtmf_gather_with_interdigit_timeout() {
     let response = Response::new()
         .say(Say::new("Please enter your account number"))
         .gather(
             Gather::new()
-                .input("dtmf")
-                .num_digits("10")
+                .input(GatherInput::Dtmf)
+                .num_digits(10)
                 .interdigit_timeout(5)
                 .timeout(15)
                 .action("/process-account")
@@ -0,0 +1,938 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::dialect::Dialect;
+use crate::sanitize::is_xml_char;
+use crate::sms::{sms_segments, MAX_BODY_CHARACTERS};
+#[cfg(feature = "voice")]
+use crate::twiml::Digits;
+#[cfg(feature = "messaging")]
+use crate::twiml::Message;
+#[cfg(feature = "pay")]
+use crate::twiml::PromptFor;
+use crate::twiml::{ElementFactory, Response, TwiMLElement};
+
+/// Maximum number of Media elements Twilio accepts in one Message
+pub const MAX_MEDIA_PER_MESSAGE: usize = 10;
+
+/// Longest text Twilio speaks in one Say
+pub const MAX_SAY_CHARACTERS: usize = 4096;
+
+/// Maximum number of speech hint phrases in a Gather
+pub const MAX_HINT_PHRASES: usize = 500;
+
+/// Longest speech hint phrase in a Gather
+pub const MAX_HINT_CHARACTERS: usize = 100;
+
+/// Maximum number of SMS segments a Message body may span in strict mode
+pub const MAX_SMS_SEGMENTS: usize = 10;
+
+/// Longest time in seconds a TaskRouter task may wait to be assigned (two weeks)
+pub const MAX_TASK_TIMEOUT: u32 = 1_209_600;
+
+/// Longest time in seconds Dial rings the called party before giving up
+pub const MAX_DIAL_TIMEOUT: u32 = 600;
+
+/// Longest time in seconds a dialed call may last (24 hours)
+pub const MAX_DIAL_TIME_LIMIT: u32 = 86_400;
+
+/// Highest TaskRouter task priority
+pub const MAX_TASK_PRIORITY: u32 = 2_147_483_647;
+
+/// Most attempts Pay gives the caller to enter each piece of payment information
+pub const MAX_PAY_ATTEMPTS: u32 = 3;
+
+/// Most participants a Conference accepts
+pub const MAX_CONFERENCE_PARTICIPANTS: u32 = 250;
+
+/// Longest Programmable Video room name or participant identity
+pub const MAX_ROOM_NAME_CHARACTERS: usize = 128;
+
+/// Options controlling which checks validation performs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidationOptions {
+    strict: bool,
+    inbound_reply: bool,
+    messaging: bool,
+    pci: bool,
+    dialect: Dialect,
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        Self {
+            strict: false,
+            inbound_reply: true,
+            messaging: false,
+            pci: false,
+            dialect: Dialect::Twilio,
+        }
+    }
+}
+
+impl ValidationOptions {
+    /// Create options that only check limits Twilio enforces
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create options with strict checks enabled
+    pub fn strict() -> Self {
+        Self::new().strict_mode(true)
+    }
+
+    /// Enable checks for documents Twilio accepts but that are likely mistakes
+    pub fn strict_mode(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Set whether the document replies to an inbound message, where `to` and `from` default to the sender and recipient
+    pub fn inbound_reply(mut self, inbound_reply: bool) -> Self {
+        self.inbound_reply = inbound_reply;
+        self
+    }
+
+    /// Set whether the document answers a message webhook, where only `<Message>` and
+    /// `<Redirect>` may appear in the `<Response>`
+    pub fn messaging(mut self, messaging: bool) -> Self {
+        self.messaging = messaging;
+        self
+    }
+
+    /// Enable PCI checks: reject card numbers anywhere in the document, and `Gather`s
+    /// that collect payment details, which must go through `<Pay>` instead
+    pub fn pci(mut self, pci: bool) -> Self {
+        self.pci = pci;
+        self
+    }
+
+    /// Set the TwiML-compatible dialect the document is served to, rejecting elements
+    /// it doesn't support as [`Dialect::convert`] does; defaults to Twilio, which
+    /// rejects SignalWire extensions such as `<Denoise>`
+    pub fn dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+}
+
+/// Error describing why a document would be rejected by Twilio
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// Name of the offending element
+    pub element: String,
+    /// Name of the offending attribute, if the problem is attribute-specific
+    pub attribute: Option<String>,
+    /// Name of the misplaced child element, if the problem is that `element` doesn't
+    /// accept it as a child
+    pub child: Option<String>,
+    /// Human-readable description of the problem
+    pub reason: String,
+}
+
+impl ValidationError {
+    fn element(node: &ElementFactory, reason: impl Into<String>) -> Self {
+        Self {
+            element: node.name().to_string(),
+            attribute: None,
+            child: None,
+            reason: reason.into(),
+        }
+    }
+
+    fn nesting(node: &ElementFactory, child: &str, reason: impl Into<String>) -> Self {
+        Self {
+            element: node.name().to_string(),
+            attribute: None,
+            child: Some(child.to_string()),
+            reason: reason.into(),
+        }
+    }
+
+    fn attribute(node: &ElementFactory, attribute: &str, reason: impl Into<String>) -> Self {
+        Self {
+            element: node.name().to_string(),
+            attribute: Some(attribute.to_string()),
+            child: None,
+            reason: reason.into(),
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.attribute {
+            Some(attribute) => write!(f, "invalid `{}` on <{}>: {}", attribute, self.element, self.reason),
+            None => write!(f, "invalid <{}>: {}", self.element, self.reason),
+        }
+    }
+}
+
+impl Error for ValidationError {}
+
+/// Validate an element and all of its descendants, returning the first problem found
+pub(crate) fn validate_tree(element: &dyn TwiMLElement, options: &ValidationOptions) -> Result<(), ValidationError> {
+    let node = element.factory();
+    let result = check_element(node, options);
+    #[cfg(feature = "tracing")]
+    if let Err(error) = &result {
+        tracing::warn!(
+            element = %error.element,
+            attribute = error.attribute.as_deref(),
+            reason = %error.reason,
+            "TwiML validation failed"
+        );
+    }
+    result?;
+    for child in node.children() {
+        validate_tree(child.as_ref(), options)?;
+    }
+    Ok(())
+}
+
+/// Children Twilio accepts inside elements that only take a few kinds, or `None` if unrestricted here
+fn allowed_children(parent: &str) -> Option<&'static [&'static str]> {
+    match parent {
+        "Gather" | "Prompt" => Some(&["Say", "Play", "Pause"]),
+        "Dial" => Some(&["Number", "Client", "Conference", "Queue", "Sip", "Application"]),
+        "Message" => Some(&["Body", "Media"]),
+        "Enqueue" => Some(&["Task"]),
+        "Client" => Some(&["Identity", "Parameter"]),
+        "Application" => Some(&["ApplicationSid", "Parameter"]),
+        _ => None,
+    }
+}
+
+/// Check that children added through generic paths such as fragments are allowed in their parent
+fn check_children(node: &ElementFactory) -> Result<(), ValidationError> {
+    let Some(allowed) = allowed_children(node.name()) else {
+        return Ok(());
+    };
+    match node.children().iter().map(|child| child.factory().name()).find(|name| !allowed.contains(name)) {
+        Some(child) => Err(ValidationError::nesting(
+            node,
+            child,
+            format!("<{}> is not allowed inside <{}>; use {}", child, node.name(), allowed.join(", ")),
+        )),
+        None => Ok(()),
+    }
+}
+
+fn check_element(node: &ElementFactory, options: &ValidationOptions) -> Result<(), ValidationError> {
+    if options.strict {
+        check_characters(node)?;
+    }
+    check_children(node)?;
+    if let Some(reason) = options.dialect.unsupported(node.name()) {
+        return Err(ValidationError::element(node, format!("{} not supported in {}", reason, options.dialect)));
+    }
+    if options.pci {
+        check_pci(node)?;
+    }
+
+    match node.name() {
+        "Response" if options.messaging => check_messaging_response(node),
+        "Redirect" => check_redirect(node),
+        "Message" => check_message(node, options),
+        "Pay" => check_pay(node),
+        "Prompt" => check_prompt(node),
+        "Conference" => check_integer(node, "maxParticipants", 2..=MAX_CONFERENCE_PARTICIPANTS),
+        "Task" => check_task(node),
+        "Say" => check_say(node),
+        #[cfg(feature = "voice")]
+        "Number" => check_digits(node, "sendDigits"),
+        #[cfg(feature = "voice")]
+        "Play" => check_digits(node, "digits"),
+        "Gather" => check_gather(node, options),
+        "Dial" => check_dial(node),
+        "Enqueue" => check_enqueue(node, options),
+        "Room" => check_room(node),
+        "Connect" if node.children().len() != 1 => Err(ValidationError::element(
+            node,
+            format!("must contain exactly one noun, found {}", node.children().len()),
+        )),
+        "Record" if options.strict => check_finish_on_key(node, true),
+        _ => Ok(()),
+    }
+}
+
+/// Words in a Gather prompt that suggest it collects payment details
+const PAYMENT_PROMPT_WORDS: &[&str] = &["card number", "credit card", "debit card", "security code", "cvv", "expiration date"];
+
+/// Check that an element holds no card numbers and doesn't collect payment details outside Pay
+fn check_pci(node: &ElementFactory) -> Result<(), ValidationError> {
+    if node.plain_text().is_some_and(|text| contains_card_number(&text)) {
+        return Err(ValidationError::element(node, "text contains a card number"));
+    }
+    if let Some((key, _)) = node.attributes().iter().find(|(_, value)| contains_card_number(value)) {
+        return Err(ValidationError::attribute(node, key, "contains a card number"));
+    }
+    if node.name() != "Gather" {
+        return Ok(());
+    }
+    if node.attribute("numDigits").and_then(|digits| digits.parse::<usize>().ok()).is_some_and(|digits| digits >= 13) {
+        return Err(ValidationError::attribute(node, "numDigits", "collects a card-length number; use <Pay>"));
+    }
+    let prompts_for_payment = node.children().iter().filter_map(|child| child.factory().plain_text()).any(|text| {
+        let text = text.to_lowercase();
+        PAYMENT_PROMPT_WORDS.iter().any(|word| text.contains(word))
+    });
+    if prompts_for_payment {
+        return Err(ValidationError::element(node, "prompts for payment details; use <Pay>"));
+    }
+    Ok(())
+}
+
+/// Whether text contains 13 to 19 digits, optionally separated by single spaces or
+/// dashes, that pass the Luhn check
+fn contains_card_number(text: &str) -> bool {
+    let chars: Vec<char> = text.chars().collect();
+    let mut digits = Vec::new();
+    for (index, &c) in chars.iter().enumerate() {
+        if let Some(digit) = c.to_digit(10) {
+            digits.push(digit);
+            continue;
+        }
+        let separator = matches!(c, ' ' | '-')
+            && index > 0
+            && chars[index - 1].is_ascii_digit()
+            && chars.get(index + 1).is_some_and(char::is_ascii_digit);
+        if !separator {
+            if is_card_number(&digits) {
+                return true;
+            }
+            digits.clear();
+        }
+    }
+    is_card_number(&digits)
+}
+
+/// Whether a digit run has a card number's length and passes the Luhn check
+fn is_card_number(digits: &[u32]) -> bool {
+    if !(13..=19).contains(&digits.len()) {
+        return false;
+    }
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(index, &digit)| if index % 2 == 1 { if digit * 2 > 9 { digit * 2 - 9 } else { digit * 2 } } else { digit })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// Check that a messaging response only contains the verbs messaging TwiML supports
+fn check_messaging_response(node: &ElementFactory) -> Result<(), ValidationError> {
+    match node.children().iter().map(|child| child.factory().name()).find(|name| !["Message", "Redirect"].contains(name)) {
+        Some(child) => Err(ValidationError::element(
+            node,
+            format!("<{}> is not allowed in a messaging response; use Message, Redirect", child),
+        )),
+        None => Ok(()),
+    }
+}
+
+fn check_redirect(node: &ElementFactory) -> Result<(), ValidationError> {
+    match node.attribute("method") {
+        Some(method) if method != "GET" && method != "POST" => {
+            Err(ValidationError::attribute(node, "method", "must be GET or POST"))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Check that text and attribute values contain no characters XML disallows,
+/// which serialization would otherwise strip silently
+fn check_characters(node: &ElementFactory) -> Result<(), ValidationError> {
+    let reason = |c: char| format!("contains U+{:04X}, which is not allowed in XML", c as u32);
+    if let Some(c) = node.text().and_then(|text| text.chars().find(|&c| !is_xml_char(c))) {
+        return Err(ValidationError::element(node, reason(c)));
+    }
+    for (key, value) in node.attributes() {
+        if let Some(c) = value.chars().find(|&c| !is_xml_char(c)) {
+            return Err(ValidationError::attribute(node, key, reason(c)));
+        }
+    }
+    Ok(())
+}
+
+fn check_say(node: &ElementFactory) -> Result<(), ValidationError> {
+    let length = node.plain_text().map_or(0, |text| text.chars().count());
+    if length > MAX_SAY_CHARACTERS {
+        return Err(ValidationError::element(
+            node,
+            format!("text is {} characters, more than {}; use `Say::split`", length, MAX_SAY_CHARACTERS),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "voice")]
+fn check_digits(node: &ElementFactory, attribute: &str) -> Result<(), ValidationError> {
+    if let Some(digits) = node.attribute(attribute)
+        && let Err(error) = Digits::parse(node.name(), digits)
+    {
+        return Err(ValidationError::attribute(node, attribute, error.reason));
+    }
+    Ok(())
+}
+
+fn check_gather(node: &ElementFactory, options: &ValidationOptions) -> Result<(), ValidationError> {
+    if let Some(digits) = node.attribute("numDigits")
+        && !digits.parse::<usize>().is_ok_and(|digits| digits >= 1)
+    {
+        return Err(ValidationError::attribute(node, "numDigits", "must be an integer of at least 1"));
+    }
+
+    if let Some(hints) = node.attribute("hints") {
+        let phrases: Vec<&str> = hints.split(',').map(str::trim).filter(|phrase| !phrase.is_empty()).collect();
+        if phrases.len() > MAX_HINT_PHRASES {
+            return Err(ValidationError::attribute(
+                node,
+                "hints",
+                format!("has {} phrases, more than {}", phrases.len(), MAX_HINT_PHRASES),
+            ));
+        }
+        if let Some(phrase) = phrases.iter().find(|phrase| phrase.chars().count() > MAX_HINT_CHARACTERS) {
+            return Err(ValidationError::attribute(
+                node,
+                "hints",
+                format!("phrase `{}` is longer than {} characters", phrase, MAX_HINT_CHARACTERS),
+            ));
+        }
+    }
+
+    if options.strict {
+        check_finish_on_key(node, false)?;
+    }
+
+    Ok(())
+}
+
+/// Check that finishOnKey is empty (disabled) or made of keypad keys;
+/// Gather accepts a single key while Record accepts any set of keys
+fn check_finish_on_key(node: &ElementFactory, multiple: bool) -> Result<(), ValidationError> {
+    let Some(keys) = node.attribute("finishOnKey") else {
+        return Ok(());
+    };
+
+    if let Some(key) = keys.chars().find(|key| !matches!(key, '0'..='9' | '*' | '#')) {
+        return Err(ValidationError::attribute(
+            node,
+            "finishOnKey",
+            format!("`{}` is not a keypad key (0-9, * or #)", key),
+        ));
+    }
+    if !multiple && keys.chars().count() > 1 {
+        return Err(ValidationError::attribute(
+            node,
+            "finishOnKey",
+            "must be a single key, or empty to disable",
+        ));
+    }
+
+    Ok(())
+}
+
+fn check_pay(node: &ElementFactory) -> Result<(), ValidationError> {
+    let charge = match node.attribute("chargeAmount") {
+        Some(amount) => amount
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|amount| amount.is_finite() && *amount >= 0.0)
+            .ok_or_else(|| ValidationError::attribute(node, "chargeAmount", "must be a non-negative decimal amount"))?,
+        None => 0.0,
+    };
+    check_integer(node, "maxAttempts", 1..=MAX_PAY_ATTEMPTS)?;
+    check_integer(node, "minPostalCodeLength", 1..=u32::MAX)?;
+
+    if charge > 0.0 && node.attribute("tokenType").is_some() {
+        return Err(ValidationError::attribute(
+            node,
+            "tokenType",
+            "only applies when tokenizing; remove it or set chargeAmount to 0",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Check that a Prompt asks for a known piece of payment information and that its
+/// attempt numbers are ones Pay can reach
+fn check_prompt(node: &ElementFactory) -> Result<(), ValidationError> {
+    #[cfg(feature = "pay")]
+    if let Some(field) = node.attribute("for") {
+        field.parse::<PromptFor>()?;
+    }
+    if let Some(attempts) = node.attribute("attempt")
+        && !attempts
+            .split_whitespace()
+            .all(|attempt| attempt.parse::<u32>().is_ok_and(|attempt| (1..=MAX_PAY_ATTEMPTS).contains(&attempt)))
+    {
+        return Err(ValidationError::attribute(
+            node,
+            "attempt",
+            format!("must be attempt numbers between 1 and {}", MAX_PAY_ATTEMPTS),
+        ));
+    }
+    Ok(())
+}
+
+/// Check that an attribute, if set, is an integer within `range`
+fn check_integer(
+    node: &ElementFactory,
+    attribute: &str,
+    range: std::ops::RangeInclusive<u32>,
+) -> Result<(), ValidationError> {
+    if let Some(value) = node.attribute(attribute)
+        && !value.parse::<u32>().is_ok_and(|value| range.contains(&value))
+    {
+        let reason = match *range.end() {
+            u32::MAX => format!("must be an integer of at least {}", range.start()),
+            end => format!("must be an integer between {} and {}", range.start(), end),
+        };
+        return Err(ValidationError::attribute(node, attribute, reason));
+    }
+    Ok(())
+}
+
+/// Check that an attribute, if set, is a whole number of seconds within `range`
+fn check_seconds(
+    node: &ElementFactory,
+    attribute: &str,
+    range: std::ops::RangeInclusive<u32>,
+) -> Result<(), ValidationError> {
+    if let Some(seconds) = node.attribute(attribute)
+        && !seconds.parse::<u32>().is_ok_and(|seconds| range.contains(&seconds))
+    {
+        return Err(ValidationError::attribute(
+            node,
+            attribute,
+            format!("must be between {} and {} seconds", range.start(), range.end()),
+        ));
+    }
+    Ok(())
+}
+
+fn check_dial(node: &ElementFactory) -> Result<(), ValidationError> {
+    if node.text().is_some_and(|number| !number.trim().is_empty()) && !node.children().is_empty() {
+        return Err(ValidationError::element(
+            node,
+            "dials either its text or its nouns, not both; use `Dial::new_empty` with nouns",
+        ));
+    }
+    check_seconds(node, "timeout", 5..=MAX_DIAL_TIMEOUT)?;
+    check_seconds(node, "timeLimit", 1..=MAX_DIAL_TIME_LIMIT)
+}
+
+/// Check that an Enqueue names a queue or routes through TaskRouter, and in strict mode
+/// that it doesn't mix the two
+fn check_enqueue(node: &ElementFactory, options: &ValidationOptions) -> Result<(), ValidationError> {
+    let queue_name = node.text().is_some_and(|name| !name.trim().is_empty());
+    let workflow = node.attribute("workflowSid").is_some();
+    if !queue_name && !workflow {
+        return Err(ValidationError::element(node, "needs a queue name or a workflowSid"));
+    }
+
+    if options.strict {
+        if queue_name && workflow {
+            return Err(ValidationError::attribute(
+                node,
+                "workflowSid",
+                "the queue name is ignored when routing through TaskRouter; use `Enqueue::new_empty`",
+            ));
+        }
+        if !workflow && node.children().iter().any(|child| child.factory().name() == "Task") {
+            return Err(ValidationError::element(node, "a Task only applies with a workflowSid"));
+        }
+    }
+
+    Ok(())
+}
+
+fn check_task(node: &ElementFactory) -> Result<(), ValidationError> {
+    check_integer(node, "priority", 0..=MAX_TASK_PRIORITY)?;
+    check_seconds(node, "timeout", 1..=MAX_TASK_TIMEOUT)?;
+
+    let attributes = node.text().unwrap_or_default();
+    if !matches!(serde_json::from_str(attributes), Ok(serde_json::Value::Object(_))) {
+        return Err(ValidationError::element(node, "task attributes must be a JSON object"));
+    }
+
+    Ok(())
+}
+
+/// Check that a Room names a room and that the names fit Programmable Video's limits
+fn check_room(node: &ElementFactory) -> Result<(), ValidationError> {
+    let name = node.text().unwrap_or_default().trim();
+    if name.is_empty() {
+        return Err(ValidationError::element(node, "needs a room name or SID"));
+    }
+    if name.chars().count() > MAX_ROOM_NAME_CHARACTERS {
+        return Err(ValidationError::element(
+            node,
+            format!("room name is longer than {} characters", MAX_ROOM_NAME_CHARACTERS),
+        ));
+    }
+    if let Some(identity) = node.attribute("participantIdentity")
+        && (identity.trim().is_empty() || identity.chars().count() > MAX_ROOM_NAME_CHARACTERS)
+    {
+        return Err(ValidationError::attribute(
+            node,
+            "participantIdentity",
+            format!("must be 1 to {} characters", MAX_ROOM_NAME_CHARACTERS),
+        ));
+    }
+    Ok(())
+}
+
+fn check_message(node: &ElementFactory, options: &ValidationOptions) -> Result<(), ValidationError> {
+    let media = node.children().iter().filter(|child| child.factory().name() == "Media").count();
+    if media > MAX_MEDIA_PER_MESSAGE {
+        return Err(ValidationError::element(
+            node,
+            format!("{} media attachments exceed the limit of {}", media, MAX_MEDIA_PER_MESSAGE),
+        ));
+    }
+
+    let body = node.text().or_else(|| {
+        node.children()
+            .iter()
+            .find(|child| child.factory().name() == "Body")
+            .and_then(|body| body.factory().text())
+    });
+    if let Some(body) = body {
+        let length = body.chars().count();
+        if length > MAX_BODY_CHARACTERS {
+            return Err(ValidationError::element(
+                node,
+                format!("body of {} characters exceeds the limit of {}", length, MAX_BODY_CHARACTERS),
+            ));
+        }
+    }
+
+    if !options.strict {
+        return Ok(());
+    }
+
+    match body {
+        None if media == 0 => return Err(ValidationError::element(node, "must contain a Body or Media")),
+        Some(body) => {
+            let info = sms_segments(body);
+            if info.segments > MAX_SMS_SEGMENTS {
+                return Err(ValidationError::element(
+                    node,
+                    format!(
+                        "body needs {} {:?} segments, more than the limit of {}",
+                        info.segments, info.encoding, MAX_SMS_SEGMENTS
+                    ),
+                ));
+            }
+        }
+        None => {}
+    }
+
+    if !options.inbound_reply {
+        for attribute in ["to", "from"] {
+            if node.attribute(attribute).is_none() {
+                return Err(ValidationError::attribute(
+                    node,
+                    attribute,
+                    "required when not replying to an inbound message",
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl Response {
+    /// Check the document against Twilio's constraints
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        self.validate_with(&ValidationOptions::default())
+    }
+
+    /// Check the document with the given validation options
+    pub fn validate_with(&self, options: &ValidationOptions) -> Result<(), ValidationError> {
+        validate_tree(self, options)
+    }
+}
+
+#[cfg(feature = "messaging")]
+impl Message {
+    /// Check the message against Twilio's constraints
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        self.validate_with(&ValidationOptions::default())
+    }
+
+    /// Check the message with the given validation options
+    pub fn validate_with(&self, options: &ValidationOptions) -> Result<(), ValidationError> {
+        validate_tree(self, options)
+    }
+}
+
+#[cfg(all(test, feature = "messaging", feature = "pay"))]
+mod tests {
+    use super::*;
+    use crate::twiml::*;
+
+    #[test]
+    fn test_media_limit() {
+        let urls: Vec<String> = (0..MAX_MEDIA_PER_MESSAGE).map(|i| format!("https://example.com/{}.jpg", i)).collect();
+
+        let message = Message::new_empty().media_urls(urls.clone());
+        assert!(message.validate().is_ok());
+
+        let response = Response::new().message(Message::new_empty().media_urls(urls).media_url("https://example.com/x.jpg"));
+        let error = response.validate().unwrap_err();
+        assert_eq!(error.element, "Message");
+        assert!(error.reason.contains("11 media"));
+    }
+
+    #[test]
+    fn test_strict_message_content() {
+        let strict = ValidationOptions::strict();
+
+        assert!(Message::new_empty().validate().is_ok());
+        let error = Message::new_empty().validate_with(&strict).unwrap_err();
+        assert!(error.reason.contains("Body or Media"));
+
+        assert!(Message::new_with_text("Hi").validate_with(&strict).is_ok());
+        assert!(Message::new_empty().media_url("https://example.com/a.jpg").validate_with(&strict).is_ok());
+
+        let long = Message::new_empty().body(Body::new("x".repeat(1601)));
+        assert!(long.validate().is_err());
+
+        // 11 UCS-2 segments fit in 1600 characters but exceed the strict segment limit
+        let unicode = Message::new_empty().body(Body::new("ж".repeat(700)));
+        assert!(unicode.validate().is_ok());
+        assert!(unicode.validate_with(&strict).unwrap_err().reason.contains("Ucs2"));
+    }
+
+    #[test]
+    fn test_strict_outbound_message_requires_addresses() {
+        let outbound = ValidationOptions::strict().inbound_reply(false);
+
+        let message = Message::new_with_text("Reminder").to("+15551234567");
+        assert!(message.validate_with(&ValidationOptions::strict()).is_ok());
+        let error = message.validate_with(&outbound).unwrap_err();
+        assert_eq!(error.attribute.as_deref(), Some("from"));
+
+        let message = Message::new_with_text("Reminder").to("+15551234567").from("+15557654321");
+        assert!(message.validate_with(&outbound).is_ok());
+    }
+
+    #[test]
+    fn test_pay_charge_and_token_type() {
+        let tokenize = Response::new().pay(Pay::new().charge_amount("0").token_type(TokenType::Reusable));
+        assert!(tokenize.validate().is_ok());
+
+        let charge = Response::new().pay(
+            Pay::new().charge_amount("10.00").payment_connector(PaymentConnector::new("Stripe_Connector")),
+        );
+        assert!(charge.validate().is_ok());
+        assert!(charge.to_xml_string().contains("<Pay chargeAmount=\"10.00\" paymentConnector=\"Stripe_Connector\" />"));
+
+        let conflicting = Response::new().pay(Pay::new().charge_amount("10.00").token_type(TokenType::OneTime));
+        assert_eq!(conflicting.validate().unwrap_err().attribute.as_deref(), Some("tokenType"));
+
+        let invalid = Response::new().pay(Pay::new().charge_amount("ten"));
+        assert_eq!(invalid.validate().unwrap_err().attribute.as_deref(), Some("chargeAmount"));
+    }
+
+    #[test]
+    fn test_strict_finish_on_key() {
+        let strict = ValidationOptions::strict();
+
+        assert!(Response::new().gather(Gather::new().finish_on_key("#")).validate_with(&strict).is_ok());
+        assert!(Response::new().gather(Gather::new().finish_on_key("")).validate_with(&strict).is_ok());
+        assert!(Response::new().record(Record::new().finish_on_key("*#")).validate_with(&strict).is_ok());
+
+        let multiple = Response::new().gather(Gather::new().finish_on_key("*#"));
+        assert!(multiple.validate().is_ok());
+        assert_eq!(multiple.validate_with(&strict).unwrap_err().attribute.as_deref(), Some("finishOnKey"));
+
+        let invalid = Response::new().record(Record::new().finish_on_key("A"));
+        assert!(invalid.validate_with(&strict).unwrap_err().reason.contains("`A`"));
+    }
+
+    #[test]
+    fn test_task_attributes() {
+        #[derive(serde::Serialize)]
+        struct Attributes {
+            language: &'static str,
+            vip: bool,
+        }
+
+        let task = Task::from_attributes(&Attributes { language: "es", vip: true }).unwrap().priority(10);
+        assert!(task.to_xml_string().ends_with(r#"<Task priority="10">{"language":"es","vip":true}</Task>"#));
+        assert!(Task::from_attributes(&[1, 2]).is_err());
+
+        let enqueue = |task: Task| Response::new().enqueue(Enqueue::new_empty().workflow_sid("WW1").task(task));
+        assert!(enqueue(Task::new("{}").timeout(3600)).validate().is_ok());
+        let error = enqueue(Task::new("{}").timeout(MAX_TASK_TIMEOUT as usize + 1)).validate().unwrap_err();
+        assert_eq!(error.attribute.as_deref(), Some("timeout"));
+        assert!(enqueue(Task::new("{\"a\":")).validate().is_err());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_num_digits_range() {
+        assert!(Response::new().gather(Gather::new().num_digits(4)).validate().is_ok());
+
+        let zero = Response::new().gather(Gather::new().num_digits(0));
+        assert_eq!(zero.validate().unwrap_err().attribute.as_deref(), Some("numDigits"));
+
+        let word = Response::new().gather(Gather::new().num_digits_str("one"));
+        assert!(word.validate().is_err());
+    }
+
+    #[test]
+    fn test_media_urls_serialization() {
+        let xml = Response::new()
+            .message(
+                Message::new_empty()
+                    .body(Body::new("Photos"))
+                    .media_urls(["https://example.com/a.jpg", "https://example.com/b.jpg"]),
+            )
+            .to_xml_string();
+        assert!(xml.contains(
+            "<Body>Photos</Body><Media>https://example.com/a.jpg</Media><Media>https://example.com/b.jpg</Media>"
+        ));
+    }
+
+    #[test]
+    fn test_numeric_ranges() {
+        let prompt = Prompt::new("Please enter your card number").attempts([1, 2]);
+        assert!(prompt.to_xml_string().contains(r#"<Prompt attempt="1 2">"#));
+        let pay = Pay::new().max_attempts(3).prompt(prompt);
+        assert!(Response::new().pay(pay).validate().is_ok());
+
+        let too_many = Response::new().pay(Pay::new().max_attempts(4));
+        assert_eq!(too_many.validate().unwrap_err().reason, "must be an integer between 1 and 3");
+        let unreachable = Response::new().pay(Pay::new().prompt(Prompt::new("Last try").attempt(4)));
+        assert_eq!(unreachable.validate().unwrap_err().attribute.as_deref(), Some("attempt"));
+
+        let task = Response::new().enqueue(Enqueue::new_empty().workflow_sid("WW1").task(Task::new("{}").priority(MAX_TASK_PRIORITY + 1)));
+        assert_eq!(task.validate().unwrap_err().attribute.as_deref(), Some("priority"));
+        let conference = Dial::new_empty().conference(Conference::new("Room").max_participants(251));
+        assert_eq!(Response::new().dial(conference).validate().unwrap_err().attribute.as_deref(), Some("maxParticipants"));
+    }
+
+    #[test]
+    fn test_child_constraints() {
+        let menu = Fragment::new().say("Press 1 for sales").pause(Pause::new().length(1));
+        assert!(Response::new().gather(Gather::new().fragment(menu)).validate().is_ok());
+
+        let invalid = Response::new().gather(Gather::new().fragment(Fragment::new().say("Goodbye").hangup()));
+        assert_eq!(
+            invalid.validate().unwrap_err().to_string(),
+            "invalid <Gather>: <Hangup> is not allowed inside <Gather>; use Say, Play, Pause"
+        );
+        let shared = Shared::new(Record::new());
+        assert!(Response::new().gather(Gather::new().shared(&shared)).validate().is_err());
+    }
+
+    #[test]
+    fn test_dial_destination() {
+        assert!(Response::new().dial(Dial::new_with_number("+15551234567")).validate().is_ok());
+        let both = Response::new().dial(Dial::new_with_number("+15551234567").number(Number::new("+15557654321")));
+        assert!(both.validate().unwrap_err().reason.starts_with("dials either its text or its nouns"));
+        assert!(matches!(both.try_to_xml_string(), Err(crate::TwiMLError::Serialization(_))));
+    }
+
+    #[test]
+    fn test_room_names() {
+        let room = |room: Room| Response::new().connect(Connect::new().room(room));
+        assert!(room(Room::new("DailyStandup").participant_identity("alice")).validate().is_ok());
+        assert_eq!(room(Room::new(" ")).validate().unwrap_err().reason, "needs a room name or SID");
+        assert!(room(Room::new("a".repeat(MAX_ROOM_NAME_CHARACTERS + 1))).validate().is_err());
+        let identity = room(Room::new("DailyStandup").participant_identity(""));
+        assert_eq!(identity.validate().unwrap_err().attribute.as_deref(), Some("participantIdentity"));
+    }
+
+    #[test]
+    fn test_prompt_content() {
+        let prompt = Prompt::new("Please enter your card number").for_attr(PromptFor::PaymentCardNumber);
+        assert!(prompt.to_xml_string().contains(r#"<Prompt for="payment-card-number">"#));
+        assert!(Response::new().pay(Pay::new().prompt(prompt)).validate().is_ok());
+        assert_eq!("postal-code".parse::<PromptFor>().unwrap(), PromptFor::PostalCode);
+
+        let error = "pin".parse::<PromptFor>().unwrap_err();
+        assert_eq!((error.element.as_str(), error.attribute.as_deref()), ("Prompt", Some("for")));
+    }
+
+    #[test]
+    fn test_messaging_response() {
+        let options = ValidationOptions::new().messaging(true);
+        let reply = Response::new()
+            .message(Message::new_with_text("Forwarding you now"))
+            .redirect(Redirect::new("/sms/next").method("POST"));
+        assert!(reply.validate_with(&options).is_ok());
+
+        let error = Response::new().message(Message::new_with_text("Hi")).say("Hi").validate_with(&options).unwrap_err();
+        assert_eq!(error.reason, "<Say> is not allowed in a messaging response; use Message, Redirect");
+        assert!(Response::new().say("Hi").validate().is_ok());
+
+        let error = Response::new().redirect(Redirect::new("/next").method("PUT")).validate().unwrap_err();
+        assert_eq!(error.attribute.as_deref(), Some("method"));
+    }
+
+    #[test]
+    fn test_pci_mode() {
+        let pci = ValidationOptions::new().pci(true);
+        let leaked = Response::new().say("Your card 4111 1111 1111 1111 was charged");
+        assert_eq!(leaked.validate_with(&pci).unwrap_err().reason, "text contains a card number");
+        assert!(leaked.validate().is_ok());
+        let action = Response::new().gather(Gather::new().action("/paid?card=4111-1111-1111-1111"));
+        assert_eq!(action.validate_with(&pci).unwrap_err().attribute.as_deref(), Some("action"));
+        assert!(Response::new().say("Order 4111 1111 1111 1112 shipped").validate_with(&pci).is_ok());
+
+        let gather = Response::new().gather(Gather::new().say("Please enter your card number"));
+        assert_eq!(gather.validate_with(&pci).unwrap_err().reason, "prompts for payment details; use <Pay>");
+        assert!(Response::new().gather(Gather::new().num_digits(16)).validate_with(&pci).is_err());
+        let pay = Response::new().pay(Pay::new().prompt(Prompt::new_empty().for_attr(PromptFor::PaymentCardNumber).say("Please enter your card number")));
+        assert!(pay.validate_with(&pci).is_ok());
+
+        let digits = SayPart::SayAs { interpret_as: "digits".to_string(), text: "4111 1111 1111 1111".to_string() };
+        let leaked = Response::new().say(Say::from_parts([SayPart::from("Your card "), digits]).unwrap());
+        assert_eq!(leaked.validate_with(&pci).unwrap_err().reason, "text contains a card number");
+    }
+
+    #[test]
+    fn test_say_length_counts_spoken_text() {
+        let text = "&".repeat(MAX_SAY_CHARACTERS);
+        let say = Say::from_parts([SayPart::from(text.as_str()), SayPart::Break("1s".to_string())]).unwrap();
+        assert!(Response::new().say(say).validate().is_ok());
+        let say = Say::from_parts([text.as_str(), "!"]).unwrap();
+        assert!(Response::new().say(say).validate().is_err());
+    }
+
+    #[test]
+    fn test_dialect_elements() {
+        let denoise = Response::new().denoise().say("Hello");
+        let error = denoise.validate().unwrap_err();
+        assert_eq!(error.to_string(), "invalid <Denoise>: SignalWire-only extension not supported in Twilio TwiML");
+        assert!(denoise.validate_with(&ValidationOptions::new().dialect(Dialect::SignalWire)).is_ok());
+
+        let application = Response::new().dial(Dial::new_empty().application(Application::new("AP123")));
+        assert!(application.validate().is_ok());
+        assert!(application.validate_with(&ValidationOptions::new().dialect(Dialect::SignalWire)).is_err());
+    }
+
+    #[test]
+    fn test_enqueue_combinations() {
+        let strict = ValidationOptions::strict();
+        assert!(Response::new().enqueue(Enqueue::new("support")).validate_with(&strict).is_ok());
+        assert!(Response::new().enqueue(Enqueue::new_empty()).validate().is_err());
+
+        let both = Response::new().enqueue(Enqueue::new("support").workflow_sid("WW1"));
+        assert!(both.validate().is_ok());
+        assert_eq!(both.validate_with(&strict).unwrap_err().attribute.as_deref(), Some("workflowSid"));
+
+        let stray_task = Response::new().enqueue(Enqueue::new_empty().task(Task::new("{}")));
+        assert!(stray_task.validate().is_err());
+    }
+}
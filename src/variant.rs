@@ -0,0 +1,140 @@
+use crate::twiml::{fnv1a, Gather, Play, Response, Say};
+
+/// Prompt played by one variant of a [`VariantSet`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VariantPrompt {
+    /// Text spoken with `Say`
+    Say(String),
+    /// Audio URL played with `Play`
+    Play(String),
+}
+
+/// One prompt of an A/B test, identified for analytics
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variant {
+    id: String,
+    prompt: VariantPrompt,
+}
+
+impl Variant {
+    /// Get the variant ID to report alongside call outcomes
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Get the prompt
+    pub fn prompt(&self) -> &VariantPrompt {
+        &self.prompt
+    }
+
+    /// Append the prompt to a document
+    pub fn add_to(&self, response: Response) -> Response {
+        match &self.prompt {
+            VariantPrompt::Say(text) => response.say(Say::new(text.as_str())),
+            VariantPrompt::Play(url) => response.play(Play::new(url.as_str())),
+        }
+    }
+
+    /// Append the prompt to a Gather
+    pub fn add_to_gather(&self, gather: Gather) -> Gather {
+        match &self.prompt {
+            VariantPrompt::Say(text) => gather.say(Say::new(text.as_str())),
+            VariantPrompt::Play(url) => gather.play(Play::new(url.as_str())),
+        }
+    }
+}
+
+/// Prompt variants of an experiment, selected per caller by hashing the CallSid
+///
+/// Selection is deterministic: the same CallSid always gets the same variant, and the
+/// experiment name is hashed along with it so separate experiments split callers
+/// independently. The hash is FNV-1a, which is stable across Rust versions and
+/// platforms, so assignments survive deploys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariantSet {
+    name: String,
+    variants: Vec<Variant>,
+}
+
+impl VariantSet {
+    /// Create an empty experiment
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            variants: Vec::new(),
+        }
+    }
+
+    /// Add a variant speaking `text`
+    pub fn say(mut self, id: impl Into<String>, text: impl Into<String>) -> Self {
+        self.variants.push(Variant {
+            id: id.into(),
+            prompt: VariantPrompt::Say(text.into()),
+        });
+        self
+    }
+
+    /// Add a variant playing the audio at `url`
+    pub fn play(mut self, id: impl Into<String>, url: impl Into<String>) -> Self {
+        self.variants.push(Variant {
+            id: id.into(),
+            prompt: VariantPrompt::Play(url.into()),
+        });
+        self
+    }
+
+    /// Get the experiment name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the variants in the order they were added
+    pub fn variants(&self) -> &[Variant] {
+        &self.variants
+    }
+
+    /// Select the variant for a call, or `None` if the set is empty
+    pub fn select(&self, call_sid: &str) -> Option<&Variant> {
+        if self.variants.is_empty() {
+            return None;
+        }
+        let hash = fnv1a([self.name.as_bytes(), &[0], call_sid.as_bytes()]);
+        self.variants.get((hash % self.variants.len() as u64) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::twiml::ToXmlString;
+
+    fn greeting() -> VariantSet {
+        VariantSet::new("greeting")
+            .say("short", "Hi!")
+            .say("long", "Hello and thank you for calling.")
+            .play("recorded", "https://example.com/greeting.mp3")
+    }
+
+    #[test]
+    fn test_selection_is_stable() {
+        let set = greeting();
+        let variant = set.select("CA0123456789abcdef0123456789abcdef").unwrap();
+        assert_eq!(set.select("CA0123456789abcdef0123456789abcdef").unwrap().id(), variant.id());
+        assert_eq!(fnv1a([b"a".as_slice()]), 0xaf63_dc4c_8601_ec8c);
+
+        let ids: std::collections::HashSet<&str> =
+            (0..100).map(|call| set.select(&format!("CA{:032x}", call)).unwrap().id()).collect();
+        assert_eq!(ids.len(), 3);
+        assert!(VariantSet::new("empty").select("CA1").is_none());
+    }
+
+    #[test]
+    fn test_variant_prompt() {
+        let set = VariantSet::new("hold").play("music", "https://example.com/hold.mp3");
+        let variant = set.select("CA1").unwrap();
+        assert_eq!(variant.id(), "music");
+        assert!(variant.add_to(Response::new()).to_xml_string().ends_with(
+            "<Response><Play>https://example.com/hold.mp3</Play></Response>"
+        ));
+    }
+}
@@ -0,0 +1,455 @@
+use std::any::Any;
+use std::iter::FusedIterator;
+use std::slice;
+
+use crate::twiml::*;
+
+/// Typed reference to a child element
+#[derive(Debug, Clone, Copy)]
+pub enum Verb<'a> {
+    #[cfg(feature = "voice")]
+    Say(&'a Say),
+    #[cfg(feature = "voice")]
+    Play(&'a Play),
+    #[cfg(feature = "voice")]
+    Pause(&'a Pause),
+    #[cfg(feature = "voice")]
+    Gather(&'a Gather),
+    #[cfg(feature = "pay")]
+    Prompt(&'a Prompt),
+    Redirect(&'a Redirect),
+    #[cfg(feature = "voice")]
+    Hangup(&'a Hangup),
+    #[cfg(feature = "voice")]
+    Reject(&'a Reject),
+    #[cfg(feature = "voice")]
+    Record(&'a Record),
+    #[cfg(feature = "voice")]
+    Dial(&'a Dial),
+    #[cfg(feature = "voice")]
+    Number(&'a Number),
+    #[cfg(feature = "voice")]
+    Client(&'a Client),
+    #[cfg(feature = "voice")]
+    Application(&'a Application),
+    #[cfg(feature = "voice")]
+    Conference(&'a Conference),
+    #[cfg(feature = "voice")]
+    Sip(&'a Sip),
+    #[cfg(feature = "voice")]
+    Sms(&'a Sms),
+    #[cfg(feature = "messaging")]
+    Message(&'a Message),
+    #[cfg(feature = "messaging")]
+    Body(&'a Body),
+    #[cfg(feature = "messaging")]
+    Media(&'a Media),
+    #[cfg(feature = "voice")]
+    Enqueue(&'a Enqueue),
+    #[cfg(feature = "voice")]
+    Task(&'a Task),
+    #[cfg(feature = "voice")]
+    Leave(&'a Leave),
+    #[cfg(feature = "pay")]
+    Pay(&'a Pay),
+    #[cfg(feature = "voice")]
+    Denoise(&'a Denoise),
+    #[cfg(feature = "voice")]
+    Connect(&'a Connect),
+    #[cfg(feature = "voice")]
+    ConversationRelay(&'a ConversationRelay),
+    #[cfg(feature = "voice")]
+    Language(&'a Language),
+    #[cfg(feature = "voice")]
+    Parameter(&'a Parameter),
+    #[cfg(feature = "voice")]
+    VirtualAgent(&'a VirtualAgent),
+    #[cfg(feature = "voice")]
+    Config(&'a Config),
+    #[cfg(feature = "voice")]
+    Stream(&'a Stream),
+    #[cfg(feature = "voice")]
+    Room(&'a Room),
+    #[cfg(feature = "voice")]
+    Conversation(&'a Conversation),
+    #[cfg(feature = "fax")]
+    Receive(&'a Receive),
+    /// Element of a type not listed above, such as a raw [`ElementFactory`]
+    Other(&'a dyn TwiMLElement),
+}
+
+impl<'a> Verb<'a> {
+    /// Classify an element by its concrete type, looking through [`Shared`] fragments
+    pub fn from_element(element: &'a dyn TwiMLElement) -> Self {
+        let any: &'a dyn Any = element;
+        if let Some(shared) = any.downcast_ref::<Shared>() {
+            return Self::from_element(shared.inner());
+        }
+        #[cfg(feature = "voice")]
+        if let Some(say) = any.downcast_ref() {
+            return Verb::Say(say);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(play) = any.downcast_ref() {
+            return Verb::Play(play);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(pause) = any.downcast_ref() {
+            return Verb::Pause(pause);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(gather) = any.downcast_ref() {
+            return Verb::Gather(gather);
+        }
+        #[cfg(feature = "pay")]
+        if let Some(prompt) = any.downcast_ref() {
+            return Verb::Prompt(prompt);
+        }
+        if let Some(redirect) = any.downcast_ref() {
+            return Verb::Redirect(redirect);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(hangup) = any.downcast_ref() {
+            return Verb::Hangup(hangup);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(reject) = any.downcast_ref() {
+            return Verb::Reject(reject);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(record) = any.downcast_ref() {
+            return Verb::Record(record);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(dial) = any.downcast_ref() {
+            return Verb::Dial(dial);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(number) = any.downcast_ref() {
+            return Verb::Number(number);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(client) = any.downcast_ref() {
+            return Verb::Client(client);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(application) = any.downcast_ref() {
+            return Verb::Application(application);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(conference) = any.downcast_ref() {
+            return Verb::Conference(conference);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(sip) = any.downcast_ref() {
+            return Verb::Sip(sip);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(sms) = any.downcast_ref() {
+            return Verb::Sms(sms);
+        }
+        #[cfg(feature = "messaging")]
+        if let Some(message) = any.downcast_ref() {
+            return Verb::Message(message);
+        }
+        #[cfg(feature = "messaging")]
+        if let Some(body) = any.downcast_ref() {
+            return Verb::Body(body);
+        }
+        #[cfg(feature = "messaging")]
+        if let Some(media) = any.downcast_ref() {
+            return Verb::Media(media);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(enqueue) = any.downcast_ref() {
+            return Verb::Enqueue(enqueue);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(task) = any.downcast_ref() {
+            return Verb::Task(task);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(leave) = any.downcast_ref() {
+            return Verb::Leave(leave);
+        }
+        #[cfg(feature = "pay")]
+        if let Some(pay) = any.downcast_ref() {
+            return Verb::Pay(pay);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(denoise) = any.downcast_ref() {
+            return Verb::Denoise(denoise);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(connect) = any.downcast_ref() {
+            return Verb::Connect(connect);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(relay) = any.downcast_ref() {
+            return Verb::ConversationRelay(relay);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(language) = any.downcast_ref() {
+            return Verb::Language(language);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(parameter) = any.downcast_ref() {
+            return Verb::Parameter(parameter);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(agent) = any.downcast_ref() {
+            return Verb::VirtualAgent(agent);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(config) = any.downcast_ref() {
+            return Verb::Config(config);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(stream) = any.downcast_ref() {
+            return Verb::Stream(stream);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(room) = any.downcast_ref() {
+            return Verb::Room(room);
+        }
+        #[cfg(feature = "voice")]
+        if let Some(conversation) = any.downcast_ref() {
+            return Verb::Conversation(conversation);
+        }
+        #[cfg(feature = "fax")]
+        if let Some(receive) = any.downcast_ref() {
+            return Verb::Receive(receive);
+        }
+        Verb::Other(element)
+    }
+
+    /// Get the element as a trait object
+    pub fn element(&self) -> &'a dyn TwiMLElement {
+        match *self {
+            #[cfg(feature = "voice")]
+            Verb::Say(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::Play(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::Pause(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::Gather(element) => element,
+            #[cfg(feature = "pay")]
+            Verb::Prompt(element) => element,
+            Verb::Redirect(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::Hangup(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::Reject(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::Record(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::Dial(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::Number(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::Client(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::Application(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::Conference(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::Sip(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::Sms(element) => element,
+            #[cfg(feature = "messaging")]
+            Verb::Message(element) => element,
+            #[cfg(feature = "messaging")]
+            Verb::Body(element) => element,
+            #[cfg(feature = "messaging")]
+            Verb::Media(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::Enqueue(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::Task(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::Leave(element) => element,
+            #[cfg(feature = "pay")]
+            Verb::Pay(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::Denoise(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::Connect(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::ConversationRelay(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::Language(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::Parameter(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::VirtualAgent(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::Config(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::Stream(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::Room(element) => element,
+            #[cfg(feature = "voice")]
+            Verb::Conversation(element) => element,
+            #[cfg(feature = "fax")]
+            Verb::Receive(element) => element,
+            Verb::Other(element) => element,
+        }
+    }
+
+    /// Get the element name
+    pub fn name(&self) -> &'a str {
+        self.element().factory().name()
+    }
+}
+
+/// Iterator over the children of an element as [`Verb`]s
+#[derive(Debug, Clone)]
+pub struct Verbs<'a> {
+    children: slice::Iter<'a, Box<dyn TwiMLElement>>,
+}
+
+impl<'a> Verbs<'a> {
+    /// Iterate over the children of any element
+    pub fn new(element: &'a dyn TwiMLElement) -> Self {
+        Self {
+            children: element.factory().children().iter(),
+        }
+    }
+}
+
+impl<'a> Iterator for Verbs<'a> {
+    type Item = Verb<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.children.next().map(|child| Verb::from_element(child.as_ref()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.children.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Verbs<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.children.next_back().map(|child| Verb::from_element(child.as_ref()))
+    }
+}
+
+impl ExactSizeIterator for Verbs<'_> {}
+
+impl FusedIterator for Verbs<'_> {}
+
+impl Response {
+    /// Iterate over the verbs of the document
+    pub fn iter(&self) -> Verbs<'_> {
+        Verbs::new(self)
+    }
+}
+
+impl<'a> IntoIterator for &'a Response {
+    type Item = Verb<'a>;
+    type IntoIter = Verbs<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(feature = "voice")]
+impl Gather {
+    /// Iterate over the nested verbs
+    pub fn iter(&self) -> Verbs<'_> {
+        Verbs::new(self)
+    }
+}
+
+#[cfg(feature = "voice")]
+impl<'a> IntoIterator for &'a Gather {
+    type Item = Verb<'a>;
+    type IntoIter = Verbs<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(feature = "voice")]
+impl Dial {
+    /// Iterate over the nested nouns
+    pub fn iter(&self) -> Verbs<'_> {
+        Verbs::new(self)
+    }
+}
+
+#[cfg(feature = "voice")]
+impl<'a> IntoIterator for &'a Dial {
+    type Item = Verb<'a>;
+    type IntoIter = Verbs<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(feature = "messaging")]
+impl Message {
+    /// Iterate over the Body and Media children
+    pub fn iter(&self) -> Verbs<'_> {
+        Verbs::new(self)
+    }
+}
+
+#[cfg(feature = "messaging")]
+impl<'a> IntoIterator for &'a Message {
+    type Item = Verb<'a>;
+    type IntoIter = Verbs<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "voice")]
+    #[test]
+    fn test_iterate_verbs() {
+        let response = Response::new()
+            .say("Hello")
+            .gather(Gather::new().say("Press 1").pause(Pause::new()))
+            .hangup();
+
+        let names: Vec<&str> = response.iter().map(|verb| verb.name()).collect();
+        assert_eq!(names, vec!["Say", "Gather", "Hangup"]);
+
+        for verb in &response {
+            match verb {
+                Verb::Say(say) => assert_eq!(say.factory().text(), Some("Hello")),
+                Verb::Gather(gather) => assert_eq!(gather.iter().len(), 2),
+                Verb::Hangup(_) => {}
+                other => panic!("unexpected verb {:?}", other),
+            }
+        }
+    }
+
+    #[cfg(feature = "voice")]
+    #[test]
+    fn test_shared_verbs() {
+        let disclaimer = Shared::new(Say::new("Calls are recorded"));
+        let response = Response::new().shared(&disclaimer).shared(&Shared::new(Shared::new(Hangup::new())));
+        let mut verbs = response.iter();
+        assert!(matches!(verbs.next(), Some(Verb::Say(say)) if say.factory().text() == Some("Calls are recorded")));
+        assert!(matches!(verbs.next(), Some(Verb::Hangup(_))));
+    }
+
+    #[test]
+    fn test_raw_elements_are_other() {
+        let mut response = Response::new();
+        response.insert_child_at(0, ElementFactory::new("Custom", None::<String>));
+
+        assert!(matches!(response.iter().next(), Some(Verb::Other(element)) if element.factory().name() == "Custom"));
+    }
+}
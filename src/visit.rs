@@ -0,0 +1,165 @@
+use std::fmt;
+
+use crate::twiml::{Response, TwiMLElement};
+
+/// One step of an [`ElementPath`]: an element name and its position among its siblings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathSegment<'a> {
+    /// Element name
+    pub name: &'a str,
+    /// Index of the element among its parent's children
+    pub index: usize,
+}
+
+/// Location of an element in a document, starting at the root
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ElementPath<'a> {
+    segments: Vec<PathSegment<'a>>,
+}
+
+impl<'a> ElementPath<'a> {
+    /// Get the segments from the root to the element
+    pub fn segments(&self) -> &[PathSegment<'a>] {
+        &self.segments
+    }
+
+    /// Number of ancestors of the element; the root has depth 0
+    pub fn depth(&self) -> usize {
+        self.segments.len().saturating_sub(1)
+    }
+
+    /// Iterate over the element names from the root to the element
+    pub fn names(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.segments.iter().map(|segment| segment.name)
+    }
+
+    /// Name of the parent element, if any
+    pub fn parent(&self) -> Option<&'a str> {
+        self.segments.len().checked_sub(2).map(|index| self.segments[index].name)
+    }
+}
+
+impl fmt::Display for ElementPath<'_> {
+    /// Format as `Response/Gather[1]/Say[0]`, omitting the root index
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (position, segment) in self.segments.iter().enumerate() {
+            if position == 0 {
+                f.write_str(segment.name)?;
+            } else {
+                write!(f, "/{}[{}]", segment.name, segment.index)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Callbacks invoked while walking a TwiML tree depth-first
+pub trait Visitor {
+    /// Called for an element before any of its children
+    fn enter(&mut self, _element: &dyn TwiMLElement, _path: &ElementPath<'_>) {}
+
+    /// Called for an element after all of its children
+    fn leave(&mut self, _element: &dyn TwiMLElement, _path: &ElementPath<'_>) {}
+}
+
+/// Closures receive each element as it is entered
+impl<F: FnMut(&dyn TwiMLElement, &ElementPath<'_>)> Visitor for F {
+    fn enter(&mut self, element: &dyn TwiMLElement, path: &ElementPath<'_>) {
+        self(element, path)
+    }
+}
+
+/// Walk an element and all of its descendants depth-first
+pub fn walk(element: &dyn TwiMLElement, visitor: &mut (impl Visitor + ?Sized)) {
+    let mut path = ElementPath::default();
+    walk_at(element, 0, &mut path, visitor);
+}
+
+fn walk_at<'a>(
+    element: &'a dyn TwiMLElement,
+    index: usize,
+    path: &mut ElementPath<'a>,
+    visitor: &mut (impl Visitor + ?Sized),
+) {
+    let node = element.factory();
+    path.segments.push(PathSegment { name: node.name(), index });
+
+    visitor.enter(element, path);
+    for (index, child) in node.children().iter().enumerate() {
+        walk_at(child.as_ref(), index, path, visitor);
+    }
+    visitor.leave(element, path);
+
+    path.segments.pop();
+}
+
+impl Response {
+    /// Walk every element of the document depth-first, starting at the root
+    pub fn walk(&self, visitor: &mut (impl Visitor + ?Sized)) {
+        walk(self, visitor)
+    }
+}
+
+#[cfg(all(test, feature = "voice"))]
+mod tests {
+    use super::*;
+    use crate::twiml::*;
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Vec<String>,
+    }
+
+    impl Visitor for Recorder {
+        fn enter(&mut self, _element: &dyn TwiMLElement, path: &ElementPath<'_>) {
+            self.events.push(format!("enter {}", path));
+        }
+
+        fn leave(&mut self, element: &dyn TwiMLElement, _path: &ElementPath<'_>) {
+            self.events.push(format!("leave {}", element.factory().name()));
+        }
+    }
+
+    #[test]
+    fn test_walk_order_and_paths() {
+        let response = Response::new()
+            .say(Say::new("Hi"))
+            .gather(Gather::new().say(Say::new("Press 1")).pause(Pause::new()));
+
+        let mut recorder = Recorder::default();
+        response.walk(&mut recorder);
+
+        assert_eq!(
+            recorder.events,
+            vec![
+                "enter Response",
+                "enter Response/Say[0]",
+                "leave Say",
+                "enter Response/Gather[1]",
+                "enter Response/Gather[1]/Say[0]",
+                "leave Say",
+                "enter Response/Gather[1]/Pause[1]",
+                "leave Pause",
+                "leave Gather",
+                "leave Response",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_closure_visitor() {
+        let response = Response::new().gather(Gather::new().action("/a")).record(Record::new().action("/b"));
+
+        let mut actions = Vec::new();
+        response.walk(&mut |element: &dyn TwiMLElement, path: &ElementPath<'_>| {
+            if let Some(action) = element.factory().attribute("action") {
+                actions.push((path.depth(), path.parent().unwrap().to_string(), action.to_string()));
+            }
+        });
+
+        assert_eq!(
+            actions,
+            vec![(1, "Response".to_string(), "/a".to_string()), (1, "Response".to_string(), "/b".to_string())]
+        );
+    }
+}
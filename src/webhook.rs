@@ -0,0 +1,846 @@
+//! Typed parameters of the requests Twilio sends to webhooks
+//!
+//! Enum values parse case-insensitively, so handlers can match on them instead of
+//! comparing raw strings and missing a spelling.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+use crate::error::TwiMLError;
+#[cfg(feature = "voice")]
+use crate::twiml::{EventSet, Sip};
+
+/// Prefix of the parameters carrying SIP headers of a SIP-originated call
+pub const SIP_HEADER_PREFIX: &str = "SipHeader_";
+
+/// Find a form parameter by name
+fn param<'a, K: AsRef<str>, V: AsRef<str>>(params: &'a [(K, V)], name: &str) -> Option<&'a str> {
+    params.iter().find(|(key, _)| key.as_ref() == name).map(|(_, value)| value.as_ref())
+}
+
+/// Find a required form parameter by name
+fn required<K: AsRef<str>, V: AsRef<str>>(params: &[(K, V)], name: &str) -> Result<String, TwiMLError> {
+    param(params, name)
+        .map(str::to_string)
+        .ok_or_else(|| TwiMLError::Parse(format!("webhook request has no `{}` parameter", name)))
+}
+
+/// Find an optional, non-empty form parameter by name
+fn optional<K: AsRef<str>, V: AsRef<str>>(params: &[(K, V)], name: &str) -> Option<String> {
+    param(params, name).filter(|value| !value.is_empty()).map(str::to_string)
+}
+
+/// Define an enum of webhook parameter values with `as_str`, `Display` and a
+/// case-insensitive `FromStr`
+macro_rules! webhook_enum {
+    (
+        $(#[$meta:meta])*
+        $name:ident, $description:literal {
+            $($(#[$variant_meta:meta])* $variant:ident => $value:literal,)+
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)+
+        }
+
+        impl $name {
+            /// Get the parameter value Twilio sends
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $value,)+
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = TwiMLError;
+
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                let value = value.trim();
+                $(if value.eq_ignore_ascii_case($value) {
+                    return Ok($name::$variant);
+                })+
+                Err(TwiMLError::Parse(format!("`{}` is not {}", value, $description)))
+            }
+        }
+    };
+}
+
+webhook_enum! {
+    /// State of a call, sent as `CallStatus` and, for the dialed leg, `DialCallStatus`
+    CallStatus, "a call status" {
+        /// Waiting to be dialed (`queued`)
+        Queued => "queued",
+        /// Ringing at the far end (`ringing`)
+        Ringing => "ringing",
+        /// Answered and connected (`in-progress`)
+        InProgress => "in-progress",
+        /// Ended normally after being answered (`completed`)
+        Completed => "completed",
+        /// The far end was busy (`busy`)
+        Busy => "busy",
+        /// The call could not be placed (`failed`)
+        Failed => "failed",
+        /// Nobody answered (`no-answer`)
+        NoAnswer => "no-answer",
+        /// Hung up while queued or ringing (`canceled`)
+        Canceled => "canceled",
+    }
+}
+
+impl CallStatus {
+    /// Whether the call has ended
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            CallStatus::Completed | CallStatus::Busy | CallStatus::Failed | CallStatus::NoAnswer | CallStatus::Canceled
+        )
+    }
+
+    /// Read the `CallStatus` field from webhook form parameters
+    pub fn from_params<K: AsRef<str>, V: AsRef<str>>(params: &[(K, V)]) -> Option<Self> {
+        param(params, "CallStatus").and_then(|value| value.parse().ok())
+    }
+
+    /// Read the `DialCallStatus` field sent to a Dial `action` URL
+    pub fn from_dial_params<K: AsRef<str>, V: AsRef<str>>(params: &[(K, V)]) -> Option<Self> {
+        param(params, "DialCallStatus").and_then(|value| value.parse().ok())
+    }
+}
+
+webhook_enum! {
+    /// Direction of a call or message, sent as `Direction`
+    Direction, "a call or message direction" {
+        /// Received from outside (`inbound`)
+        Inbound => "inbound",
+        /// Created with the REST API (`outbound-api`)
+        OutboundApi => "outbound-api",
+        /// Created by a Dial verb (`outbound-dial`)
+        OutboundDial => "outbound-dial",
+        /// Message sent by a call's TwiML (`outbound-call`)
+        OutboundCall => "outbound-call",
+        /// Message sent in reply to an incoming message (`outbound-reply`)
+        OutboundReply => "outbound-reply",
+    }
+}
+
+impl Direction {
+    /// Whether the call or message was received rather than sent
+    pub fn is_inbound(&self) -> bool {
+        *self == Direction::Inbound
+    }
+
+    /// Read the `Direction` field from webhook form parameters
+    pub fn from_params<K: AsRef<str>, V: AsRef<str>>(params: &[(K, V)]) -> Option<Self> {
+        param(params, "Direction").and_then(|value| value.parse().ok())
+    }
+}
+
+webhook_enum! {
+    /// State of a message, sent as `MessageStatus` (or the older `SmsStatus`)
+    MessageStatus, "a message status" {
+        /// Accepted by a Messaging Service, awaiting a sender (`accepted`)
+        Accepted => "accepted",
+        /// Scheduled to be sent later (`scheduled`)
+        Scheduled => "scheduled",
+        /// A scheduled message was canceled (`canceled`)
+        Canceled => "canceled",
+        /// Waiting to be sent (`queued`)
+        Queued => "queued",
+        /// Being sent to the carrier (`sending`)
+        Sending => "sending",
+        /// Accepted by the carrier (`sent`)
+        Sent => "sent",
+        /// Could not be sent (`failed`)
+        Failed => "failed",
+        /// Confirmed delivered to the handset (`delivered`)
+        Delivered => "delivered",
+        /// The carrier reported it undelivered (`undelivered`)
+        Undelivered => "undelivered",
+        /// Inbound message being received (`receiving`)
+        Receiving => "receiving",
+        /// Inbound message received (`received`)
+        Received => "received",
+        /// Read by the recipient, on channels that report it (`read`)
+        Read => "read",
+        /// Only some parts were delivered (`partially_delivered`)
+        PartiallyDelivered => "partially_delivered",
+    }
+}
+
+impl MessageStatus {
+    /// Whether the message has reached a state it won't leave
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            MessageStatus::Canceled
+                | MessageStatus::Failed
+                | MessageStatus::Delivered
+                | MessageStatus::Undelivered
+                | MessageStatus::Received
+                | MessageStatus::Read
+                | MessageStatus::PartiallyDelivered
+        )
+    }
+
+    /// Read the `MessageStatus` field, or `SmsStatus` if absent, from webhook form parameters
+    pub fn from_params<K: AsRef<str>, V: AsRef<str>>(params: &[(K, V)]) -> Option<Self> {
+        param(params, "MessageStatus")
+            .or_else(|| param(params, "SmsStatus"))
+            .and_then(|value| value.parse().ok())
+    }
+}
+
+webhook_enum! {
+    /// How a call left a queue, sent to an Enqueue `action` URL as `QueueResult`
+    QueueResult, "a queue result" {
+        /// Connected to a Dial on the queue (`bridged`)
+        Bridged => "bridged",
+        /// Being connected when the caller hung up (`bridging-in-process`)
+        BridgingInProcess => "bridging-in-process",
+        /// The queue had reached its maximum size (`queue-full`)
+        QueueFull => "queue-full",
+        /// Redirected out of the queue with the REST API (`redirected`)
+        Redirected => "redirected",
+        /// Left with a Leave verb in the wait-URL document (`leave`)
+        Leave => "leave",
+        /// The caller hung up while waiting (`hangup`)
+        Hangup => "hangup",
+        /// The wait-URL document was invalid (`error`)
+        Error => "error",
+        /// Twilio could not queue the call (`system-error`)
+        SystemError => "system-error",
+    }
+}
+
+impl QueueResult {
+    /// Read the `QueueResult` field from webhook form parameters
+    pub fn from_params<K: AsRef<str>, V: AsRef<str>>(params: &[(K, V)]) -> Option<Self> {
+        param(params, "QueueResult").and_then(|value| value.parse().ok())
+    }
+}
+
+/// Parameters of a request to an Enqueue `waitUrl`, made while the caller waits
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WaitUrlRequest {
+    /// Waiting call (`CallSid`)
+    pub call_sid: String,
+    /// Queue the call is waiting in (`QueueSid`)
+    pub queue_sid: String,
+    /// Caller's position in the queue, starting at 1 (`QueuePosition`)
+    pub position: usize,
+    /// Number of calls in the queue (`CurrentQueueSize`)
+    pub queue_size: usize,
+    /// Largest number of calls the queue accepts (`MaxQueueSize`)
+    pub max_queue_size: usize,
+    /// Seconds this caller has waited (`QueueTime`)
+    pub queue_time: u64,
+    /// Average seconds calls have waited in this queue (`AvgQueueTime`)
+    pub average_queue_time: u64,
+}
+
+impl WaitUrlRequest {
+    /// Read the request from webhook form parameters, treating missing or malformed
+    /// numbers as zero
+    pub fn from_params<K: AsRef<str>, V: AsRef<str>>(params: &[(K, V)]) -> Result<Self, TwiMLError> {
+        fn number<T: FromStr + Default>(value: Option<&str>) -> T {
+            value.and_then(|value| value.trim().parse().ok()).unwrap_or_default()
+        }
+        Ok(Self {
+            call_sid: required(params, "CallSid")?,
+            queue_sid: required(params, "QueueSid")?,
+            position: number(param(params, "QueuePosition")),
+            queue_size: number(param(params, "CurrentQueueSize")),
+            max_queue_size: number(param(params, "MaxQueueSize")),
+            queue_time: number(param(params, "QueueTime")),
+            average_queue_time: number(param(params, "AvgQueueTime")),
+        })
+    }
+
+    /// Estimate the seconds this caller has left to wait, if the queue has any history
+    pub fn estimated_wait(&self) -> Option<u64> {
+        (self.average_queue_time > 0).then(|| self.average_queue_time.saturating_sub(self.queue_time))
+    }
+}
+
+/// Where a phone number is registered, as far as Twilio can tell
+///
+/// Twilio fills these in from the number's area code or prefix, so they describe the
+/// number rather than where the caller is, and are often missing for mobile and
+/// international numbers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Location {
+    /// City (`FromCity` or `ToCity`)
+    pub city: Option<String>,
+    /// State or province (`FromState` or `ToState`)
+    pub state: Option<String>,
+    /// Postal code (`FromZip` or `ToZip`)
+    pub zip: Option<String>,
+    /// ISO 3166-1 alpha-2 country code (`FromCountry` or `ToCountry`)
+    pub country: Option<String>,
+}
+
+impl Location {
+    /// Read the location fields starting with `prefix`, which is `From` or `To`
+    fn from_params<K: AsRef<str>, V: AsRef<str>>(params: &[(K, V)], prefix: &str) -> Self {
+        let field = |name: &str| optional(params, &format!("{}{}", prefix, name));
+        Self {
+            city: field("City"),
+            state: field("State"),
+            zip: field("Zip"),
+            country: field("Country"),
+        }
+    }
+
+    /// Whether Twilio sent none of the fields
+    pub fn is_empty(&self) -> bool {
+        *self == Location::default()
+    }
+}
+
+/// Parameters of a request to a voice webhook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VoiceRequest {
+    /// Unique ID of the call (`CallSid`)
+    pub call_sid: String,
+    /// Account the call belongs to (`AccountSid`)
+    pub account_sid: String,
+    /// Caller's number, client or SIP address (`From`)
+    pub from: String,
+    /// Called number, client or SIP address (`To`)
+    pub to: String,
+    /// State of the call (`CallStatus`)
+    pub call_status: Option<CallStatus>,
+    /// Direction of the call (`Direction`)
+    pub direction: Option<Direction>,
+    /// Caller ID name, when CNAM lookup is enabled on the number (`CallerName`)
+    pub caller_name: Option<String>,
+    /// Number that forwarded the call, when the carrier reports it (`ForwardedFrom`)
+    pub forwarded_from: Option<String>,
+    /// Where the caller's number is registered (`FromCity`, `FromState`, ...)
+    pub from_location: Location,
+    /// Where the called number is registered (`ToCity`, `ToState`, ...)
+    pub to_location: Location,
+    /// Custom `X-` headers of a SIP-originated call, by header name (`SipHeader_X-...`)
+    pub sip_headers: HashMap<String, String>,
+}
+
+impl VoiceRequest {
+    /// Read the request from webhook form parameters
+    pub fn from_params<K: AsRef<str>, V: AsRef<str>>(params: &[(K, V)]) -> Result<Self, TwiMLError> {
+        Ok(Self {
+            call_sid: required(params, "CallSid")?,
+            account_sid: required(params, "AccountSid")?,
+            from: required(params, "From")?,
+            to: required(params, "To")?,
+            call_status: CallStatus::from_params(params),
+            direction: Direction::from_params(params),
+            caller_name: optional(params, "CallerName"),
+            forwarded_from: optional(params, "ForwardedFrom"),
+            from_location: Location::from_params(params, "From"),
+            to_location: Location::from_params(params, "To"),
+            sip_headers: params
+                .iter()
+                .filter_map(|(key, value)| {
+                    let name = key.as_ref().strip_prefix(SIP_HEADER_PREFIX)?;
+                    Some((name.to_string(), value.as_ref().to_string()))
+                })
+                .collect(),
+        })
+    }
+
+    /// Get a SIP header by name, ignoring case as SIP does
+    pub fn sip_header(&self, name: &str) -> Option<&str> {
+        self.sip_headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Copy the named SIP headers of this call, when present, onto an outgoing `Sip` noun
+    #[cfg(feature = "voice")]
+    pub fn echo_sip_headers(&self, sip: Sip, names: &[&str]) -> Sip {
+        names.iter().fold(sip, |sip, &name| match self.sip_header(name) {
+            Some(value) => sip.header(name, value),
+            None => sip,
+        })
+    }
+}
+
+/// One recognition hypothesis for the caller's speech
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeechAlternative {
+    /// Recognized text
+    pub transcript: String,
+    /// Recognizer confidence between 0 and 1, when reported
+    pub confidence: Option<f32>,
+}
+
+/// Parameters Twilio sends to a Gather `action` URL
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GatherResult {
+    /// Keys the caller pressed (`Digits`)
+    pub digits: Option<String>,
+    /// Speech hypotheses, most likely first
+    ///
+    /// Twilio currently reports only its top hypothesis, as `SpeechResult` and
+    /// `Confidence`, so this holds at most one entry.
+    pub alternatives: Vec<SpeechAlternative>,
+}
+
+impl GatherResult {
+    /// Read the result from webhook form parameters
+    pub fn from_params<K: AsRef<str>, V: AsRef<str>>(params: &[(K, V)]) -> Self {
+        let alternatives = optional(params, "SpeechResult")
+            .map(|transcript| SpeechAlternative {
+                transcript,
+                confidence: param(params, "Confidence").and_then(|confidence| confidence.trim().parse().ok()),
+            })
+            .into_iter()
+            .collect();
+        Self {
+            digits: optional(params, "Digits"),
+            alternatives,
+        }
+    }
+
+    /// Get the most likely speech hypothesis
+    pub fn speech(&self) -> Option<&SpeechAlternative> {
+        self.alternatives.first()
+    }
+
+    /// Get the most likely hypothesis with a confidence of at least `threshold`
+    ///
+    /// Returns `None` when nothing was recognized confidently enough, which is the cue
+    /// to re-prompt. Hypotheses without a reported confidence never qualify.
+    pub fn best_above(&self, threshold: f32) -> Option<&SpeechAlternative> {
+        self.alternatives
+            .iter()
+            .find(|alternative| alternative.confidence.is_some_and(|confidence| confidence >= threshold))
+    }
+}
+
+webhook_enum! {
+    /// Conference lifecycle event, sent as `StatusCallbackEvent` to a Conference `statusCallback`
+    ConferenceEvent, "a conference event" {
+        /// The conference started (`conference-start`)
+        ConferenceStart => "conference-start",
+        /// The conference ended (`conference-end`)
+        ConferenceEnd => "conference-end",
+        /// A participant joined (`participant-join`)
+        ParticipantJoin => "participant-join",
+        /// A participant left (`participant-leave`)
+        ParticipantLeave => "participant-leave",
+        /// A participant was muted (`participant-mute`)
+        ParticipantMute => "participant-mute",
+        /// A participant was unmuted (`participant-unmute`)
+        ParticipantUnmute => "participant-unmute",
+        /// A participant was put on hold (`participant-hold`)
+        ParticipantHold => "participant-hold",
+        /// A participant was taken off hold (`participant-unhold`)
+        ParticipantUnhold => "participant-unhold",
+        /// A participant's settings changed (`participant-modify`)
+        ParticipantModify => "participant-modify",
+        /// A participant started speaking (`participant-speech-start`)
+        ParticipantSpeechStart => "participant-speech-start",
+        /// A participant stopped speaking (`participant-speech-stop`)
+        ParticipantSpeechStop => "participant-speech-stop",
+        /// An announcement finished playing (`announcement-end`)
+        AnnouncementEnd => "announcement-end",
+        /// An announcement could not be played (`announcement-fail`)
+        AnnouncementFail => "announcement-fail",
+    }
+}
+
+impl ConferenceEvent {
+    /// Get the `statusCallbackEvent` value that subscribes to this event
+    #[cfg(feature = "voice")]
+    pub fn event_set(&self) -> EventSet {
+        match self {
+            ConferenceEvent::ConferenceStart => EventSet::START,
+            ConferenceEvent::ConferenceEnd => EventSet::END,
+            ConferenceEvent::ParticipantJoin => EventSet::JOIN,
+            ConferenceEvent::ParticipantLeave => EventSet::LEAVE,
+            ConferenceEvent::ParticipantMute | ConferenceEvent::ParticipantUnmute => EventSet::MUTE,
+            ConferenceEvent::ParticipantHold | ConferenceEvent::ParticipantUnhold => EventSet::HOLD,
+            ConferenceEvent::ParticipantModify => EventSet::MODIFY,
+            ConferenceEvent::ParticipantSpeechStart | ConferenceEvent::ParticipantSpeechStop => EventSet::SPEAKER,
+            ConferenceEvent::AnnouncementEnd | ConferenceEvent::AnnouncementFail => EventSet::ANNOUNCEMENT,
+        }
+    }
+}
+
+/// Parameters of a request to a Conference `statusCallback`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConferenceStatusCallback {
+    /// Conference the event belongs to (`ConferenceSid`)
+    pub conference_sid: String,
+    /// Name the conference was created with in TwiML (`FriendlyName`)
+    pub friendly_name: String,
+    /// Account the conference belongs to (`AccountSid`)
+    pub account_sid: String,
+    /// What happened (`StatusCallbackEvent`)
+    pub event: ConferenceEvent,
+    /// Order of the event among this conference's events (`SequenceNumber`)
+    pub sequence_number: Option<u64>,
+    /// Participant's call, for participant events (`CallSid`)
+    pub call_sid: Option<String>,
+    /// Label the participant joined with, such as `agent` or `supervisor` from the
+    /// conference presets (`ParticipantLabel`)
+    pub participant_label: Option<String>,
+    /// Whether the participant is muted (`Muted`)
+    pub muted: Option<bool>,
+    /// Whether the participant is on hold (`Hold`)
+    pub hold: Option<bool>,
+    /// Whether the participant is coaching another (`Coaching`)
+    pub coaching: Option<bool>,
+    /// Whether the participant leaving ends the conference (`EndConferenceOnExit`)
+    pub end_conference_on_exit: Option<bool>,
+    /// Whether the participant joining starts the conference (`StartConferenceOnEnter`)
+    pub start_conference_on_enter: Option<bool>,
+    /// Why the conference ended or the participant left, when given (`Reason`)
+    pub reason: Option<String>,
+}
+
+impl ConferenceStatusCallback {
+    /// Read the callback from webhook form parameters
+    pub fn from_params<K: AsRef<str>, V: AsRef<str>>(params: &[(K, V)]) -> Result<Self, TwiMLError> {
+        let flag = |name: &str| param(params, name).and_then(|value| value.trim().to_ascii_lowercase().parse().ok());
+        Ok(Self {
+            conference_sid: required(params, "ConferenceSid")?,
+            friendly_name: required(params, "FriendlyName")?,
+            account_sid: required(params, "AccountSid")?,
+            event: required(params, "StatusCallbackEvent")?.parse()?,
+            sequence_number: param(params, "SequenceNumber").and_then(|number| number.trim().parse().ok()),
+            call_sid: optional(params, "CallSid"),
+            participant_label: optional(params, "ParticipantLabel"),
+            muted: flag("Muted"),
+            hold: flag("Hold"),
+            coaching: flag("Coaching"),
+            end_conference_on_exit: flag("EndConferenceOnExit"),
+            start_conference_on_enter: flag("StartConferenceOnEnter"),
+            reason: optional(params, "Reason"),
+        })
+    }
+}
+
+/// Parameters of a request to a Refer `action` URL, reporting how a SIP transfer went
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferResult {
+    /// Call that was transferred (`CallSid`)
+    pub call_sid: String,
+    /// Account the call belongs to (`AccountSid`)
+    pub account_sid: String,
+    /// Outcome of the transfer (`ReferCallStatus`)
+    pub refer_call_status: Option<CallStatus>,
+    /// SIP response code the far end returned to the REFER, such as 202 (`ReferSipResponseCode`)
+    pub refer_sip_response_code: Option<u16>,
+    /// SIP response code reported in the far end's NOTIFY, such as 200 (`NotifySipResponseCode`)
+    pub notify_sip_response_code: Option<u16>,
+}
+
+impl ReferResult {
+    /// Read the result from webhook form parameters
+    pub fn from_params<K: AsRef<str>, V: AsRef<str>>(params: &[(K, V)]) -> Result<Self, TwiMLError> {
+        let code = |name: &str| param(params, name).and_then(|code| code.trim().parse().ok());
+        Ok(Self {
+            call_sid: required(params, "CallSid")?,
+            account_sid: required(params, "AccountSid")?,
+            refer_call_status: param(params, "ReferCallStatus").and_then(|status| status.parse().ok()),
+            refer_sip_response_code: code("ReferSipResponseCode"),
+            notify_sip_response_code: code("NotifySipResponseCode"),
+        })
+    }
+
+    /// Whether the transfer completed, so Twilio's leg of the call can end
+    pub fn is_transferred(&self) -> bool {
+        self.refer_call_status == Some(CallStatus::Completed)
+    }
+}
+
+/// Media attachment of an inbound MMS
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaItem {
+    /// URL of the media on Twilio's API (`MediaUrl{N}`)
+    pub url: String,
+    /// MIME type of the media (`MediaContentType{N}`)
+    pub content_type: Option<String>,
+}
+
+impl MediaItem {
+    /// Read the first `count` attachments, skipping any without a URL
+    fn from_params<K: AsRef<str>, V: AsRef<str>>(params: &[(K, V)], count: usize) -> Vec<Self> {
+        (0..count)
+            .filter_map(|index| {
+                Some(Self {
+                    url: optional(params, &format!("MediaUrl{}", index))?,
+                    content_type: optional(params, &format!("MediaContentType{}", index)),
+                })
+            })
+            .collect()
+    }
+
+    /// Whether the media is an image, such as a photo sent from a phone
+    pub fn is_image(&self) -> bool {
+        self.content_type.as_deref().is_some_and(|content_type| content_type.starts_with("image/"))
+    }
+}
+
+/// Build the `Authorization` header value for fetching media from Twilio's API, which
+/// requires HTTP basic authentication when media access is protected
+///
+/// The crate doesn't download media itself; send a GET for [`MediaItem::url`] with this
+/// header from the application's HTTP client.
+pub fn basic_authorization(account_sid: &str, auth_token: &str) -> String {
+    format!("Basic {}", STANDARD.encode(format!("{}:{}", account_sid, auth_token)))
+}
+
+/// Parameters of a request to a messaging webhook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessagingRequest {
+    /// Unique ID of the message (`MessageSid`)
+    pub message_sid: String,
+    /// Account the message belongs to (`AccountSid`)
+    pub account_sid: String,
+    /// Sender's number or address (`From`)
+    pub from: String,
+    /// Recipient's number or address (`To`)
+    pub to: String,
+    /// Text of the message (`Body`)
+    pub body: String,
+    /// Number of media attachments (`NumMedia`)
+    pub num_media: usize,
+    /// Media attachments of an MMS (`MediaUrl{N}` and `MediaContentType{N}`)
+    pub media: Vec<MediaItem>,
+    /// State of the message (`MessageStatus` or `SmsStatus`)
+    pub message_status: Option<MessageStatus>,
+    /// Where the sender's number is registered (`FromCity`, `FromState`, ...)
+    pub from_location: Location,
+    /// Where the recipient's number is registered (`ToCity`, `ToState`, ...)
+    pub to_location: Location,
+}
+
+impl MessagingRequest {
+    /// Read the request from webhook form parameters
+    pub fn from_params<K: AsRef<str>, V: AsRef<str>>(params: &[(K, V)]) -> Result<Self, TwiMLError> {
+        let num_media = param(params, "NumMedia").and_then(|count| count.trim().parse().ok()).unwrap_or(0);
+        Ok(Self {
+            message_sid: required(params, "MessageSid")?,
+            account_sid: required(params, "AccountSid")?,
+            from: required(params, "From")?,
+            to: required(params, "To")?,
+            body: param(params, "Body").unwrap_or_default().to_string(),
+            num_media,
+            media: MediaItem::from_params(params, num_media),
+            message_status: MessageStatus::from_params(params),
+            from_location: Location::from_params(params, "From"),
+            to_location: Location::from_params(params, "To"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_call_status() {
+        assert_eq!("In-Progress".parse::<CallStatus>().unwrap(), CallStatus::InProgress);
+        assert_eq!(CallStatus::NoAnswer.to_string(), "no-answer");
+        assert!(CallStatus::Busy.is_final() && !CallStatus::Ringing.is_final());
+
+        let params = [("CallStatus", "completed"), ("DialCallStatus", "busy"), ("Direction", "outbound-api")];
+        assert_eq!(CallStatus::from_params(&params), Some(CallStatus::Completed));
+        assert_eq!(CallStatus::from_dial_params(&params), Some(CallStatus::Busy));
+        assert_eq!(Direction::from_params(&params), Some(Direction::OutboundApi));
+        assert_eq!(
+            "answered".parse::<CallStatus>(),
+            Err(TwiMLError::Parse("`answered` is not a call status".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_message_status() {
+        assert_eq!(MessageStatus::from_params(&[("SmsStatus", "RECEIVED")]), Some(MessageStatus::Received));
+        assert_eq!(MessageStatus::PartiallyDelivered.as_str(), "partially_delivered");
+        assert!(!MessageStatus::Sent.is_final());
+        assert!(MessageStatus::from_params(&[("MessageStatus", "lost")]).is_none());
+    }
+
+    #[test]
+    fn test_voice_request() {
+        let params = [
+            ("CallSid", "CA1"),
+            ("AccountSid", "AC1"),
+            ("From", "+14155550100"),
+            ("To", "+12125550199"),
+            ("CallStatus", "ringing"),
+            ("Direction", "inbound"),
+            ("CallerName", "JANE DOE"),
+            ("FromCity", "SAN FRANCISCO"),
+            ("FromState", "CA"),
+            ("FromZip", "94105"),
+            ("FromCountry", "US"),
+            ("ToCountry", "US"),
+            ("ToCity", ""),
+        ];
+        let request = VoiceRequest::from_params(&params).unwrap();
+        assert_eq!(request.call_status, Some(CallStatus::Ringing));
+        assert_eq!(request.caller_name.as_deref(), Some("JANE DOE"));
+        assert_eq!(request.from_location.state.as_deref(), Some("CA"));
+        assert_eq!(request.to_location, Location { country: Some("US".to_string()), ..Location::default() });
+        assert!(request.forwarded_from.is_none());
+
+        let error = VoiceRequest::from_params(&params[1..]).unwrap_err();
+        assert_eq!(error, TwiMLError::Parse("webhook request has no `CallSid` parameter".to_string()));
+    }
+
+    #[test]
+    fn test_refer_result() {
+        let params = [
+            ("CallSid", "CA1"),
+            ("AccountSid", "AC1"),
+            ("ReferCallStatus", "completed"),
+            ("ReferSipResponseCode", "202"),
+            ("NotifySipResponseCode", "200"),
+        ];
+        let result = ReferResult::from_params(&params).unwrap();
+        assert!(result.is_transferred());
+        assert_eq!((result.refer_sip_response_code, result.notify_sip_response_code), (Some(202), Some(200)));
+
+        let params = [("CallSid", "CA1"), ("AccountSid", "AC1"), ("ReferCallStatus", "failed"), ("ReferSipResponseCode", "603")];
+        let rejected = ReferResult::from_params(&params).unwrap();
+        assert!(!rejected.is_transferred());
+        assert_eq!(rejected.notify_sip_response_code, None);
+    }
+
+    #[test]
+    fn test_messaging_request() {
+        let params = [
+            ("MessageSid", "SM1"),
+            ("AccountSid", "AC1"),
+            ("From", "+14155550100"),
+            ("To", "+12125550199"),
+            ("Body", "STOP"),
+            ("NumMedia", "2"),
+            ("SmsStatus", "received"),
+        ];
+        let request = MessagingRequest::from_params(&params).unwrap();
+        assert_eq!((request.body.as_str(), request.num_media), ("STOP", 2));
+        assert_eq!(request.message_status, Some(MessageStatus::Received));
+        assert!(request.from_location.is_empty());
+        assert!(request.media.is_empty());
+    }
+
+    #[test]
+    fn test_inbound_media() {
+        let params = [
+            ("MessageSid", "MM1"),
+            ("AccountSid", "AC1"),
+            ("From", "+14155550100"),
+            ("To", "+12125550199"),
+            ("NumMedia", "2"),
+            ("MediaUrl0", "https://api.twilio.com/2010-04-01/Accounts/AC1/Messages/MM1/Media/ME0"),
+            ("MediaContentType0", "image/jpeg"),
+            ("MediaUrl1", "https://api.twilio.com/2010-04-01/Accounts/AC1/Messages/MM1/Media/ME1"),
+            ("MediaContentType1", "video/mp4"),
+            ("MediaUrl2", "https://example.com/ignored"),
+        ];
+        let request = MessagingRequest::from_params(&params).unwrap();
+        assert_eq!(request.media.len(), 2);
+        assert!(request.media[0].is_image() && !request.media[1].is_image());
+        assert!(request.media[1].url.ends_with("/Media/ME1"));
+        assert_eq!(basic_authorization("AC1", "secret"), "Basic QUMxOnNlY3JldA==");
+    }
+
+    #[cfg(feature = "voice")]
+    #[test]
+    fn test_sip_headers() {
+        use crate::twiml::ToXmlString;
+
+        let params = [
+            ("CallSid", "CA1"),
+            ("AccountSid", "AC1"),
+            ("From", "sip:alice@example.com"),
+            ("To", "sip:ivr@example.com"),
+            ("SipHeader_X-Customer-Id", "42"),
+            ("SipHeader_X-Trace", "a b&c"),
+        ];
+        let request = VoiceRequest::from_params(&params).unwrap();
+        assert_eq!(request.sip_headers.len(), 2);
+        assert_eq!(request.sip_header("x-customer-id"), Some("42"));
+
+        let sip = request.echo_sip_headers(Sip::new("sip:agent@example.com"), &["X-Trace", "X-Missing", "X-Customer-Id"]);
+        assert!(sip.to_xml_string().ends_with(
+            "<Sip>sip:agent@example.com?X-Trace=a%20b%26c&amp;X-Customer-Id=42</Sip>"
+        ));
+    }
+
+    #[test]
+    fn test_gather_result() {
+        let result = GatherResult::from_params(&[("SpeechResult", "billing"), ("Confidence", "0.62")]);
+        assert_eq!(result.speech().unwrap().transcript, "billing");
+        assert_eq!(result.best_above(0.5).unwrap().confidence, Some(0.62));
+        assert!(result.best_above(0.8).is_none());
+
+        let result = GatherResult::from_params(&[("Digits", "1"), ("SpeechResult", "")]);
+        assert_eq!(result.digits.as_deref(), Some("1"));
+        assert!(result.alternatives.is_empty());
+    }
+
+    #[test]
+    fn test_queue_parameters() {
+        let params = [
+            ("CallSid", "CA1"),
+            ("QueueSid", "QU1"),
+            ("QueuePosition", "3"),
+            ("CurrentQueueSize", "5"),
+            ("QueueTime", "30"),
+            ("AvgQueueTime", "150"),
+        ];
+        let wait = WaitUrlRequest::from_params(&params).unwrap();
+        assert_eq!(wait.queue_sid, "QU1");
+        assert_eq!((wait.position, wait.queue_size, wait.max_queue_size), (3, 5, 0));
+        assert_eq!(wait.estimated_wait(), Some(120));
+        assert_eq!(WaitUrlRequest::default().estimated_wait(), None);
+        assert!(WaitUrlRequest::from_params(&params[1..]).is_err());
+        assert_eq!(QueueResult::from_params(&[("QueueResult", "queue-full")]), Some(QueueResult::QueueFull));
+    }
+
+    #[test]
+    fn test_conference_status_callback() {
+        let params = [
+            ("ConferenceSid", "CF1"),
+            ("FriendlyName", "support-42"),
+            ("AccountSid", "AC1"),
+            ("StatusCallbackEvent", "participant-mute"),
+            ("SequenceNumber", "4"),
+            ("CallSid", "CA1"),
+            ("ParticipantLabel", "supervisor"),
+            ("Muted", "true"),
+            ("Hold", "False"),
+        ];
+        let callback = ConferenceStatusCallback::from_params(&params).unwrap();
+        assert_eq!(callback.event, ConferenceEvent::ParticipantMute);
+        assert_eq!((callback.muted, callback.hold, callback.coaching), (Some(true), Some(false), None));
+        assert_eq!(callback.sequence_number, Some(4));
+        assert_eq!(callback.participant_label.as_deref(), Some("supervisor"));
+        #[cfg(feature = "voice")]
+        assert_eq!(callback.event.event_set(), crate::twiml::EventSet::MUTE);
+
+        let unknown = [("ConferenceSid", "CF1"), ("FriendlyName", "x"), ("AccountSid", "AC1"), ("StatusCallbackEvent", "moved")];
+        assert!(matches!(ConferenceStatusCallback::from_params(&unknown), Err(TwiMLError::Parse(_))));
+    }
+}
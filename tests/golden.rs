@@ -0,0 +1,231 @@
+//! Documents built with this crate compared against the output of Twilio's helper
+//! libraries for the same flows
+//!
+//! `tests/golden/generate.py` writes twilio-python's documents to `tests/golden/python/`
+//! and `tests/golden/generate.js` writes twilio-node's to `tests/golden/node/`. Snapshots
+//! must be committed as the libraries wrote them, never edited by hand, and every flow is
+//! compared against both libraries. The flow tests are ignored until the generated
+//! snapshots are committed.
+//!
+//! The comparison is semantic: attribute order, the XML declaration and self-closing
+//! tags may differ, but element names, attribute names and values, text and nesting
+//! must match.
+
+#![cfg(all(feature = "messaging", feature = "pay"))]
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use twiml::*;
+
+/// Element of a parsed document, with attributes sorted and text trimmed
+#[derive(Debug, PartialEq, Eq)]
+struct Node {
+    name: String,
+    attributes: BTreeMap<String, String>,
+    text: String,
+    children: Vec<Node>,
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Parse the small subset of XML TwiML documents use: elements, quoted attributes,
+/// text and an optional declaration
+fn parse(xml: &str) -> Node {
+    let mut stack: Vec<Node> = vec![Node {
+        name: String::new(),
+        attributes: BTreeMap::new(),
+        text: String::new(),
+        children: Vec::new(),
+    }];
+    let mut rest = xml.trim();
+    if rest.starts_with("<?xml") {
+        rest = &rest[rest.find("?>").expect("unterminated declaration") + 2..];
+    }
+    while !rest.is_empty() {
+        let Some(tag_start) = rest.find('<') else {
+            stack.last_mut().unwrap().text.push_str(&unescape(rest));
+            break;
+        };
+        stack.last_mut().unwrap().text.push_str(&unescape(&rest[..tag_start]));
+        let tag_end = tag_start + rest[tag_start..].find('>').expect("unterminated tag");
+        let tag = &rest[tag_start + 1..tag_end];
+        rest = &rest[tag_end + 1..];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            let mut node = stack.pop().unwrap();
+            assert_eq!(node.name, name.trim(), "mismatched closing tag");
+            node.text = node.text.trim().to_string();
+            stack.last_mut().unwrap().children.push(node);
+            continue;
+        }
+
+        let self_closing = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/');
+        let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+        let mut node = Node {
+            name: tag[..name_end].to_string(),
+            attributes: BTreeMap::new(),
+            text: String::new(),
+            children: Vec::new(),
+        };
+        let mut attributes = tag[name_end..].trim();
+        while let Some(equals) = attributes.find('=') {
+            let key = attributes[..equals].trim().to_string();
+            let quoted = attributes[equals + 1..].trim_start();
+            let quote = quoted.chars().next().expect("attribute without a value");
+            let value_end = quoted[1..].find(quote).expect("unterminated attribute value") + 1;
+            node.attributes.insert(key, unescape(&quoted[1..value_end]));
+            attributes = quoted[value_end + 1..].trim_start();
+        }
+        if self_closing {
+            stack.last_mut().unwrap().children.push(node);
+        } else {
+            stack.push(node);
+        }
+    }
+    let mut document = stack.pop().unwrap();
+    assert!(stack.is_empty(), "unclosed element");
+    assert_eq!(document.children.len(), 1, "document must have a single root");
+    document.children.remove(0)
+}
+
+/// Helper libraries with a generator in `tests/golden/`
+const LIBRARIES: &[&str] = &["python", "node"];
+
+/// Flows built by the generators and the tests below
+const FLOWS: &[&str] = &["ivr_menu", "voicemail", "forward_call", "conference", "queue", "payment", "sms_reply"];
+
+fn golden_dir() -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+fn assert_golden(name: &str, response: Response) {
+    let actual = response.to_xml_string();
+    for library in LIBRARIES {
+        let path = golden_dir().join(library).join(format!("{}.xml", name));
+        let expected = fs::read_to_string(&path).unwrap_or_else(|err| panic!("cannot read {}: {}", path.display(), err));
+        assert_eq!(parse(&actual), parse(&expected), "{} differs from twilio-{}:\n{}", name, library, actual);
+    }
+    assert!(response.validate().is_ok(), "{} does not validate", name);
+}
+
+#[test]
+#[ignore = "helper-library snapshots not generated yet"]
+fn ivr_menu() {
+    let response = Response::new()
+        .gather(
+            Gather::new()
+                .num_digits(1)
+                .action("/menu")
+                .timeout(5)
+                .say(Say::new("Press 1 for sales or 2 for support.").voice("Polly.Joanna")),
+        )
+        .say("We didn't receive any input. Goodbye!");
+    assert_golden("ivr_menu", response);
+}
+
+#[test]
+#[ignore = "helper-library snapshots not generated yet"]
+fn voicemail() {
+    let response = Response::new()
+        .say("Please leave a message after the beep.")
+        .record(
+            Record::new()
+                .action("/recorded")
+                .max_length(60)
+                .finish_on_key("#")
+                .play_beep(true)
+                .transcribe(true)
+                .recording_status_callback("/recording-status"),
+        )
+        .hangup();
+    assert_golden("voicemail", response);
+}
+
+#[test]
+#[ignore = "helper-library snapshots not generated yet"]
+fn forward_call() {
+    let dial = Dial::new_empty()
+        .caller_id("+15550001111")
+        .timeout(20)
+        .action("/after-dial")
+        .time_limit(3600)
+        .number(Number::new("+15551234567").send_digits("ww1234"));
+    assert_golden("forward_call", Response::new().dial(dial));
+}
+
+#[test]
+#[ignore = "helper-library snapshots not generated yet"]
+fn conference() {
+    let conference = Conference::new("Standup")
+        .start_conference_on_enter(true)
+        .end_conference_on_exit(false)
+        .wait_url("/hold-music")
+        .max_participants(10)
+        .status_callback("/conference-events");
+    assert_golden("conference", Response::new().dial(Dial::new_empty().conference(conference)));
+}
+
+#[test]
+#[ignore = "helper-library snapshots not generated yet"]
+fn queue() {
+    let enqueue = Enqueue::new("support").wait_url("/wait").action("/queue-done");
+    assert_golden("queue", Response::new().enqueue(enqueue));
+}
+
+#[test]
+#[ignore = "helper-library snapshots not generated yet"]
+fn payment() {
+    let pay = Pay::new()
+        .charge_amount("10.00")
+        .payment_connector("Stripe_Connector")
+        .max_attempts(2)
+        .action("/paid")
+        .prompt(
+            Prompt::new_empty()
+                .for_attr(PromptFor::PaymentCardNumber)
+                .attempt(1)
+                .say("Please enter your card number."),
+        );
+    assert_golden("payment", Response::new().pay(pay));
+}
+
+#[test]
+#[ignore = "helper-library snapshots not generated yet"]
+fn sms_reply() {
+    let message = Message::new_empty()
+        .to("+15551234567")
+        .action("/sent")
+        .body(Body::new("Your order shipped & will arrive <Friday>."))
+        .media_url("https://example.com/label.png");
+    assert_golden("sms_reply", Response::new().message(message));
+}
+
+#[test]
+fn snapshots_match_flows() {
+    for library in LIBRARIES {
+        let Ok(entries) = fs::read_dir(golden_dir().join(library)) else {
+            continue;
+        };
+        for entry in entries {
+            let path = entry.unwrap().path();
+            let flow = path.file_stem().unwrap().to_string_lossy();
+            assert!(FLOWS.contains(&flow.as_ref()), "{} has no test", path.display());
+        }
+    }
+}
+
+#[test]
+fn comparison_catches_attribute_typos() {
+    let expected = parse(r#"<Response><Gather numDigits="1" /></Response>"#);
+    assert_ne!(parse(r#"<Response><Gather numdigits="1"></Gather></Response>"#), expected);
+    assert_eq!(parse(r#"<?xml version="1.1"?><Response><Gather numDigits='1'></Gather></Response>"#), expected);
+}
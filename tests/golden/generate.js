@@ -0,0 +1,88 @@
+// Write the expected documents produced by Twilio's Node helper library.
+//
+//     npm install twilio
+//     node tests/golden/generate.js
+//
+// Each flow here must build the same document as the test of the same name in
+// tests/golden.rs and the flow of the same name in tests/golden/generate.py.
+// Output goes to tests/golden/node/ and is committed unedited.
+
+const fs = require("fs");
+const path = require("path");
+const { MessagingResponse, VoiceResponse } = require("twilio").twiml;
+
+const flows = {
+  ivr_menu() {
+    const response = new VoiceResponse();
+    const gather = response.gather({ numDigits: 1, action: "/menu", timeout: 5 });
+    gather.say({ voice: "Polly.Joanna" }, "Press 1 for sales or 2 for support.");
+    response.say("We didn't receive any input. Goodbye!");
+    return response;
+  },
+
+  voicemail() {
+    const response = new VoiceResponse();
+    response.say("Please leave a message after the beep.");
+    response.record({
+      action: "/recorded",
+      maxLength: 60,
+      finishOnKey: "#",
+      playBeep: true,
+      transcribe: true,
+      recordingStatusCallback: "/recording-status",
+    });
+    response.hangup();
+    return response;
+  },
+
+  forward_call() {
+    const response = new VoiceResponse();
+    const dial = response.dial({ callerId: "+15550001111", timeout: 20, action: "/after-dial", timeLimit: 3600 });
+    dial.number({ sendDigits: "ww1234" }, "+15551234567");
+    return response;
+  },
+
+  conference() {
+    const response = new VoiceResponse();
+    const dial = response.dial();
+    dial.conference(
+      {
+        startConferenceOnEnter: true,
+        endConferenceOnExit: false,
+        waitUrl: "/hold-music",
+        maxParticipants: 10,
+        statusCallback: "/conference-events",
+      },
+      "Standup"
+    );
+    return response;
+  },
+
+  queue() {
+    const response = new VoiceResponse();
+    response.enqueue({ waitUrl: "/wait", action: "/queue-done" }, "support");
+    return response;
+  },
+
+  payment() {
+    const response = new VoiceResponse();
+    const pay = response.pay({ chargeAmount: "10.00", paymentConnector: "Stripe_Connector", maxAttempts: 2, action: "/paid" });
+    const prompt = pay.prompt({ for: "payment-card-number", attempt: 1 });
+    prompt.say("Please enter your card number.");
+    return response;
+  },
+
+  sms_reply() {
+    const response = new MessagingResponse();
+    const message = response.message({ to: "+15551234567", action: "/sent" });
+    message.body("Your order shipped & will arrive <Friday>.");
+    message.media("https://example.com/label.png");
+    return response;
+  },
+};
+
+const out = path.join(__dirname, "node");
+fs.mkdirSync(out, { recursive: true });
+for (const [name, flow] of Object.entries(flows)) {
+  fs.writeFileSync(path.join(out, `${name}.xml`), flow().toString() + "\n");
+}
@@ -0,0 +1,91 @@
+"""Write the expected documents produced by Twilio's Python helper library.
+
+    pip install twilio
+    python tests/golden/generate.py
+
+Each flow here must build the same document as the test of the same name in
+tests/golden.rs, and tests/golden/generate.js must build it with twilio-node.
+Output goes to tests/golden/python/ and is committed unedited.
+"""
+
+from pathlib import Path
+
+from twilio.twiml.messaging_response import MessagingResponse
+from twilio.twiml.voice_response import Dial, Gather, Pay, VoiceResponse
+
+
+def ivr_menu():
+    response = VoiceResponse()
+    gather = Gather(num_digits=1, action="/menu", timeout=5)
+    gather.say("Press 1 for sales or 2 for support.", voice="Polly.Joanna")
+    response.append(gather)
+    response.say("We didn't receive any input. Goodbye!")
+    return response
+
+
+def voicemail():
+    response = VoiceResponse()
+    response.say("Please leave a message after the beep.")
+    response.record(
+        action="/recorded",
+        max_length=60,
+        finish_on_key="#",
+        play_beep=True,
+        transcribe=True,
+        recording_status_callback="/recording-status",
+    )
+    response.hangup()
+    return response
+
+
+def forward_call():
+    response = VoiceResponse()
+    dial = Dial(caller_id="+15550001111", timeout=20, action="/after-dial", time_limit=3600)
+    dial.number("+15551234567", send_digits="ww1234")
+    response.append(dial)
+    return response
+
+
+def conference():
+    response = VoiceResponse()
+    dial = Dial()
+    dial.conference(
+        "Standup",
+        start_conference_on_enter=True,
+        end_conference_on_exit=False,
+        wait_url="/hold-music",
+        max_participants=10,
+        status_callback="/conference-events",
+    )
+    response.append(dial)
+    return response
+
+
+def queue():
+    response = VoiceResponse()
+    response.enqueue("support", wait_url="/wait", action="/queue-done")
+    return response
+
+
+def payment():
+    response = VoiceResponse()
+    pay = Pay(charge_amount="10.00", payment_connector="Stripe_Connector", max_attempts=2, action="/paid")
+    prompt = pay.prompt(for_="payment-card-number", attempt=1)
+    prompt.say("Please enter your card number.")
+    response.append(pay)
+    return response
+
+
+def sms_reply():
+    response = MessagingResponse()
+    message = response.message(to="+15551234567", action="/sent")
+    message.body("Your order shipped & will arrive <Friday>.")
+    message.media("https://example.com/label.png")
+    return response
+
+
+if __name__ == "__main__":
+    out = Path(__file__).parent / "python"
+    out.mkdir(exist_ok=True)
+    for flow in (ivr_menu, voicemail, forward_call, conference, queue, payment, sms_reply):
+        (out / f"{flow.__name__}.xml").write_text(str(flow()) + "\n")